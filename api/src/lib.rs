@@ -3,9 +3,11 @@ pub use esc_client_base::identity::TokenConfig;
 pub use esc_client_base::Authorization;
 pub use esc_client_base::Client;
 pub use esc_client_base::Error;
+pub use esc_client_base::Redactor;
 pub use esc_client_base::RequestObserver;
 pub use esc_client_base::RequestSender;
 pub use esc_client_base::Result;
+pub use esc_client_base::SendMode;
 pub use esc_client_base::Token;
 
 pub mod access {
@@ -36,6 +38,44 @@ pub mod resources {
     pub use esc_client_generated::resources::*;
 }
 
+pub mod manifest;
+pub mod teardown;
+pub mod wait;
+
+// JSON fields of the integrate schemas which hold secrets.
+const INTEGRATE_SENSITIVE_FIELDS: &[&str] = &[
+    "accessKeyId",
+    "apiKey",
+    "authToken",
+    "gcpServiceAccountPrivateKey",
+    "secretAccessKey",
+    "token",
+];
+
+// JSON fields of the mesdb schemas which hold secrets, such as the password of
+// shared cluster credentials.
+const MESDB_SENSITIVE_FIELDS: &[&str] = &["password"];
+
+/// Every JSON field which holds a secret, regardless of the schema it's in.
+pub fn sensitive_fields() -> impl Iterator<Item = &'static str> {
+    INTEGRATE_SENSITIVE_FIELDS
+        .iter()
+        .chain(MESDB_SENSITIVE_FIELDS.iter())
+        .copied()
+}
+
+/// A redactor which hides every field listed by `sensitive_fields`.
+///
+/// The fields are matched by name in every request and response rather than
+/// per operation, since the sender only sees bodies, not which operation they
+/// belong to. A field of another schema which happens to share one of these
+/// names, such as a `token`, is redacted as well. That only affects what the
+/// traffic output shows, since the bodies sent and deserialized are
+/// untouched, and the CLI leaves the redactor out with `--show-secrets`.
+pub fn redactor() -> Redactor {
+    Redactor::new(sensitive_fields())
+}
+
 // All of the following using statements are just to keep compatability with the
 // test code. In the future they'll be scrapped.
 pub use access::GroupId;
@@ -0,0 +1,899 @@
+use super::live::LiveState;
+use super::model::*;
+use super::plan::{Action, Change};
+use super::ManifestError;
+use crate::infra::AclId;
+use crate::integrate::{
+    CreateIntegrationData, CreateIntegrationRequest, IntegrationId, UpdateIntegrationData,
+    UpdateIntegrationRequest,
+};
+use crate::mesdb::SharedCluster;
+use crate::orchestrate::{CreateJobRequest, JobData, ScheduledBackupData};
+use crate::wait::{Target, WaitError, WaitOptions, Waitable};
+use crate::{ClusterId, JobId, NetworkId, OrgId, PeeringId, ProjectId};
+
+#[derive(Clone, Debug)]
+pub struct ApplyOptions {
+    /// Deletes and replacements are refused unless this is set.
+    pub allow_delete: bool,
+    /// How long to wait on each resource.
+    pub wait: WaitOptions,
+}
+
+/// Reported as a plan is applied.
+pub enum ApplyEvent<'a> {
+    Started(&'a Change),
+    /// The resource is being waited on. Holds its current status.
+    Waiting(&'a Change, String),
+    Finished(&'a Change),
+}
+
+struct Applier<'a, P> {
+    client: &'a crate::Client,
+    org_id: OrgId,
+    project_id: ProjectId,
+    desired: &'a Manifest,
+    live: LiveState,
+    options: &'a ApplyOptions,
+    on_event: P,
+}
+
+fn missing(kind: Kind, name: &str) -> ManifestError {
+    ManifestError::Invalid(format!("Could not find the id of {} \"{}\"", kind, name))
+}
+
+fn required_secret(
+    integration: &str,
+    field: &str,
+    value: &Option<String>,
+) -> Result<String, ManifestError> {
+    value.clone().ok_or_else(|| {
+        ManifestError::Invalid(format!(
+            "Integration \"{}\" needs {} to be created",
+            integration, field
+        ))
+    })
+}
+
+// Deleted resources may vanish instead of reporting a deleted status.
+fn gone(result: Result<(), WaitError>) -> Result<(), ManifestError> {
+    match result {
+        Err(WaitError::Api(err)) => match err.api_response() {
+            Ok(resp) if resp.status_code.as_u16() == 404 => Ok(()),
+            Ok(resp) => Err(ManifestError::Api(crate::Error::ApiResponse(resp))),
+            Err(err) => Err(ManifestError::Api(err)),
+        },
+        other => Ok(other?),
+    }
+}
+
+impl<'a, P> Applier<'a, P>
+where
+    P: FnMut(ApplyEvent<'_>),
+{
+    fn id(&self, kind: Kind, name: &str) -> Result<String, ManifestError> {
+        self.live
+            .id(kind, name)
+            .map(|id| id.to_string())
+            .ok_or_else(|| missing(kind, name))
+    }
+
+    // Nothing was created or deleted, so there's nothing to wait on.
+    fn dry_run(&self) -> bool {
+        self.client.sender.mode == crate::SendMode::DryRun
+    }
+
+    async fn wait_network(
+        &mut self,
+        change: &Change,
+        id: &str,
+        target: Target,
+    ) -> Result<(), WaitError> {
+        if self.dry_run() {
+            return Ok(());
+        }
+        let on_event = &mut self.on_event;
+        crate::wait::wait_for_network(
+            self.client,
+            self.org_id.clone(),
+            self.project_id.clone(),
+            NetworkId(id.to_string()),
+            &target,
+            &self.options.wait,
+            |n| on_event(ApplyEvent::Waiting(change, n.describe())),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn wait_peering(
+        &mut self,
+        change: &Change,
+        id: &str,
+        target: Target,
+    ) -> Result<(), WaitError> {
+        if self.dry_run() {
+            return Ok(());
+        }
+        let on_event = &mut self.on_event;
+        crate::wait::wait_for_peering(
+            self.client,
+            self.org_id.clone(),
+            self.project_id.clone(),
+            PeeringId(id.to_string()),
+            &target,
+            &self.options.wait,
+            |p| on_event(ApplyEvent::Waiting(change, p.describe())),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn wait_acl(
+        &mut self,
+        change: &Change,
+        id: &str,
+        target: Target,
+    ) -> Result<(), WaitError> {
+        if self.dry_run() {
+            return Ok(());
+        }
+        let on_event = &mut self.on_event;
+        crate::wait::wait_for_acl(
+            self.client,
+            self.org_id.clone(),
+            self.project_id.clone(),
+            AclId(id.to_string()),
+            &target,
+            &self.options.wait,
+            |a| on_event(ApplyEvent::Waiting(change, a.describe())),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn wait_cluster(
+        &mut self,
+        change: &Change,
+        id: &str,
+        target: Target,
+    ) -> Result<(), WaitError> {
+        if self.dry_run() {
+            return Ok(());
+        }
+        let on_event = &mut self.on_event;
+        crate::wait::wait_for_cluster(
+            self.client,
+            self.org_id.clone(),
+            self.project_id.clone(),
+            ClusterId(id.to_string()),
+            &target,
+            &self.options.wait,
+            |c| on_event(ApplyEvent::Waiting(change, c.describe())),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn wait_shared_cluster(
+        &mut self,
+        change: &Change,
+        id: &str,
+        target: Target,
+    ) -> Result<(), WaitError> {
+        if self.dry_run() {
+            return Ok(());
+        }
+        let on_event = &mut self.on_event;
+        crate::wait::wait_for_shared_cluster(
+            self.client,
+            self.org_id.clone(),
+            self.project_id.clone(),
+            ClusterId(id.to_string()),
+            &target,
+            &self.options.wait,
+            |c| on_event(ApplyEvent::Waiting(change, c.describe())),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn delete(&mut self, change: &Change) -> Result<(), ManifestError> {
+        let id = self.id(change.kind, &change.name)?;
+        let org_id = self.org_id.clone();
+        let project_id = self.project_id.clone();
+        let deleted = || Target::statuses(vec!["deleted".to_string()]);
+
+        match change.kind {
+            Kind::Network => {
+                crate::infra::delete_network(
+                    self.client,
+                    org_id,
+                    project_id,
+                    NetworkId(id.clone()),
+                )
+                .await?;
+                gone(self.wait_network(change, &id, deleted()).await)?;
+            }
+            Kind::Peering => {
+                crate::infra::delete_peering(
+                    self.client,
+                    org_id,
+                    project_id,
+                    PeeringId(id.clone()),
+                )
+                .await?;
+                gone(self.wait_peering(change, &id, deleted()).await)?;
+            }
+            Kind::Acl => {
+                crate::infra::delete_acl(self.client, org_id, project_id, AclId(id.clone()))
+                    .await?;
+                gone(self.wait_acl(change, &id, deleted()).await)?;
+            }
+            Kind::Cluster => {
+                crate::mesdb::delete_cluster(
+                    self.client,
+                    org_id,
+                    project_id,
+                    ClusterId(id.clone()),
+                )
+                .await?;
+                gone(self.wait_cluster(change, &id, deleted()).await)?;
+            }
+            Kind::SharedCluster => {
+                crate::mesdb::delete_shared_cluster(
+                    self.client,
+                    org_id,
+                    project_id,
+                    ClusterId(id.clone()),
+                )
+                .await?;
+                gone(self.wait_shared_cluster(change, &id, deleted()).await)?;
+            }
+            Kind::Job => {
+                crate::orchestrate::delete_job(self.client, org_id, project_id, JobId(id)).await?;
+            }
+            Kind::Integration => {
+                crate::integrate::delete_integration(
+                    self.client,
+                    org_id,
+                    project_id,
+                    IntegrationId(id),
+                )
+                .await?;
+            }
+        }
+
+        self.live.remove_id(change.kind, &change.name);
+        Ok(())
+    }
+
+    fn cluster_ids(
+        &self,
+        names: &Option<Vec<String>>,
+    ) -> Result<Option<Vec<String>>, ManifestError> {
+        match names {
+            None => Ok(None),
+            Some(names) => names
+                .iter()
+                .map(|name| self.id(Kind::Cluster, name))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Some),
+        }
+    }
+
+    fn integration_data(
+        &self,
+        spec: &IntegrationSpec,
+    ) -> Result<CreateIntegrationData, ManifestError> {
+        use crate::integrate::*;
+
+        let name = &spec.name;
+        let data = match &spec.sink {
+            IntegrationSink::OpsGenie { source, api_key } => {
+                CreateIntegrationData::OpsGenie(CreateOpsGenieIntegrationData {
+                    api_key: required_secret(name, "api_key", api_key)?,
+                    source: source.clone(),
+                })
+            }
+            IntegrationSink::Slack {
+                source,
+                channel_id,
+                token,
+            } => CreateIntegrationData::Slack(CreateSlackIntegrationData {
+                channel_id: channel_id.clone(),
+                token: required_secret(name, "token", token)?,
+                source: source.clone(),
+            }),
+            IntegrationSink::PagerDuty {
+                source,
+                service,
+                user,
+                auth_token,
+            } => CreateIntegrationData::PagerDuty(CreatePagerDutyIntegrationData {
+                auth_token: required_secret(name, "auth_token", auth_token)?,
+                service: service.clone(),
+                user: user.clone(),
+                source: source.clone(),
+            }),
+            IntegrationSink::AwsCloudWatchLogs {
+                source,
+                group_name,
+                region,
+                clusters,
+                access_key_id,
+                secret_access_key,
+            } => CreateIntegrationData::AwsCloudWatchLogs(CreateAwsCloudWatchLogsIntegrationData {
+                access_key_id: required_secret(name, "access_key_id", access_key_id)?,
+                cluster_ids: self.cluster_ids(clusters)?,
+                group_name: group_name.clone(),
+                region: region.clone(),
+                secret_access_key: required_secret(name, "secret_access_key", secret_access_key)?,
+                source: required_secret(name, "source", source)?,
+            }),
+            IntegrationSink::AwsCloudWatchMetrics {
+                source,
+                namespace,
+                region,
+                clusters,
+                access_key_id,
+                secret_access_key,
+            } => CreateIntegrationData::AwsCloudWatchMetrics(
+                CreateAwsCloudWatchMetricsIntegrationData {
+                    access_key_id: required_secret(name, "access_key_id", access_key_id)?,
+                    cluster_ids: self.cluster_ids(clusters)?,
+                    namespace: namespace.clone(),
+                    region: region.clone(),
+                    secret_access_key: required_secret(
+                        name,
+                        "secret_access_key",
+                        secret_access_key,
+                    )?,
+                    source: required_secret(name, "source", source)?,
+                },
+            ),
+            IntegrationSink::GcpLogging {
+                source,
+                gcp_project_id,
+                log_id,
+                gcp_service_account_private_key,
+            } => CreateIntegrationData::GcpLogging(CreateGcpLoggingIntegrationData {
+                gcp_project_id: gcp_project_id.clone(),
+                gcp_service_account_private_key: required_secret(
+                    name,
+                    "gcp_service_account_private_key",
+                    gcp_service_account_private_key,
+                )?,
+                log_id: log_id.clone(),
+                source: source.clone(),
+            }),
+        };
+        Ok(data)
+    }
+
+    // Secrets left out of the manifest are left as they are.
+    fn integration_update(
+        &self,
+        spec: &IntegrationSpec,
+    ) -> Result<UpdateIntegrationData, ManifestError> {
+        use crate::integrate::*;
+
+        let data = match &spec.sink {
+            IntegrationSink::OpsGenie { api_key, .. } => {
+                UpdateIntegrationData::UpdateOpsGenieIntegrationData(
+                    UpdateOpsGenieIntegrationData {
+                        api_key: api_key.clone(),
+                    },
+                )
+            }
+            IntegrationSink::Slack {
+                channel_id, token, ..
+            } => UpdateIntegrationData::UpdateSlackIntegrationData(UpdateSlackIntegrationData {
+                channel_id: Some(channel_id.clone()),
+                token: token.clone(),
+            }),
+            IntegrationSink::PagerDuty {
+                service,
+                user,
+                auth_token,
+                ..
+            } => UpdateIntegrationData::UpdatePagerDutyIntegrationData(
+                UpdatePagerDutyIntegrationData {
+                    auth_token: auth_token.clone(),
+                    service: Some(service.clone()),
+                    user: Some(user.clone()),
+                },
+            ),
+            IntegrationSink::AwsCloudWatchLogs {
+                group_name,
+                region,
+                clusters,
+                access_key_id,
+                secret_access_key,
+                ..
+            } => UpdateIntegrationData::UpdateAwsCloudWatchLogsIntegrationData(
+                UpdateAwsCloudWatchLogsIntegrationData {
+                    access_key_id: access_key_id.clone(),
+                    cluster_ids: self.cluster_ids(clusters)?,
+                    group_name: Some(group_name.clone()),
+                    region: Some(region.clone()),
+                    secret_access_key: secret_access_key.clone(),
+                },
+            ),
+            IntegrationSink::AwsCloudWatchMetrics {
+                namespace,
+                region,
+                clusters,
+                access_key_id,
+                secret_access_key,
+                ..
+            } => UpdateIntegrationData::UpdateAwsCloudWatchMetricsIntegrationData(
+                UpdateAwsCloudWatchMetricsIntegrationData {
+                    access_key_id: access_key_id.clone(),
+                    cluster_ids: self.cluster_ids(clusters)?,
+                    namespace: Some(namespace.clone()),
+                    region: Some(region.clone()),
+                    secret_access_key: secret_access_key.clone(),
+                },
+            ),
+            IntegrationSink::GcpLogging {
+                gcp_project_id,
+                log_id,
+                gcp_service_account_private_key,
+                ..
+            } => UpdateIntegrationData::UpdateGcpLoggingIntegrationData(
+                UpdateGcpLoggingIntegrationData {
+                    gcp_project_id: Some(gcp_project_id.clone()),
+                    gcp_service_account_private_key: gcp_service_account_private_key.clone(),
+                    log_id: Some(log_id.clone()),
+                },
+            ),
+        };
+        Ok(data)
+    }
+
+    async fn create(&mut self, change: &Change) -> Result<(), ManifestError> {
+        let desired = self.desired;
+        let name = change.name.as_str();
+        let org_id = self.org_id.clone();
+        let project_id = self.project_id.clone();
+        let spec_missing = || missing(change.kind, name);
+
+        let id = match change.kind {
+            Kind::Network => {
+                let spec = desired
+                    .networks
+                    .iter()
+                    .find(|n| n.name == name)
+                    .ok_or_else(spec_missing)?;
+                let id = crate::infra::create_network(
+                    self.client,
+                    org_id,
+                    project_id,
+                    crate::infra::CreateNetworkRequest {
+                        cidr_block: spec.cidr_block.clone(),
+                        description: spec.name.clone(),
+                        provider: spec.provider.clone(),
+                        public_access: spec.public_access.unwrap_or(false),
+                        region: spec.region.clone(),
+                    },
+                )
+                .await?
+                .id
+                .0;
+                self.wait_network(change, &id, Target::ready::<crate::infra::Network>())
+                    .await?;
+                id
+            }
+            Kind::Peering => {
+                let spec = desired
+                    .peerings
+                    .iter()
+                    .find(|p| p.name == name)
+                    .ok_or_else(spec_missing)?;
+                let network_id = NetworkId(self.id(Kind::Network, &spec.network)?);
+                let id = crate::infra::create_peering(
+                    self.client,
+                    org_id,
+                    project_id,
+                    crate::infra::CreatePeeringRequest {
+                        network_id,
+                        description: spec.name.clone(),
+                        peer_account_id: spec.peer_account_id.clone(),
+                        peer_network_id: spec.peer_network_id.clone(),
+                        peer_network_region: spec.peer_network_region.clone(),
+                        routes: spec.routes.clone(),
+                    },
+                )
+                .await?
+                .id
+                .0;
+                self.wait_peering(change, &id, Target::ready::<crate::infra::Peering>())
+                    .await?;
+                id
+            }
+            Kind::Acl => {
+                let spec = desired
+                    .acls
+                    .iter()
+                    .find(|a| a.name == name)
+                    .ok_or_else(spec_missing)?;
+                let id = crate::infra::create_acl(
+                    self.client,
+                    org_id,
+                    project_id,
+                    crate::infra::CreateAclRequest {
+                        cidr_blocks: spec.cidr_blocks.clone(),
+                        description: spec.name.clone(),
+                    },
+                )
+                .await?
+                .id
+                .0;
+                self.wait_acl(change, &id, Target::ready::<crate::infra::Acl>())
+                    .await?;
+                id
+            }
+            Kind::Cluster => {
+                let spec = desired
+                    .clusters
+                    .iter()
+                    .find(|c| c.name == name)
+                    .ok_or_else(spec_missing)?;
+                let network_id = NetworkId(self.id(Kind::Network, &spec.network)?);
+                let acl_id = match spec.acl.as_ref() {
+                    Some(acl) => Some(self.id(Kind::Acl, acl)?),
+                    None => None,
+                };
+                let id = crate::mesdb::create_cluster(
+                    self.client,
+                    org_id,
+                    project_id,
+                    crate::mesdb::CreateClusterRequest {
+                        acl_id,
+                        description: spec.name.clone(),
+                        disk_iops: spec.disk_iops,
+                        disk_size_gb: spec.disk_size_gb,
+                        disk_throughput: spec.disk_throughput,
+                        disk_type: spec.disk_type.clone(),
+                        instance_type: spec.instance_type.clone(),
+                        network_id,
+                        projection_level: spec.projection_level.clone(),
+                        server_version: spec.server_version.clone(),
+                        source_backup_id: None,
+                        source_backup_project_id: None,
+                        source_node_index: None,
+                        topology: spec.topology.clone(),
+                        protected: spec.protected,
+                        public_access: spec.public_access,
+                        provider: None,
+                        region: None,
+                    },
+                )
+                .await?
+                .id
+                .0;
+                self.wait_cluster(change, &id, Target::healthy_cluster())
+                    .await?;
+                id
+            }
+            Kind::SharedCluster => {
+                let spec = desired
+                    .shared_clusters
+                    .iter()
+                    .find(|c| c.name == name)
+                    .ok_or_else(spec_missing)?;
+                let acl_id = self.id(Kind::Acl, &spec.acl)?;
+                let id = crate::mesdb::create_shared_cluster(
+                    self.client,
+                    org_id,
+                    project_id,
+                    crate::mesdb::CreateSharedClusterDeploymentRequest {
+                        cluster: crate::mesdb::CreateSharedClusterRequest {
+                            name: spec.name.clone(),
+                            provider: spec.provider.clone(),
+                            region: spec.region.clone(),
+                            deployment_tier: spec.deployment_tier.clone(),
+                            mutual_tls_enabled: spec.mutual_tls_enabled.unwrap_or(false),
+                            projection_level: spec.projection_level.clone(),
+                            server_version: spec.server_version.clone(),
+                            topology: spec.topology.clone(),
+                        },
+                        acl: crate::mesdb::Acl::ResourceIdentifier(
+                            crate::mesdb::ResourceIdentifier { id: acl_id },
+                        ),
+                    },
+                )
+                .await?
+                .id
+                .0;
+                self.wait_shared_cluster(change, &id, Target::ready::<SharedCluster>())
+                    .await?;
+                id
+            }
+            Kind::Job => {
+                let spec = desired
+                    .jobs
+                    .iter()
+                    .find(|j| j.name == name)
+                    .ok_or_else(spec_missing)?;
+                let cluster_id = ClusterId(self.id(Kind::Cluster, &spec.cluster)?);
+                crate::orchestrate::create_job(
+                    self.client,
+                    org_id,
+                    project_id,
+                    CreateJobRequest {
+                        data: JobData::ScheduledBackup(ScheduledBackupData {
+                            cluster_id,
+                            description: spec
+                                .backup_description
+                                .clone()
+                                .unwrap_or_else(|| spec.name.clone()),
+                            max_backup_count: spec.max_backup_count,
+                        }),
+                        description: spec.name.clone(),
+                        schedule: spec.schedule.clone(),
+                    },
+                )
+                .await?
+                .id
+                .0
+            }
+            Kind::Integration => {
+                let spec = desired
+                    .integrations
+                    .iter()
+                    .find(|i| i.name == name)
+                    .ok_or_else(spec_missing)?;
+                let data = self.integration_data(spec)?;
+                crate::integrate::create_integration(
+                    self.client,
+                    org_id,
+                    project_id,
+                    CreateIntegrationRequest {
+                        data,
+                        description: spec.name.clone(),
+                    },
+                )
+                .await?
+                .id
+                .0
+            }
+        };
+
+        self.live.insert_id(change.kind, name, id);
+        Ok(())
+    }
+
+    async fn update(&mut self, change: &Change) -> Result<(), ManifestError> {
+        let desired = self.desired;
+        let name = change.name.as_str();
+        let id = self.id(change.kind, name)?;
+        let org_id = self.org_id.clone();
+        let project_id = self.project_id.clone();
+        let changed = |field: &str| change.fields.iter().any(|f| f.field == field);
+
+        match change.kind {
+            Kind::Acl => {
+                let spec = desired
+                    .acls
+                    .iter()
+                    .find(|a| a.name == name)
+                    .ok_or_else(|| missing(Kind::Acl, name))?;
+                crate::infra::update_acl(
+                    self.client,
+                    org_id,
+                    project_id,
+                    AclId(id.clone()),
+                    crate::infra::UpdateAclRequest {
+                        cidr_blocks: Some(spec.cidr_blocks.clone()),
+                        description: None,
+                    },
+                )
+                .await?;
+                self.wait_acl(change, &id, Target::ready::<crate::infra::Acl>())
+                    .await?;
+            }
+            Kind::Cluster => {
+                let spec = desired
+                    .clusters
+                    .iter()
+                    .find(|c| c.name == name)
+                    .ok_or_else(|| missing(Kind::Cluster, name))?;
+                let cluster_id = ClusterId(id.clone());
+
+                if changed("acl") || changed("protected") {
+                    let acl_id = match spec.acl.as_ref() {
+                        Some(acl) => Some(self.id(Kind::Acl, acl)?),
+                        None => None,
+                    };
+                    crate::mesdb::update_cluster(
+                        self.client,
+                        org_id.clone(),
+                        project_id.clone(),
+                        cluster_id.clone(),
+                        crate::mesdb::UpdateClusterRequest {
+                            acl_id,
+                            description: None,
+                            protected: spec.protected,
+                        },
+                    )
+                    .await?;
+                }
+
+                if ["disk_size_gb", "disk_type", "disk_iops", "disk_throughput"]
+                    .iter()
+                    .any(|f| changed(f))
+                {
+                    crate::mesdb::expand_cluster_disk(
+                        self.client,
+                        org_id.clone(),
+                        project_id.clone(),
+                        cluster_id.clone(),
+                        crate::mesdb::ExpandClusterDiskRequest {
+                            disk_size_gb: spec.disk_size_gb,
+                            disk_iops: spec.disk_iops,
+                            disk_throughput: spec.disk_throughput,
+                            disk_type: Some(spec.disk_type.clone()),
+                        },
+                    )
+                    .await?;
+                    self.wait_cluster(change, &id, Target::healthy_cluster().after_change())
+                        .await?;
+                }
+
+                if changed("instance_type") {
+                    crate::mesdb::resize_cluster(
+                        self.client,
+                        org_id.clone(),
+                        project_id.clone(),
+                        cluster_id.clone(),
+                        crate::mesdb::ResizeClusterRequest {
+                            target_size: spec.instance_type.clone(),
+                        },
+                    )
+                    .await?;
+                    self.wait_cluster(change, &id, Target::healthy_cluster().after_change())
+                        .await?;
+                }
+
+                if changed("server_version") {
+                    crate::mesdb::upgrade_cluster(
+                        self.client,
+                        org_id,
+                        project_id,
+                        cluster_id,
+                        crate::mesdb::UpgradeClusterRequest {
+                            target_tag: spec.server_version.clone(),
+                        },
+                    )
+                    .await?;
+                    self.wait_cluster(change, &id, Target::healthy_cluster().after_change())
+                        .await?;
+                }
+            }
+            Kind::SharedCluster => {
+                let spec = desired
+                    .shared_clusters
+                    .iter()
+                    .find(|c| c.name == name)
+                    .ok_or_else(|| missing(Kind::SharedCluster, name))?;
+                let acl_id = self.id(Kind::Acl, &spec.acl)?;
+                crate::mesdb::update_shared_cluster(
+                    self.client,
+                    org_id,
+                    project_id,
+                    ClusterId(id.clone()),
+                    crate::mesdb::UpdateSharedClusterRequest {
+                        acl_id: Some(acl_id),
+                        mutual_tls_enabled: spec.mutual_tls_enabled,
+                    },
+                )
+                .await?;
+                self.wait_shared_cluster(change, &id, Target::ready::<SharedCluster>())
+                    .await?;
+            }
+            Kind::Integration => {
+                let spec = desired
+                    .integrations
+                    .iter()
+                    .find(|i| i.name == name)
+                    .ok_or_else(|| missing(Kind::Integration, name))?;
+                let data = self.integration_update(spec)?;
+                crate::integrate::update_integration(
+                    self.client,
+                    org_id,
+                    project_id,
+                    IntegrationId(id),
+                    UpdateIntegrationRequest {
+                        data: Some(data),
+                        description: None,
+                    },
+                )
+                .await?;
+            }
+            Kind::Network | Kind::Peering | Kind::Job => {
+                return Err(ManifestError::Invalid(format!(
+                    "{} \"{}\" can't be updated in place",
+                    change.kind, name
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Carries out the plan which turns `live` into `desired`, as reported by
+/// `plan`. Resources are deleted first, dependents before what they depend
+/// on, then created and updated in dependency order: networks, peerings and
+/// ACLs, clusters and shared clusters, jobs and finally integrations. Each
+/// resource is waited on before moving to the next one.
+pub async fn apply<P>(
+    client: &crate::Client,
+    org_id: &OrgId,
+    project_id: &ProjectId,
+    desired: &Manifest,
+    live: LiveState,
+    options: &ApplyOptions,
+    on_event: P,
+) -> Result<(), ManifestError>
+where
+    P: FnMut(ApplyEvent<'_>),
+{
+    let plan = super::plan::plan(desired, &live);
+    let destructive = plan.destructive();
+    if !destructive.is_empty() && !options.allow_delete {
+        let names = destructive
+            .iter()
+            .map(|c| format!("{} \"{}\"", c.kind, c.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(ManifestError::Invalid(format!(
+            "The plan deletes or replaces {}, which isn't allowed",
+            names
+        )));
+    }
+
+    let mut applier = Applier {
+        client,
+        org_id: org_id.clone(),
+        project_id: project_id.clone(),
+        desired,
+        live,
+        options,
+        on_event,
+    };
+
+    for kind in Kind::ALL.iter().rev() {
+        for change in plan.changes.iter().filter(|c| c.kind == *kind) {
+            if change.action.is_destructive() {
+                (applier.on_event)(ApplyEvent::Started(change));
+                applier.delete(change).await?;
+                if change.action == Action::Delete {
+                    (applier.on_event)(ApplyEvent::Finished(change));
+                }
+            }
+        }
+    }
+
+    for kind in Kind::ALL.iter() {
+        for change in plan.changes.iter().filter(|c| c.kind == *kind) {
+            match change.action {
+                Action::Create => {
+                    (applier.on_event)(ApplyEvent::Started(change));
+                    applier.create(change).await?;
+                }
+                Action::Replace => applier.create(change).await?,
+                Action::Update => {
+                    (applier.on_event)(ApplyEvent::Started(change));
+                    applier.update(change).await?;
+                }
+                Action::Delete => continue,
+            }
+            (applier.on_event)(ApplyEvent::Finished(change));
+        }
+    }
+
+    Ok(())
+}
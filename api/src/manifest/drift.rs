@@ -0,0 +1,96 @@
+use super::model::*;
+use super::plan::{diff, FieldChange};
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DriftStatus {
+    /// The resource exists, but isn't in the baseline.
+    Added,
+    /// The resource is in the baseline, but doesn't exist anymore.
+    Removed,
+    Changed,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ResourceDrift {
+    pub kind: Kind,
+    pub name: String,
+    pub status: DriftStatus,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldChange>,
+}
+
+/// How a project moved away from a baseline.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Drift {
+    pub resources: Vec<ResourceDrift>,
+}
+
+impl Drift {
+    pub fn is_empty(&self) -> bool {
+        self.resources.is_empty()
+    }
+
+    pub fn count(&self, status: DriftStatus) -> usize {
+        self.resources.iter().filter(|r| r.status == status).count()
+    }
+}
+
+fn without_secrets(manifest: &Manifest) -> Manifest {
+    let mut manifest = manifest.clone();
+    for integration in manifest.integrations.iter_mut() {
+        integration.sink = integration.sink.without_secrets();
+    }
+    manifest
+}
+
+/// Compares a baseline, usually an earlier export, with the current state of
+/// a project. Unlike a plan, fields the baseline leaves out count as changes
+/// when the project sets them. Integration secrets are ignored.
+pub fn drift(baseline: &Manifest, current: &Manifest) -> Drift {
+    let baseline = without_secrets(baseline);
+    let current = without_secrets(current);
+    let mut resources = Vec::new();
+
+    for kind in Kind::ALL.iter().copied() {
+        for name in baseline.names(kind) {
+            let old = baseline.get(kind, name).unwrap_or_default();
+            let drift = match current.get(kind, name) {
+                None => ResourceDrift {
+                    kind,
+                    name: name.to_string(),
+                    status: DriftStatus::Removed,
+                    fields: vec![],
+                },
+                Some(new) => {
+                    let fields = diff(&old, &new, false);
+                    if fields.is_empty() {
+                        continue;
+                    }
+                    ResourceDrift {
+                        kind,
+                        name: name.to_string(),
+                        status: DriftStatus::Changed,
+                        fields,
+                    }
+                }
+            };
+            resources.push(drift);
+        }
+
+        let known = baseline.names(kind);
+        for name in current.names(kind) {
+            if !known.contains(&name) {
+                resources.push(ResourceDrift {
+                    kind,
+                    name: name.to_string(),
+                    status: DriftStatus::Added,
+                    fields: vec![],
+                });
+            }
+        }
+    }
+
+    Drift { resources }
+}
@@ -0,0 +1,259 @@
+use super::model::*;
+use super::ManifestError;
+use crate::infra::{AclStatus, NetworkStatus, PeeringStatus};
+use crate::integrate::{IntegrationData, IntegrationStatus};
+use crate::mesdb::ClusterStatus;
+use crate::orchestrate::JobData;
+use crate::{OrgId, ProjectId};
+use std::collections::{BTreeMap, HashMap};
+
+/// The resources currently in a project, as a manifest, along with the ids
+/// of the resources it names.
+#[derive(Clone, Debug, Default)]
+pub struct LiveState {
+    pub manifest: Manifest,
+    ids: BTreeMap<(Kind, String), String>,
+}
+
+impl LiveState {
+    /// The id of the resource with the given kind and name.
+    pub fn id(&self, kind: Kind, name: &str) -> Option<&str> {
+        self.ids
+            .get(&(kind, name.to_string()))
+            .map(|id| id.as_str())
+    }
+
+    pub fn insert_id(&mut self, kind: Kind, name: &str, id: String) {
+        self.ids.insert((kind, name.to_string()), id);
+    }
+
+    pub fn remove_id(&mut self, kind: Kind, name: &str) {
+        self.ids.remove(&(kind, name.to_string()));
+    }
+}
+
+// Builds the reverse lookups and rejects resources sharing a name, since
+// manifests couldn't tell them apart.
+#[derive(Default)]
+struct Names {
+    by_id: HashMap<(Kind, String), String>,
+    state: LiveState,
+}
+
+impl Names {
+    fn add(&mut self, kind: Kind, id: &str, name: &str) -> Result<(), ManifestError> {
+        if let Some(other) = self.state.id(kind, name) {
+            return Err(ManifestError::Invalid(format!(
+                "The {}s {} and {} are both named \"{}\". Give them distinct descriptions first",
+                kind, other, id, name
+            )));
+        }
+        self.state.insert_id(kind, name, id.to_string());
+        self.by_id.insert((kind, id.to_string()), name.to_string());
+        Ok(())
+    }
+
+    // Falls back to the id for resources outside of the project.
+    fn name(&self, kind: Kind, id: &str) -> String {
+        self.by_id
+            .get(&(kind, id.to_string()))
+            .cloned()
+            .unwrap_or_else(|| id.to_string())
+    }
+}
+
+/// Lists every resource a manifest can describe. Deleted resources are left
+/// out.
+pub async fn fetch(
+    client: &crate::Client,
+    org_id: &OrgId,
+    project_id: &ProjectId,
+) -> Result<LiveState, ManifestError> {
+    let mut names = Names::default();
+    let mut manifest = Manifest::default();
+
+    let networks = crate::infra::list_networks(client, org_id.clone(), project_id.clone())
+        .await?
+        .networks;
+    for network in networks
+        .into_iter()
+        .filter(|n| n.status != NetworkStatus::Deleted)
+    {
+        names.add(Kind::Network, &network.id.0, &network.description)?;
+        manifest.networks.push(NetworkSpec {
+            name: network.description,
+            provider: network.provider,
+            region: network.region,
+            cidr_block: network.cidr_block,
+            public_access: Some(network.public_access),
+        });
+    }
+
+    let peerings = crate::infra::list_peerings(client, org_id.clone(), project_id.clone())
+        .await?
+        .peerings;
+    for peering in peerings
+        .into_iter()
+        .filter(|p| p.status != PeeringStatus::Deleted)
+    {
+        names.add(Kind::Peering, &peering.id.0, &peering.description)?;
+        manifest.peerings.push(PeeringSpec {
+            name: peering.description,
+            network: names.name(Kind::Network, &peering.network_id.0),
+            peer_account_id: peering.peer_account_id,
+            peer_network_id: peering.peer_network_id,
+            peer_network_region: peering.peer_network_region,
+            routes: peering.routes,
+        });
+    }
+
+    let acls = crate::infra::list_acls(client, org_id.clone(), project_id.clone())
+        .await?
+        .acls;
+    for acl in acls.into_iter().filter(|a| a.status != AclStatus::Deleted) {
+        names.add(Kind::Acl, &acl.id.0, &acl.description)?;
+        manifest.acls.push(AclSpec {
+            name: acl.description,
+            cidr_blocks: acl.cidr_blocks,
+        });
+    }
+
+    let clusters = crate::mesdb::list_clusters(client, org_id.clone(), project_id.clone())
+        .await?
+        .clusters;
+    for cluster in clusters
+        .into_iter()
+        .filter(|c| c.status != ClusterStatus::Deleted)
+    {
+        names.add(Kind::Cluster, &cluster.id.0, &cluster.description)?;
+        manifest.clusters.push(ClusterSpec {
+            name: cluster.description,
+            network: names.name(Kind::Network, &cluster.network_id.0),
+            acl: cluster
+                .acl_id
+                .filter(|id| !id.is_empty())
+                .map(|id| names.name(Kind::Acl, &id)),
+            instance_type: cluster.instance_type,
+            disk_size_gb: cluster.disk_size_gb,
+            disk_type: cluster.disk_type,
+            disk_iops: cluster.disk_iops,
+            disk_throughput: cluster.disk_throughput,
+            server_version: cluster.server_version_tag,
+            topology: cluster.topology,
+            projection_level: cluster.projection_level,
+            protected: Some(cluster.protected),
+            public_access: Some(cluster.public_access),
+        });
+    }
+
+    let shared_clusters =
+        crate::mesdb::list_shared_clusters(client, org_id.clone(), project_id.clone())
+            .await?
+            .clusters;
+    for cluster in shared_clusters
+        .into_iter()
+        .filter(|c| c.status != ClusterStatus::Deleted)
+    {
+        names.add(Kind::SharedCluster, &cluster.id.0, &cluster.name)?;
+        manifest.shared_clusters.push(SharedClusterSpec {
+            acl: names.name(Kind::Acl, &cluster.acl_id),
+            name: cluster.name,
+            provider: cluster.provider,
+            region: cluster.region,
+            deployment_tier: cluster.deployment_tier,
+            server_version: cluster.server_version_tag,
+            topology: cluster.topology,
+            projection_level: cluster.projection_level,
+            mutual_tls_enabled: Some(cluster.mutual_tls_enabled),
+        });
+    }
+
+    let jobs = crate::orchestrate::list_jobs(client, org_id.clone(), project_id.clone())
+        .await?
+        .jobs;
+    for job in jobs
+        .into_iter()
+        .filter(|j| !j.status.eq_ignore_ascii_case("deleted"))
+    {
+        names.add(Kind::Job, &job.id.0, &job.description)?;
+        let name = job.description;
+        let JobData::ScheduledBackup(data) = job.data;
+        let backup_description = Some(data.description).filter(|d| *d != name);
+        let cluster = names.name(Kind::Cluster, &data.cluster_id.0);
+        manifest.jobs.push(JobSpec {
+            name,
+            schedule: job.schedule,
+            cluster,
+            backup_description,
+            max_backup_count: data.max_backup_count,
+        });
+    }
+
+    let integrations =
+        crate::integrate::list_integrations(client, org_id.clone(), project_id.clone())
+            .await?
+            .integrations;
+    for integration in integrations
+        .into_iter()
+        .filter(|i| i.status != IntegrationStatus::Deleted)
+    {
+        names.add(
+            Kind::Integration,
+            &integration.id.0,
+            &integration.description,
+        )?;
+        let cluster_names = |ids: Vec<String>| -> Vec<String> {
+            ids.iter().map(|id| names.name(Kind::Cluster, id)).collect()
+        };
+        let sink = match integration.data {
+            IntegrationData::OpsGenie(data) => IntegrationSink::OpsGenie {
+                source: Some(data.source),
+                api_key: None,
+            },
+            IntegrationData::Slack(data) => IntegrationSink::Slack {
+                source: Some(data.source),
+                channel_id: data.channel_id,
+                token: None,
+            },
+            IntegrationData::PagerDuty(data) => IntegrationSink::PagerDuty {
+                source: Some(data.source),
+                service: data.service,
+                user: data.user,
+                auth_token: None,
+            },
+            IntegrationData::AwsCloudWatchLogs(data) => IntegrationSink::AwsCloudWatchLogs {
+                source: Some(data.source),
+                group_name: data.group_name,
+                region: data.region,
+                clusters: data.cluster_ids.map(cluster_names),
+                access_key_id: None,
+                secret_access_key: None,
+            },
+            IntegrationData::AwsCloudWatchMetrics(data) => IntegrationSink::AwsCloudWatchMetrics {
+                source: Some(data.source),
+                namespace: data.namespace,
+                region: data.region,
+                clusters: Some(data.cluster_ids)
+                    .filter(|ids| !ids.is_empty())
+                    .map(cluster_names),
+                access_key_id: None,
+                secret_access_key: None,
+            },
+            IntegrationData::GcpLogging(data) => IntegrationSink::GcpLogging {
+                source: data.source,
+                gcp_project_id: data.gcp_project_id,
+                log_id: data.log_id,
+                gcp_service_account_private_key: None,
+            },
+        };
+        manifest.integrations.push(IntegrationSpec {
+            name: integration.description,
+            sink,
+        });
+    }
+
+    manifest.sort();
+    let mut state = names.state;
+    state.manifest = manifest;
+    Ok(state)
+}
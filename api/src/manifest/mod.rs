@@ -0,0 +1,67 @@
+//! Declarative descriptions of a project's resources. A `Manifest` lists the
+//! desired networks, peerings, ACLs, clusters, shared clusters, jobs and
+//! integrations. `plan` compares it with the live state returned by `fetch`,
+//! and `apply` carries out the resulting changes, while `drift` reports how a
+//! project moved away from an earlier version of it.
+mod apply;
+mod drift;
+mod live;
+mod model;
+mod plan;
+
+pub use apply::{apply, ApplyEvent, ApplyOptions};
+pub use drift::{drift, Drift, DriftStatus, ResourceDrift};
+pub use live::{fetch, LiveState};
+pub use model::*;
+pub use plan::{diff, plan, Action, Change, FieldChange, Plan};
+
+use crate::wait::WaitError;
+
+pub enum ManifestError {
+    /// The manifest or the live state can't be worked with.
+    Invalid(String),
+    Api(crate::Error),
+    Wait(WaitError),
+}
+
+impl std::fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ManifestError::Invalid(message) => write!(f, "{}", message),
+            ManifestError::Api(err) => write!(f, "{}", err),
+            ManifestError::Wait(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::fmt::Debug for ManifestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ManifestError::Api(err) => write!(f, "{:?}", err),
+            ManifestError::Wait(err) => write!(f, "{:?}", err),
+            _ => write!(f, "{}", self),
+        }
+    }
+}
+
+impl std::error::Error for ManifestError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ManifestError::Api(err) => Some(err),
+            ManifestError::Wait(err) => Some(err),
+            ManifestError::Invalid(_) => None,
+        }
+    }
+}
+
+impl From<crate::Error> for ManifestError {
+    fn from(err: crate::Error) -> Self {
+        ManifestError::Api(err)
+    }
+}
+
+impl From<WaitError> for ManifestError {
+    fn from(err: WaitError) -> Self {
+        ManifestError::Wait(err)
+    }
+}
@@ -0,0 +1,466 @@
+use crate::infra::AclCidrBlock;
+use crate::mesdb::{ProjectionLevel, Topology};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// The kinds of resources a manifest describes, in the order they have to be
+/// created in.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Kind {
+    Network,
+    Peering,
+    Acl,
+    Cluster,
+    SharedCluster,
+    Job,
+    Integration,
+}
+
+impl Kind {
+    pub const ALL: &'static [Kind] = &[
+        Kind::Network,
+        Kind::Peering,
+        Kind::Acl,
+        Kind::Cluster,
+        Kind::SharedCluster,
+        Kind::Job,
+        Kind::Integration,
+    ];
+}
+
+impl std::fmt::Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Kind::Network => "network",
+            Kind::Peering => "peering",
+            Kind::Acl => "acl",
+            Kind::Cluster => "cluster",
+            Kind::SharedCluster => "shared cluster",
+            Kind::Job => "job",
+            Kind::Integration => "integration",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The desired state of a project. Resources are identified by their name,
+/// which is the description they carry in the API, and refer to each other
+/// by name as well.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub networks: Vec<NetworkSpec>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub peerings: Vec<PeeringSpec>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub acls: Vec<AclSpec>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub clusters: Vec<ClusterSpec>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub shared_clusters: Vec<SharedClusterSpec>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub jobs: Vec<JobSpec>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub integrations: Vec<IntegrationSpec>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkSpec {
+    pub name: String,
+    pub provider: String,
+    pub region: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cidr_block: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_access: Option<bool>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PeeringSpec {
+    pub name: String,
+    /// Name of the network the peering belongs to.
+    pub network: String,
+    pub peer_account_id: String,
+    pub peer_network_id: String,
+    pub peer_network_region: String,
+    pub routes: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AclSpec {
+    pub name: String,
+    pub cidr_blocks: Vec<AclCidrBlock>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClusterSpec {
+    pub name: String,
+    /// Name of the network the cluster is deployed in.
+    pub network: String,
+    /// Name of the ACL restricting access to the cluster.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acl: Option<String>,
+    pub instance_type: String,
+    pub disk_size_gb: i32,
+    pub disk_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disk_iops: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disk_throughput: Option<i32>,
+    pub server_version: String,
+    pub topology: Topology,
+    pub projection_level: ProjectionLevel,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protected: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_access: Option<bool>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SharedClusterSpec {
+    pub name: String,
+    /// Name of the ACL restricting access to the cluster.
+    pub acl: String,
+    pub provider: String,
+    pub region: String,
+    pub deployment_tier: String,
+    pub server_version: String,
+    pub topology: Topology,
+    pub projection_level: ProjectionLevel,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mutual_tls_enabled: Option<bool>,
+}
+
+/// A scheduled backup job.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JobSpec {
+    pub name: String,
+    /// Cron expression.
+    pub schedule: String,
+    /// Name of the cluster which is backed up.
+    pub cluster: String,
+    /// Description given to the backups. Defaults to the job's name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_description: Option<String>,
+    pub max_backup_count: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IntegrationSpec {
+    pub name: String,
+    #[serde(flatten)]
+    pub sink: IntegrationSink,
+}
+
+/// Where an integration sends its data. Secrets can't be read back from the
+/// API, so they're only used when an integration is created or updated and
+/// never cause a difference.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "sink", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum IntegrationSink {
+    OpsGenie {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        source: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        api_key: Option<String>,
+    },
+    Slack {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        source: Option<String>,
+        channel_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        token: Option<String>,
+    },
+    PagerDuty {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        source: Option<String>,
+        service: String,
+        user: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        auth_token: Option<String>,
+    },
+    AwsCloudWatchLogs {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        source: Option<String>,
+        group_name: String,
+        region: String,
+        /// Names of the clusters whose logs are sent. All of them if absent.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        clusters: Option<Vec<String>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        access_key_id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        secret_access_key: Option<String>,
+    },
+    AwsCloudWatchMetrics {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        source: Option<String>,
+        namespace: String,
+        region: String,
+        /// Names of the clusters whose metrics are sent. All of them if absent.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        clusters: Option<Vec<String>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        access_key_id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        secret_access_key: Option<String>,
+    },
+    GcpLogging {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        source: Option<String>,
+        gcp_project_id: String,
+        log_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        gcp_service_account_private_key: Option<String>,
+    },
+}
+
+impl IntegrationSink {
+    pub fn name(&self) -> &'static str {
+        match self {
+            IntegrationSink::OpsGenie { .. } => "ops-genie",
+            IntegrationSink::Slack { .. } => "slack",
+            IntegrationSink::PagerDuty { .. } => "pager-duty",
+            IntegrationSink::AwsCloudWatchLogs { .. } => "aws-cloud-watch-logs",
+            IntegrationSink::AwsCloudWatchMetrics { .. } => "aws-cloud-watch-metrics",
+            IntegrationSink::GcpLogging { .. } => "gcp-logging",
+        }
+    }
+
+    /// The same sink with every secret removed.
+    pub fn without_secrets(&self) -> IntegrationSink {
+        let mut sink = self.clone();
+        for secret in sink.secrets_mut() {
+            *secret = None;
+        }
+        sink
+    }
+
+    /// The secrets of the sink, which may be set or not.
+    pub fn secrets_mut(&mut self) -> Vec<&mut Option<String>> {
+        match self {
+            IntegrationSink::OpsGenie { api_key, .. } => vec![api_key],
+            IntegrationSink::Slack { token, .. } => vec![token],
+            IntegrationSink::PagerDuty { auth_token, .. } => vec![auth_token],
+            IntegrationSink::AwsCloudWatchLogs {
+                access_key_id,
+                secret_access_key,
+                ..
+            }
+            | IntegrationSink::AwsCloudWatchMetrics {
+                access_key_id,
+                secret_access_key,
+                ..
+            } => vec![access_key_id, secret_access_key],
+            IntegrationSink::GcpLogging {
+                gcp_service_account_private_key,
+                ..
+            } => vec![gcp_service_account_private_key],
+        }
+    }
+
+    fn clusters(&self) -> Option<&Vec<String>> {
+        match self {
+            IntegrationSink::AwsCloudWatchLogs { clusters, .. }
+            | IntegrationSink::AwsCloudWatchMetrics { clusters, .. } => clusters.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+impl Manifest {
+    /// Names of every resource of the given kind.
+    pub fn names(&self, kind: Kind) -> Vec<&str> {
+        match kind {
+            Kind::Network => self.networks.iter().map(|r| r.name.as_str()).collect(),
+            Kind::Peering => self.peerings.iter().map(|r| r.name.as_str()).collect(),
+            Kind::Acl => self.acls.iter().map(|r| r.name.as_str()).collect(),
+            Kind::Cluster => self.clusters.iter().map(|r| r.name.as_str()).collect(),
+            Kind::SharedCluster => self
+                .shared_clusters
+                .iter()
+                .map(|r| r.name.as_str())
+                .collect(),
+            Kind::Job => self.jobs.iter().map(|r| r.name.as_str()).collect(),
+            Kind::Integration => self.integrations.iter().map(|r| r.name.as_str()).collect(),
+        }
+    }
+
+    /// The resource of the given kind and name as JSON, which is how plans
+    /// compare them.
+    pub fn get(&self, kind: Kind, name: &str) -> Option<serde_json::Value> {
+        fn find<A: serde::Serialize>(
+            items: &[A],
+            name: &str,
+            name_of: fn(&A) -> &str,
+        ) -> Option<serde_json::Value> {
+            items
+                .iter()
+                .find(|item| name_of(item) == name)
+                .and_then(|item| serde_json::to_value(item).ok())
+        }
+
+        match kind {
+            Kind::Network => find(&self.networks, name, |r| &r.name),
+            Kind::Peering => find(&self.peerings, name, |r| &r.name),
+            Kind::Acl => find(&self.acls, name, |r| &r.name),
+            Kind::Cluster => find(&self.clusters, name, |r| &r.name),
+            Kind::SharedCluster => find(&self.shared_clusters, name, |r| &r.name),
+            Kind::Job => find(&self.jobs, name, |r| &r.name),
+            Kind::Integration => find(&self.integrations, name, |r| &r.name),
+        }
+    }
+
+    /// Sorts every resource by name, so the manifest renders the same way no
+    /// matter the order the API listed resources in.
+    pub fn sort(&mut self) {
+        self.networks.sort_by(|a, b| a.name.cmp(&b.name));
+        self.peerings.sort_by(|a, b| a.name.cmp(&b.name));
+        self.acls.sort_by(|a, b| a.name.cmp(&b.name));
+        self.clusters.sort_by(|a, b| a.name.cmp(&b.name));
+        self.shared_clusters.sort_by(|a, b| a.name.cmp(&b.name));
+        self.jobs.sort_by(|a, b| a.name.cmp(&b.name));
+        self.integrations.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    /// The resources the resource of the given kind and name refers to.
+    pub fn references(&self, kind: Kind, name: &str) -> Vec<(Kind, &str)> {
+        let mut references = Vec::new();
+        match kind {
+            Kind::Network | Kind::Acl => {}
+            Kind::Peering => {
+                for peering in self.peerings.iter().filter(|r| r.name == name) {
+                    references.push((Kind::Network, peering.network.as_str()));
+                }
+            }
+            Kind::Cluster => {
+                for cluster in self.clusters.iter().filter(|r| r.name == name) {
+                    references.push((Kind::Network, cluster.network.as_str()));
+                    if let Some(acl) = cluster.acl.as_ref() {
+                        references.push((Kind::Acl, acl.as_str()));
+                    }
+                }
+            }
+            Kind::SharedCluster => {
+                for cluster in self.shared_clusters.iter().filter(|r| r.name == name) {
+                    references.push((Kind::Acl, cluster.acl.as_str()));
+                }
+            }
+            Kind::Job => {
+                for job in self.jobs.iter().filter(|r| r.name == name) {
+                    references.push((Kind::Cluster, job.cluster.as_str()));
+                }
+            }
+            Kind::Integration => {
+                for integration in self.integrations.iter().filter(|r| r.name == name) {
+                    for cluster in integration.sink.clusters().into_iter().flatten() {
+                        references.push((Kind::Cluster, cluster.as_str()));
+                    }
+                }
+            }
+        }
+        references
+    }
+
+    /// Checks names are unique and every reference points to a resource of
+    /// the manifest.
+    pub fn validate(&self) -> Result<(), String> {
+        for kind in Kind::ALL {
+            let mut seen = BTreeSet::new();
+            for name in self.names(*kind) {
+                if !seen.insert(name) {
+                    return Err(format!(
+                        "There is more than one {} named \"{}\"",
+                        kind, name
+                    ));
+                }
+            }
+        }
+
+        for kind in Kind::ALL {
+            for name in self.names(*kind) {
+                for (to, reference) in self.references(*kind, name) {
+                    if !self.names(to).contains(&reference) {
+                        return Err(format!(
+                            "{} \"{}\" refers to {} \"{}\", which isn't in the manifest",
+                            kind, name, to, reference
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn manifest(value: serde_json::Value) -> Manifest {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn validate_rejects_duplicate_names() {
+        let acl = json!({"name": "office", "cidr_blocks": []});
+        let err = manifest(json!({"acls": [acl.clone(), acl]}))
+            .validate()
+            .unwrap_err();
+        assert_eq!(err, "There is more than one acl named \"office\"");
+    }
+
+    #[test]
+    fn validate_rejects_dangling_references() {
+        let job = json!({
+            "name": "nightly",
+            "schedule": "0 0 * * *",
+            "cluster": "db",
+            "max_backup_count": 3,
+        });
+        let err = manifest(json!({"jobs": [job]})).validate().unwrap_err();
+        assert_eq!(
+            err,
+            "job \"nightly\" refers to cluster \"db\", which isn't in the manifest"
+        );
+
+        let integration = json!({
+            "name": "metrics",
+            "sink": "aws-cloud-watch-metrics",
+            "namespace": "esdb",
+            "region": "eu-west-1",
+            "clusters": ["db"],
+        });
+        assert!(manifest(json!({"integrations": [integration]}))
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn validate_accepts_resources_referring_to_each_other() {
+        let manifest = manifest(json!({
+            "acls": [{"name": "office", "cidr_blocks": []}],
+            "shared_clusters": [{
+                "name": "dev",
+                "acl": "office",
+                "provider": "aws",
+                "region": "eu-west-1",
+                "deployment_tier": "shared",
+                "server_version": "23.10",
+                "topology": "single-node",
+                "projection_level": "off",
+            }],
+        }));
+        assert_eq!(manifest.validate(), Ok(()));
+    }
+}
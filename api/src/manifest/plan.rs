@@ -0,0 +1,386 @@
+use super::live::LiveState;
+use super::model::*;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A field which differs between two versions of a resource. Fields are named
+/// by their path, such as `disk_size_gb` or `cidr_blocks`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<Value>,
+}
+
+fn diff_into(
+    path: &str,
+    old: Option<&Value>,
+    new: Option<&Value>,
+    ignore_unset: bool,
+    changes: &mut Vec<FieldChange>,
+) {
+    let new = new.filter(|v| !v.is_null());
+    let old = old.filter(|v| !v.is_null());
+
+    if ignore_unset && new.is_none() {
+        return;
+    }
+
+    if let (Some(Value::Object(old)), Some(Value::Object(new))) = (old, new) {
+        let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+        keys.sort();
+        keys.dedup();
+        for key in keys {
+            let field = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", path, key)
+            };
+            diff_into(&field, old.get(key), new.get(key), ignore_unset, changes);
+        }
+        return;
+    }
+
+    if old != new {
+        changes.push(FieldChange {
+            field: path.to_string(),
+            old: old.cloned(),
+            new: new.cloned(),
+        });
+    }
+}
+
+/// Compares two versions of a resource field by field. Nested objects are
+/// walked, while lists are compared as a whole. With `ignore_unset`, fields
+/// `new` leaves unset don't count as differences.
+pub fn diff(old: &Value, new: &Value, ignore_unset: bool) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    diff_into("", Some(old), Some(new), ignore_unset, &mut changes);
+    changes.retain(|c| c.field != "name");
+    changes
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Action {
+    Create,
+    /// The resource is changed in place.
+    Update,
+    /// The resource is deleted, then created again, since some of the fields
+    /// which changed can't be updated.
+    Replace,
+    Delete,
+}
+
+impl Action {
+    pub fn is_destructive(self) -> bool {
+        matches!(self, Action::Replace | Action::Delete)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Change {
+    pub kind: Kind,
+    pub name: String,
+    pub action: Action,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldChange>,
+    /// Set when the change is caused by a resource this one refers to being
+    /// replaced, rather than by its own fields.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// What it takes to turn the live state into the desired one.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Plan {
+    pub changes: Vec<Change>,
+}
+
+impl Plan {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn count(&self, action: Action) -> usize {
+        self.changes.iter().filter(|c| c.action == action).count()
+    }
+
+    /// Changes which delete a resource, possibly to create it again.
+    pub fn destructive(&self) -> Vec<&Change> {
+        self.changes
+            .iter()
+            .filter(|c| c.action.is_destructive())
+            .collect()
+    }
+}
+
+fn grows(change: &FieldChange) -> bool {
+    match (
+        change.old.as_ref().and_then(Value::as_i64),
+        change.new.as_ref().and_then(Value::as_i64),
+    ) {
+        (Some(old), Some(new)) => new >= old,
+        _ => false,
+    }
+}
+
+// Whether the API can change the field without recreating the resource.
+fn updatable(kind: Kind, change: &FieldChange) -> bool {
+    match kind {
+        Kind::Acl => change.field == "cidr_blocks",
+        Kind::Cluster => match change.field.as_str() {
+            "acl" | "protected" | "instance_type" | "server_version" | "disk_type" => true,
+            "disk_size_gb" | "disk_iops" | "disk_throughput" => grows(change),
+            _ => false,
+        },
+        Kind::SharedCluster => change.field == "acl" || change.field == "mutual_tls_enabled",
+        Kind::Integration => change.field != "sink" && change.field != "source",
+        Kind::Network | Kind::Peering | Kind::Job => false,
+    }
+}
+
+// The first resource the live version of a resource refers to which is
+// replaced by `changes`. Kinds come in dependency order, so the resources
+// referred to are planned by then.
+fn replaced_reference<'a>(
+    changes: &[Change],
+    live: &'a Manifest,
+    kind: Kind,
+    name: &str,
+) -> Option<(Kind, &'a str)> {
+    live.references(kind, name)
+        .into_iter()
+        .find(|(kind, name)| {
+            changes
+                .iter()
+                .any(|c| c.kind == *kind && c.name == *name && c.action == Action::Replace)
+        })
+}
+
+/// Compares the desired manifest with the live state. Fields the manifest
+/// leaves out, as well as integration secrets, are never reported as changes.
+/// Replacing a resource replaces the resources referring to it as well, since
+/// they'd be left pointing at one which is gone, except for integrations,
+/// which are updated with the new ids.
+pub fn plan(desired: &Manifest, live: &LiveState) -> Plan {
+    let mut desired = desired.clone();
+    for integration in desired.integrations.iter_mut() {
+        integration.sink = integration.sink.without_secrets();
+    }
+
+    let mut changes = Vec::new();
+
+    for kind in Kind::ALL.iter().copied() {
+        for name in desired.names(kind) {
+            let wanted = desired.get(kind, name).unwrap_or(Value::Null);
+            let change = match live.manifest.get(kind, name) {
+                None => Change {
+                    kind,
+                    name: name.to_string(),
+                    action: Action::Create,
+                    fields: vec![],
+                    reason: None,
+                },
+                Some(current) => {
+                    let fields = diff(&current, &wanted, true);
+                    let replaced = replaced_reference(&changes, &live.manifest, kind, name);
+                    let action = match replaced {
+                        // Integrations only need the ids of the new clusters.
+                        Some(_) if kind == Kind::Integration => Action::Update,
+                        Some(_) => Action::Replace,
+                        None if fields.is_empty() => continue,
+                        None if fields.iter().all(|f| updatable(kind, f)) => Action::Update,
+                        None => Action::Replace,
+                    };
+                    Change {
+                        kind,
+                        name: name.to_string(),
+                        action,
+                        fields,
+                        reason: replaced.map(|(kind, name)| {
+                            format!("the {} \"{}\" it refers to is replaced", kind, name)
+                        }),
+                    }
+                }
+            };
+            changes.push(change);
+        }
+
+        let wanted = desired.names(kind);
+        for name in live.manifest.names(kind) {
+            if !wanted.contains(&name) {
+                changes.push(Change {
+                    kind,
+                    name: name.to_string(),
+                    action: Action::Delete,
+                    fields: vec![],
+                    reason: None,
+                });
+            }
+        }
+    }
+
+    Plan { changes }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn manifest(value: Value) -> Manifest {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn live(value: Value) -> LiveState {
+        let mut state = LiveState::default();
+        state.manifest = manifest(value);
+        state
+    }
+
+    fn network(cidr_block: &str) -> Value {
+        json!({
+            "name": "net",
+            "provider": "aws",
+            "region": "eu-west-1",
+            "cidr_block": cidr_block,
+        })
+    }
+
+    fn cluster() -> Value {
+        json!({
+            "name": "db",
+            "network": "net",
+            "instance_type": "F1",
+            "disk_size_gb": 10,
+            "disk_type": "gp3",
+            "server_version": "23.10",
+            "topology": "single-node",
+            "projection_level": "off",
+        })
+    }
+
+    fn actions(plan: &Plan) -> Vec<(Kind, &str, Action)> {
+        plan.changes
+            .iter()
+            .map(|c| (c.kind, c.name.as_str(), c.action))
+            .collect()
+    }
+
+    #[test]
+    fn diff_walks_objects_and_skips_unset_fields() {
+        let old = json!({"name": "a", "size": 1, "tags": {"env": "dev", "team": "x"}});
+        let new = json!({"name": "b", "tags": {"env": "prod"}});
+
+        let fields: Vec<String> = diff(&old, &new, true)
+            .into_iter()
+            .map(|c| c.field)
+            .collect();
+        assert_eq!(fields, vec!["tags.env"]);
+
+        let fields: Vec<String> = diff(&old, &new, false)
+            .into_iter()
+            .map(|c| c.field)
+            .collect();
+        assert_eq!(fields, vec!["size", "tags.env", "tags.team"]);
+    }
+
+    #[test]
+    fn plan_updates_what_the_api_can_change_and_replaces_the_rest() {
+        let current = live(json!({"networks": [network("10.0.0.0/16")], "clusters": [cluster()]}));
+
+        let mut resized = cluster();
+        resized["instance_type"] = json!("C4");
+        resized["disk_size_gb"] = json!(20);
+        let plan = plan(
+            &manifest(json!({"networks": [network("10.0.0.0/16")], "clusters": [resized]})),
+            &current,
+        );
+        assert_eq!(actions(&plan), vec![(Kind::Cluster, "db", Action::Update)]);
+        assert_eq!(plan.changes[0].fields.len(), 2);
+
+        let mut shrunk = cluster();
+        shrunk["disk_size_gb"] = json!(5);
+        let plan = super::plan(
+            &manifest(json!({"networks": [network("10.0.0.0/16")], "clusters": [shrunk]})),
+            &current,
+        );
+        assert_eq!(actions(&plan), vec![(Kind::Cluster, "db", Action::Replace)]);
+
+        let unchanged = super::plan(
+            &manifest(json!({"networks": [network("10.0.0.0/16")], "clusters": [cluster()]})),
+            &current,
+        );
+        assert!(unchanged.is_empty());
+    }
+
+    #[test]
+    fn plan_creates_new_resources_and_deletes_unlisted_ones() {
+        let current = live(json!({"acls": [{"name": "old", "cidr_blocks": []}]}));
+        let plan = plan(
+            &manifest(json!({"networks": [network("10.0.0.0/16")]})),
+            &current,
+        );
+        assert_eq!(
+            actions(&plan),
+            vec![
+                (Kind::Network, "net", Action::Create),
+                (Kind::Acl, "old", Action::Delete),
+            ]
+        );
+    }
+
+    #[test]
+    fn plan_replaces_the_dependents_of_a_replaced_resource() {
+        let resources = |cidr_block: &str| {
+            json!({
+                "networks": [network(cidr_block)],
+                "peerings": [{
+                    "name": "peer",
+                    "network": "net",
+                    "peer_account_id": "1",
+                    "peer_network_id": "vpc",
+                    "peer_network_region": "eu-west-1",
+                    "routes": [],
+                }],
+                "clusters": [cluster()],
+                "jobs": [{
+                    "name": "nightly",
+                    "schedule": "0 0 * * *",
+                    "cluster": "db",
+                    "max_backup_count": 3,
+                }],
+                "integrations": [{
+                    "name": "logs",
+                    "sink": "aws-cloud-watch-logs",
+                    "group_name": "esdb",
+                    "region": "eu-west-1",
+                    "clusters": ["db"],
+                }],
+            })
+        };
+
+        let plan = plan(
+            &manifest(resources("10.1.0.0/16")),
+            &live(resources("10.0.0.0/16")),
+        );
+        assert_eq!(
+            actions(&plan),
+            vec![
+                (Kind::Network, "net", Action::Replace),
+                (Kind::Peering, "peer", Action::Replace),
+                (Kind::Cluster, "db", Action::Replace),
+                (Kind::Job, "nightly", Action::Replace),
+                (Kind::Integration, "logs", Action::Update),
+            ]
+        );
+        assert_eq!(plan.changes[0].reason, None);
+        assert_eq!(
+            plan.changes[3].reason.as_deref(),
+            Some("the cluster \"db\" it refers to is replaced")
+        );
+    }
+}
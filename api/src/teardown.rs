@@ -0,0 +1,815 @@
+//! Deletes a project along with everything in it. `inventory` lists what's
+//! left in the project, `plan` decides what happens to each resource, and
+//! `run` deletes them in dependency order before deleting the project itself.
+//! Since the inventory leaves out what's already deleted, and waits on what's
+//! still being deleted, an interrupted teardown resumes where it stopped when
+//! it's planned and run again.
+use crate::infra::{AclId, AclStatus, NetworkStatus, PeeringStatus};
+use crate::integrate::{IntegrationId, IntegrationStatus};
+use crate::mesdb::{BackupStatus, ClusterStatus};
+use crate::orchestrate::JobData;
+use crate::wait::{Target, WaitError, WaitOptions, Waitable};
+use crate::{BackupId, ClusterId, JobId, NetworkId, OrgId, PeeringId, ProjectId};
+use futures_util::stream::{self, TryStreamExt};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// The kinds of resources in a project, in the order they're deleted.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Kind {
+    Job,
+    Integration,
+    Cluster,
+    SharedCluster,
+    Backup,
+    Peering,
+    Acl,
+    Network,
+    Project,
+}
+
+impl std::fmt::Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            Kind::Job => "job",
+            Kind::Integration => "integration",
+            Kind::Cluster => "cluster",
+            Kind::SharedCluster => "shared cluster",
+            Kind::Backup => "backup",
+            Kind::Peering => "peering",
+            Kind::Acl => "acl",
+            Kind::Network => "network",
+            Kind::Project => "project",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A resource found in the project.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Resource {
+    pub kind: Kind,
+    pub id: String,
+    pub name: String,
+    pub status: String,
+    /// Only clusters can be protected.
+    pub protected: bool,
+    /// Whether its deletion was started earlier, in which case it's only
+    /// waited on.
+    pub deleting: bool,
+    /// The ids of the resources it refers to: the network and ACL a cluster
+    /// is in, which can't be deleted while it's around, and the cluster a job
+    /// or backup is of.
+    pub uses: Vec<String>,
+}
+
+impl Resource {
+    fn new(kind: Kind, id: &str, name: &str, status: String) -> Self {
+        Resource {
+            kind,
+            id: id.to_string(),
+            name: name.to_string(),
+            status,
+            protected: false,
+            deleting: false,
+            uses: vec![],
+        }
+    }
+}
+
+fn cluster_deleting(status: &ClusterStatus) -> bool {
+    matches!(
+        status,
+        ClusterStatus::DeletingInstances
+            | ClusterStatus::InstancesDeleted
+            | ClusterStatus::DeletingDisks
+    )
+}
+
+/// Lists the resources in the project, along with the project itself, which
+/// comes last. Deleted resources are left out.
+pub async fn inventory(
+    client: &crate::Client,
+    org_id: &OrgId,
+    project_id: &ProjectId,
+) -> crate::Result<Vec<Resource>> {
+    let mut resources = Vec::new();
+
+    let jobs = crate::orchestrate::list_jobs(client, org_id.clone(), project_id.clone())
+        .await?
+        .jobs;
+    for job in jobs
+        .into_iter()
+        .filter(|j| !j.status.eq_ignore_ascii_case("deleted"))
+    {
+        let mut resource = Resource::new(Kind::Job, &job.id.0, &job.description, job.status);
+        match job.data {
+            JobData::ScheduledBackup(data) => resource.uses.push(data.cluster_id.0),
+        }
+        resources.push(resource);
+    }
+
+    let integrations =
+        crate::integrate::list_integrations(client, org_id.clone(), project_id.clone())
+            .await?
+            .integrations;
+    for integration in integrations
+        .into_iter()
+        .filter(|i| i.status != IntegrationStatus::Deleted)
+    {
+        resources.push(Resource::new(
+            Kind::Integration,
+            &integration.id.0,
+            &integration.description,
+            integration.status.to_string(),
+        ));
+    }
+
+    let clusters = crate::mesdb::list_clusters(client, org_id.clone(), project_id.clone())
+        .await?
+        .clusters;
+    for cluster in clusters
+        .into_iter()
+        .filter(|c| c.status != ClusterStatus::Deleted)
+    {
+        let mut resource = Resource::new(
+            Kind::Cluster,
+            &cluster.id.0,
+            &cluster.description,
+            cluster.status(),
+        );
+        resource.protected = cluster.protected;
+        resource.deleting = cluster_deleting(&cluster.status);
+        resource.uses.push(cluster.network_id.0);
+        resource
+            .uses
+            .extend(cluster.acl_id.filter(|id| !id.is_empty()));
+        resources.push(resource);
+    }
+
+    let shared_clusters =
+        crate::mesdb::list_shared_clusters(client, org_id.clone(), project_id.clone())
+            .await?
+            .clusters;
+    for cluster in shared_clusters
+        .into_iter()
+        .filter(|c| c.status != ClusterStatus::Deleted)
+    {
+        let mut resource = Resource::new(
+            Kind::SharedCluster,
+            &cluster.id.0,
+            &cluster.name,
+            cluster.status(),
+        );
+        resource.deleting = cluster_deleting(&cluster.status);
+        resources.push(resource);
+    }
+
+    let backups = crate::mesdb::list_backups(client, org_id.clone(), project_id.clone())
+        .await?
+        .backups;
+    for backup in backups
+        .into_iter()
+        .filter(|b| b.status != BackupStatus::Deleted)
+    {
+        let mut resource = Resource::new(
+            Kind::Backup,
+            &backup.id.0,
+            &backup.description,
+            backup.status(),
+        );
+        resource.deleting = backup.status == BackupStatus::Deleting;
+        resource.uses.push(backup.source_cluster_id.0);
+        resources.push(resource);
+    }
+
+    let peerings = crate::infra::list_peerings(client, org_id.clone(), project_id.clone())
+        .await?
+        .peerings;
+    for peering in peerings
+        .into_iter()
+        .filter(|p| p.status != PeeringStatus::Deleted)
+    {
+        let mut resource = Resource::new(
+            Kind::Peering,
+            &peering.id.0,
+            &peering.description,
+            peering.status(),
+        );
+        resource.deleting = peering.status == PeeringStatus::Deleting;
+        resources.push(resource);
+    }
+
+    let acls = crate::infra::list_acls(client, org_id.clone(), project_id.clone())
+        .await?
+        .acls;
+    for acl in acls.into_iter().filter(|a| a.status != AclStatus::Deleted) {
+        resources.push(Resource::new(
+            Kind::Acl,
+            &acl.id.0,
+            &acl.description,
+            acl.status(),
+        ));
+    }
+
+    let networks = crate::infra::list_networks(client, org_id.clone(), project_id.clone())
+        .await?
+        .networks;
+    for network in networks
+        .into_iter()
+        .filter(|n| n.status != NetworkStatus::Deleted)
+    {
+        let mut resource = Resource::new(
+            Kind::Network,
+            &network.id.0,
+            &network.description,
+            network.status(),
+        );
+        resource.deleting = network.status == NetworkStatus::Deleting;
+        resources.push(resource);
+    }
+
+    let project = crate::resources::get_project(client, org_id.clone(), project_id.clone())
+        .await?
+        .project;
+    resources.push(Resource::new(
+        Kind::Project,
+        &project.id.0,
+        &project.name,
+        String::new(),
+    ));
+
+    Ok(resources)
+}
+
+/// What to do about protected clusters.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OnProtected {
+    /// Nothing is deleted.
+    Abort,
+    /// The clusters are kept, along with their networks, their ACLs, their
+    /// jobs and backups, and the project.
+    Skip,
+    /// The clusters are unprotected, then deleted like the others.
+    Unprotect,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Action {
+    Delete,
+    /// The cluster is unprotected first.
+    Unprotect,
+    /// The resource is already being deleted, so it's waited on.
+    Wait,
+    Keep,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Step {
+    pub kind: Kind,
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub status: String,
+    pub action: Action,
+    /// Why the resource is kept.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// What happens to every resource in the project, in the order it happens.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Plan {
+    pub steps: Vec<Step>,
+}
+
+impl Plan {
+    pub fn count(&self, action: Action) -> usize {
+        self.steps.iter().filter(|s| s.action == action).count()
+    }
+
+    /// Protected clusters which stay unless they're unprotected.
+    pub fn protected(&self) -> Vec<&Step> {
+        self.steps
+            .iter()
+            .filter(|s| s.kind == Kind::Cluster && s.action == Action::Keep)
+            .collect()
+    }
+}
+
+/// Orders the resources from `inventory` by kind and decides what happens to
+/// each. Resources protected clusters depend on, and the jobs and backups of
+/// those clusters, are kept unless they're to be unprotected, and so is the
+/// project.
+pub fn plan(resources: &[Resource], on_protected: OnProtected) -> Plan {
+    let mut kept: HashMap<&str, String> = HashMap::new();
+    if on_protected != OnProtected::Unprotect {
+        for cluster in resources.iter().filter(|r| r.protected) {
+            kept.insert(&cluster.id, "protected".to_string());
+            for id in cluster.uses.iter() {
+                kept.insert(
+                    id,
+                    format!("used by protected cluster \"{}\"", cluster.name),
+                );
+            }
+            for resource in resources.iter().filter(|r| r.uses.contains(&cluster.id)) {
+                kept.insert(
+                    &resource.id,
+                    format!("of protected cluster \"{}\"", cluster.name),
+                );
+            }
+        }
+    }
+
+    let mut resources: Vec<&Resource> = resources.iter().collect();
+    resources.sort_by_key(|r| r.kind);
+
+    let steps = resources
+        .into_iter()
+        .map(|resource| {
+            let mut reason = kept.get(resource.id.as_str()).cloned();
+            if resource.kind == Kind::Project && !kept.is_empty() {
+                reason = Some("other resources are kept".to_string());
+            }
+            let action = if reason.is_some() {
+                Action::Keep
+            } else if resource.protected {
+                Action::Unprotect
+            } else if resource.deleting {
+                Action::Wait
+            } else {
+                Action::Delete
+            };
+            Step {
+                kind: resource.kind,
+                id: resource.id.clone(),
+                name: resource.name.clone(),
+                status: resource.status.clone(),
+                action,
+                reason,
+            }
+        })
+        .collect();
+
+    Plan { steps }
+}
+
+#[derive(Clone, Debug)]
+pub struct TeardownOptions {
+    pub on_protected: OnProtected,
+    /// How many resources of the same kind are deleted at once.
+    pub concurrency: usize,
+    /// How long to wait on each resource.
+    pub wait: WaitOptions,
+}
+
+/// Fails with `TeardownError::Protected` when the plan keeps protected
+/// clusters and `OnProtected::Abort` was given, so nothing is deleted.
+#[allow(clippy::result_large_err)]
+pub fn check(plan: &Plan, on_protected: OnProtected) -> Result<(), TeardownError> {
+    let protected = plan.protected();
+    if protected.is_empty() || on_protected != OnProtected::Abort {
+        return Ok(());
+    }
+    Err(TeardownError::Protected(
+        protected
+            .iter()
+            .map(|s| format!("\"{}\" ({})", s.name, s.id))
+            .collect(),
+    ))
+}
+
+/// Reported as a plan is run.
+pub enum TeardownEvent<'a> {
+    Started(&'a Step),
+    /// The resource is being waited on. Holds its current status.
+    Waiting(&'a Step, String),
+    Finished(&'a Step),
+}
+
+pub enum TeardownError {
+    /// There are protected clusters and `OnProtected::Abort` was given.
+    Protected(Vec<String>),
+    /// Deleting the resource failed.
+    Api(Step, crate::Error),
+    /// Waiting on the resource to be deleted failed.
+    Wait(Step, WaitError),
+}
+
+impl std::fmt::Display for TeardownError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TeardownError::Protected(names) => write!(
+                f,
+                "The project has protected clusters: {}",
+                names.join(", ")
+            ),
+            TeardownError::Api(step, err) => {
+                write!(
+                    f,
+                    "Could not delete {} \"{}\": {}",
+                    step.kind, step.name, err
+                )
+            }
+            TeardownError::Wait(step, err) => write!(
+                f,
+                "Gave up waiting on {} \"{}\" to be deleted: {}",
+                step.kind, step.name, err
+            ),
+        }
+    }
+}
+
+impl std::fmt::Debug for TeardownError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TeardownError::Api(_, err) => write!(f, "{:?}", err),
+            TeardownError::Wait(_, err) => write!(f, "{:?}", err),
+            _ => write!(f, "{}", self),
+        }
+    }
+}
+
+impl std::error::Error for TeardownError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TeardownError::Api(_, err) => Some(err),
+            TeardownError::Wait(_, err) => Some(err),
+            TeardownError::Protected(_) => None,
+        }
+    }
+}
+
+fn not_found(err: &crate::Error) -> bool {
+    matches!(err, crate::Error::ApiResponse(resp) if resp.status_code.as_u16() == 404)
+}
+
+struct Teardown<'a, P> {
+    client: &'a crate::Client,
+    org_id: OrgId,
+    project_id: ProjectId,
+    options: &'a TeardownOptions,
+    on_event: Mutex<P>,
+}
+
+impl<'a, P> Teardown<'a, P>
+where
+    P: FnMut(TeardownEvent<'_>),
+{
+    fn report(&self, event: TeardownEvent<'_>) {
+        let mut on_event = self.on_event.lock().expect("Not poisoned");
+        (on_event)(event);
+    }
+
+    async fn unprotect(&self, step: &Step) -> crate::Result<()> {
+        crate::mesdb::update_cluster(
+            self.client,
+            self.org_id.clone(),
+            self.project_id.clone(),
+            ClusterId(step.id.clone()),
+            crate::mesdb::UpdateClusterRequest {
+                acl_id: None,
+                description: None,
+                protected: Some(false),
+            },
+        )
+        .await
+    }
+
+    async fn delete(&self, step: &Step) -> crate::Result<()> {
+        let org_id = self.org_id.clone();
+        let project_id = self.project_id.clone();
+        let id = step.id.clone();
+        match step.kind {
+            Kind::Job => {
+                crate::orchestrate::delete_job(self.client, org_id, project_id, JobId(id)).await
+            }
+            Kind::Integration => {
+                crate::integrate::delete_integration(
+                    self.client,
+                    org_id,
+                    project_id,
+                    IntegrationId(id),
+                )
+                .await
+            }
+            Kind::Cluster => {
+                crate::mesdb::delete_cluster(self.client, org_id, project_id, ClusterId(id)).await
+            }
+            Kind::SharedCluster => {
+                crate::mesdb::delete_shared_cluster(self.client, org_id, project_id, ClusterId(id))
+                    .await
+            }
+            Kind::Backup => {
+                crate::mesdb::delete_backup(self.client, org_id, project_id, BackupId(id)).await
+            }
+            Kind::Peering => {
+                crate::infra::delete_peering(self.client, org_id, project_id, PeeringId(id)).await
+            }
+            Kind::Acl => crate::infra::delete_acl(self.client, org_id, project_id, AclId(id)).await,
+            Kind::Network => {
+                crate::infra::delete_network(self.client, org_id, project_id, NetworkId(id)).await
+            }
+            Kind::Project => {
+                crate::resources::delete_project(self.client, org_id, project_id).await
+            }
+        }
+    }
+
+    // Jobs, integrations and projects are gone as soon as they're deleted.
+    async fn wait(&self, step: &Step) -> Result<(), WaitError> {
+        if self.client.sender.mode == crate::SendMode::DryRun {
+            return Ok(());
+        }
+        let org_id = self.org_id.clone();
+        let project_id = self.project_id.clone();
+        let id = step.id.clone();
+        let target = Target::statuses(vec!["deleted".to_string()]);
+        let options = &self.options.wait;
+        let on_poll = |status: String| self.report(TeardownEvent::Waiting(step, status));
+        let result = match step.kind {
+            Kind::Cluster => crate::wait::wait_for_cluster(
+                self.client,
+                org_id,
+                project_id,
+                ClusterId(id),
+                &target,
+                options,
+                |c| on_poll(c.describe()),
+            )
+            .await
+            .map(|_| ()),
+            Kind::SharedCluster => crate::wait::wait_for_shared_cluster(
+                self.client,
+                org_id,
+                project_id,
+                ClusterId(id),
+                &target,
+                options,
+                |c| on_poll(c.describe()),
+            )
+            .await
+            .map(|_| ()),
+            Kind::Backup => crate::wait::wait_for_backup(
+                self.client,
+                org_id,
+                project_id,
+                BackupId(id),
+                &target,
+                options,
+                |b| on_poll(b.describe()),
+            )
+            .await
+            .map(|_| ()),
+            Kind::Peering => crate::wait::wait_for_peering(
+                self.client,
+                org_id,
+                project_id,
+                PeeringId(id),
+                &target,
+                options,
+                |p| on_poll(p.describe()),
+            )
+            .await
+            .map(|_| ()),
+            Kind::Acl => crate::wait::wait_for_acl(
+                self.client,
+                org_id,
+                project_id,
+                AclId(id),
+                &target,
+                options,
+                |a| on_poll(a.describe()),
+            )
+            .await
+            .map(|_| ()),
+            Kind::Network => crate::wait::wait_for_network(
+                self.client,
+                org_id,
+                project_id,
+                NetworkId(id),
+                &target,
+                options,
+                |n| on_poll(n.describe()),
+            )
+            .await
+            .map(|_| ()),
+            Kind::Job | Kind::Integration | Kind::Project => Ok(()),
+        };
+        // Deleted resources may vanish instead of reporting a deleted status.
+        match result {
+            Err(WaitError::Api(err)) if not_found(&err) => Ok(()),
+            other => other,
+        }
+    }
+
+    async fn run_step(&self, step: &Step) -> Result<(), TeardownError> {
+        self.report(TeardownEvent::Started(step));
+        if step.action == Action::Unprotect {
+            self.unprotect(step)
+                .await
+                .map_err(|err| TeardownError::Api(step.clone(), err))?;
+        }
+        if step.action != Action::Wait {
+            match self.delete(step).await {
+                Err(err) if !not_found(&err) => {
+                    return Err(TeardownError::Api(step.clone(), err));
+                }
+                _ => {}
+            }
+        }
+        self.wait(step)
+            .await
+            .map_err(|err| TeardownError::Wait(step.clone(), err))?;
+        self.report(TeardownEvent::Finished(step));
+        Ok(())
+    }
+}
+
+/// Carries out the plan one kind at a time, deleting up to
+/// `options.concurrency` resources of that kind at once and waiting until
+/// they're gone before moving on. Kept resources are left alone. Stops at the
+/// first failure.
+pub async fn run<P>(
+    client: &crate::Client,
+    org_id: &OrgId,
+    project_id: &ProjectId,
+    plan: &Plan,
+    options: &TeardownOptions,
+    on_event: P,
+) -> Result<(), TeardownError>
+where
+    P: FnMut(TeardownEvent<'_>),
+{
+    check(plan, options.on_protected)?;
+
+    let teardown = Teardown {
+        client,
+        org_id: org_id.clone(),
+        project_id: project_id.clone(),
+        options,
+        on_event: Mutex::new(on_event),
+    };
+
+    let mut kinds: Vec<Kind> = plan.steps.iter().map(|s| s.kind).collect();
+    kinds.dedup();
+    for kind in kinds {
+        let steps = plan
+            .steps
+            .iter()
+            .filter(|s| s.kind == kind && s.action != Action::Keep);
+        stream::iter(steps.map(Ok))
+            .try_for_each_concurrent(options.concurrency.max(1), |step| teardown.run_step(step))
+            .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resource(kind: Kind, id: &str) -> Resource {
+        Resource::new(kind, id, &format!("{}-name", id), String::new())
+    }
+
+    fn project() -> Vec<Resource> {
+        let mut cluster = resource(Kind::Cluster, "cluster");
+        cluster.uses = vec!["network".to_string(), "acl".to_string()];
+        let mut job = resource(Kind::Job, "job");
+        job.uses = vec!["cluster".to_string()];
+        let mut backup = resource(Kind::Backup, "backup");
+        backup.uses = vec!["cluster".to_string()];
+        let mut other = resource(Kind::Backup, "other-backup");
+        other.uses = vec!["gone".to_string()];
+        vec![
+            resource(Kind::Project, "project"),
+            resource(Kind::Network, "network"),
+            backup,
+            other,
+            resource(Kind::Acl, "acl"),
+            cluster,
+            job,
+            resource(Kind::Integration, "integration"),
+        ]
+    }
+
+    fn actions(plan: &Plan) -> Vec<(&str, Action)> {
+        plan.steps
+            .iter()
+            .map(|s| (s.id.as_str(), s.action))
+            .collect()
+    }
+
+    #[test]
+    fn test_resources_are_deleted_in_dependency_order() {
+        let plan = plan(&project(), OnProtected::Abort);
+        let kinds: Vec<Kind> = plan.steps.iter().map(|s| s.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                Kind::Job,
+                Kind::Integration,
+                Kind::Cluster,
+                Kind::Backup,
+                Kind::Backup,
+                Kind::Acl,
+                Kind::Network,
+                Kind::Project,
+            ]
+        );
+        assert!(plan.steps.iter().all(|s| s.action == Action::Delete));
+        assert!(check(&plan, OnProtected::Abort).is_ok());
+    }
+
+    #[test]
+    fn test_protected_clusters_keep_what_they_use_and_what_uses_them() {
+        let mut resources = project();
+        resources
+            .iter_mut()
+            .find(|r| r.id == "cluster")
+            .unwrap()
+            .protected = true;
+
+        let plan = plan(&resources, OnProtected::Skip);
+        assert_eq!(
+            actions(&plan),
+            vec![
+                ("job", Action::Keep),
+                ("integration", Action::Delete),
+                ("cluster", Action::Keep),
+                ("backup", Action::Keep),
+                ("other-backup", Action::Delete),
+                ("acl", Action::Keep),
+                ("network", Action::Keep),
+                ("project", Action::Keep),
+            ]
+        );
+        let reason = |id: &str| {
+            plan.steps
+                .iter()
+                .find(|s| s.id == id)
+                .and_then(|s| s.reason.clone())
+        };
+        assert_eq!(reason("cluster").as_deref(), Some("protected"));
+        assert_eq!(
+            reason("network").as_deref(),
+            Some("used by protected cluster \"cluster-name\"")
+        );
+        assert_eq!(
+            reason("backup").as_deref(),
+            Some("of protected cluster \"cluster-name\"")
+        );
+        assert!(check(&plan, OnProtected::Skip).is_ok());
+        assert!(matches!(
+            check(&plan, OnProtected::Abort),
+            Err(TeardownError::Protected(names)) if names == vec!["\"cluster-name\" (cluster)"]
+        ));
+    }
+
+    #[test]
+    fn test_protected_clusters_are_unprotected_first() {
+        let mut resources = project();
+        resources
+            .iter_mut()
+            .find(|r| r.id == "cluster")
+            .unwrap()
+            .protected = true;
+
+        let plan = plan(&resources, OnProtected::Unprotect);
+        assert_eq!(plan.count(Action::Keep), 0);
+        assert_eq!(plan.count(Action::Unprotect), 1);
+        assert!(plan.protected().is_empty());
+    }
+
+    #[test]
+    fn test_resources_being_deleted_are_waited_on() {
+        let mut resources = project();
+        for resource in resources.iter_mut() {
+            if resource.id == "cluster" || resource.id == "network" {
+                resource.deleting = true;
+            }
+        }
+
+        let plan = plan(&resources, OnProtected::Abort);
+        assert_eq!(plan.count(Action::Wait), 2);
+        assert_eq!(plan.count(Action::Delete), 6);
+        assert!(plan
+            .steps
+            .iter()
+            .filter(|s| s.action == Action::Wait)
+            .all(|s| s.id == "cluster" || s.id == "network"));
+    }
+
+    #[test]
+    fn test_deleting_cluster_statuses() {
+        assert!(cluster_deleting(&ClusterStatus::DeletingInstances));
+        assert!(cluster_deleting(&ClusterStatus::InstancesDeleted));
+        assert!(cluster_deleting(&ClusterStatus::DeletingDisks));
+        assert!(!cluster_deleting(&ClusterStatus::Available));
+        assert!(!cluster_deleting(&ClusterStatus::Deleted));
+    }
+}
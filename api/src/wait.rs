@@ -0,0 +1,839 @@
+//! Helpers which poll a resource until it reaches a desired state.
+use crate::infra::{Acl, AclId, AclStatus, Network, NetworkStatus, Peering, PeeringStatus};
+use crate::mesdb::{Backup, BackupStatus, Cluster, ClusterStatus, Health, SharedCluster};
+use crate::orchestrate::HistoryItem;
+use crate::{BackupId, ClusterId, JobId, NetworkId, OrgId, PeeringId, ProjectId};
+use serde::de::{DeserializeOwned, Visitor};
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Controls how often and for how long a resource is polled.
+#[derive(Clone, Debug)]
+pub struct WaitOptions {
+    /// How long to wait before giving up.
+    pub timeout: Duration,
+    /// Delay between the first two polls. It grows with every poll.
+    pub initial_interval: Duration,
+    /// Upper bound for the delay between polls.
+    pub max_interval: Duration,
+}
+
+impl Default for WaitOptions {
+    fn default() -> Self {
+        WaitOptions {
+            timeout: Duration::from_secs(30 * 60),
+            initial_interval: Duration::from_secs(2),
+            max_interval: Duration::from_secs(30),
+        }
+    }
+}
+
+impl WaitOptions {
+    pub fn with_timeout(timeout: Duration) -> Self {
+        WaitOptions {
+            timeout,
+            ..Default::default()
+        }
+    }
+}
+
+/// What a single poll found out about the resource.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Progress {
+    /// The resource is in the desired state.
+    Reached,
+    /// The resource is still transitioning. Holds its current status.
+    Pending(String),
+    /// The resource entered a state it won't leave on its own. Holds its
+    /// current status.
+    Failed(String),
+}
+
+pub enum WaitError {
+    /// The resource entered a failure state.
+    Failed { status: String },
+    /// The timeout expired before the desired state was reached.
+    TimedOut { status: String, timeout: Duration },
+    /// Polling the resource failed.
+    Api(crate::Error),
+}
+
+impl std::fmt::Display for WaitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            WaitError::Failed { status } => {
+                write!(f, "Resource entered failure state \"{}\"", status)
+            }
+            WaitError::TimedOut { status, timeout } => write!(
+                f,
+                "Timed out after {}s, last status was \"{}\"",
+                timeout.as_secs(),
+                status
+            ),
+            WaitError::Api(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::fmt::Debug for WaitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            WaitError::Api(err) => write!(f, "{:?}", err),
+            _ => write!(f, "{}", self),
+        }
+    }
+}
+
+impl std::error::Error for WaitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WaitError::Api(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<crate::Error> for WaitError {
+    fn from(err: crate::Error) -> Self {
+        WaitError::Api(err)
+    }
+}
+
+// Whether a failed poll is worth trying again: the request didn't get an
+// answer from the API, such as when the connection drops or a gateway replies
+// with a page that isn't JSON, or the API failed on its side. Anything else,
+// like a 404 or a request --as-curl didn't send, won't go away by polling
+// again.
+fn transient(err: &crate::Error) -> bool {
+    match err {
+        crate::Error::ApiResponse(resp) => resp.status_code.is_server_error(),
+        crate::Error::Other(_) => err.not_sent().is_none(),
+    }
+}
+
+/// Calls `fetch` with an increasing delay until `check` says the value has
+/// reached the desired state, a failure state, or the timeout expires. Polls
+/// failing on transient errors are retried until then too, and the last such
+/// error is returned if the timeout expires.
+pub async fn poll<T, F, Fut, C>(
+    options: &WaitOptions,
+    mut fetch: F,
+    mut check: C,
+) -> Result<T, WaitError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = crate::Result<T>>,
+    C: FnMut(&T) -> Progress,
+{
+    let deadline = Instant::now() + options.timeout;
+    let mut interval = options.initial_interval;
+
+    loop {
+        let now = match fetch().await {
+            Ok(value) => {
+                let status = match check(&value) {
+                    Progress::Reached => return Ok(value),
+                    Progress::Failed(status) => return Err(WaitError::Failed { status }),
+                    Progress::Pending(status) => status,
+                };
+                let now = Instant::now();
+                if now >= deadline {
+                    return Err(WaitError::TimedOut {
+                        status,
+                        timeout: options.timeout,
+                    });
+                }
+                now
+            }
+            Err(err) => {
+                let now = Instant::now();
+                if !transient(&err) || now >= deadline {
+                    return Err(err.into());
+                }
+                now
+            }
+        };
+
+        tokio::time::sleep(std::cmp::min(interval, deadline - now)).await;
+        interval = std::cmp::min(interval.mul_f32(1.5), options.max_interval);
+    }
+}
+
+/// A resource which reports a status that can be waited on.
+pub trait Waitable {
+    /// The enum of the statuses the resource reports.
+    type Status: DeserializeOwned;
+
+    /// Statuses which mean the resource is ready to be used.
+    const READY: &'static [&'static str];
+    /// Statuses the resource won't leave on its own.
+    const FAILED: &'static [&'static str];
+
+    fn status(&self) -> String;
+
+    /// Every status the resource may report.
+    fn statuses() -> &'static [&'static str] {
+        variants::<Self::Status>()
+    }
+
+    /// Only clusters report their health.
+    fn health(&self) -> Option<&Health> {
+        None
+    }
+
+    /// The status, along with the health if there is one.
+    fn describe(&self) -> String {
+        match self.health() {
+            Some(health) => format!("{} (health: {})", self.status(), health),
+            None => self.status(),
+        }
+    }
+}
+
+/// The names serde knows the variants of `E` by, which for the generated
+/// status enums are the statuses as the API reports them. Deserializing from
+/// something that only records the variants it's asked for beats keeping a
+/// copy of every status which drifts whenever the API adds one.
+fn variants<E: DeserializeOwned>() -> &'static [&'static str] {
+    struct Variants<'a>(&'a mut &'static [&'static str]);
+
+    impl<'de, 'a> serde::Deserializer<'de> for Variants<'a> {
+        type Error = serde::de::value::Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+            Err(serde::de::Error::custom("not an enum"))
+        }
+
+        fn deserialize_enum<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            variants: &'static [&'static str],
+            _: V,
+        ) -> Result<V::Value, Self::Error> {
+            *self.0 = variants;
+            Err(serde::de::Error::custom("only the variants are needed"))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf option unit unit_struct newtype_struct seq tuple
+            tuple_struct map struct identifier ignored_any
+        }
+    }
+
+    let mut names: &'static [&'static str] = &[];
+    let _ = E::deserialize(Variants(&mut names));
+    names
+}
+
+impl Waitable for Cluster {
+    type Status = ClusterStatus;
+    const READY: &'static [&'static str] = &["available"];
+    const FAILED: &'static [&'static str] = &["defunct", "inconsistent"];
+
+    fn status(&self) -> String {
+        self.status.to_string()
+    }
+
+    fn health(&self) -> Option<&Health> {
+        Some(&self.health)
+    }
+}
+
+// Shared clusters go through the same statuses as dedicated ones, but don't
+// report their health.
+impl Waitable for SharedCluster {
+    type Status = ClusterStatus;
+    const READY: &'static [&'static str] = Cluster::READY;
+    const FAILED: &'static [&'static str] = Cluster::FAILED;
+
+    fn status(&self) -> String {
+        self.status.to_string()
+    }
+}
+
+impl Waitable for Network {
+    type Status = NetworkStatus;
+    const READY: &'static [&'static str] = &["available"];
+    const FAILED: &'static [&'static str] = &["defunct"];
+
+    fn status(&self) -> String {
+        self.status.to_string()
+    }
+}
+
+// A peering stays initiated until the peer network accepts it, which is
+// outside of our control, so that counts as ready as well.
+impl Waitable for Peering {
+    type Status = PeeringStatus;
+    const READY: &'static [&'static str] = &["initiated", "active"];
+    const FAILED: &'static [&'static str] = &["defunct"];
+
+    fn status(&self) -> String {
+        self.status.to_string()
+    }
+}
+
+impl Waitable for Acl {
+    type Status = AclStatus;
+    const READY: &'static [&'static str] = &["active"];
+    const FAILED: &'static [&'static str] = &[];
+
+    fn status(&self) -> String {
+        self.status.to_string()
+    }
+}
+
+impl Waitable for Backup {
+    type Status = BackupStatus;
+    const READY: &'static [&'static str] = &["available"];
+    const FAILED: &'static [&'static str] = &["defunct"];
+
+    fn status(&self) -> String {
+        self.status.to_string()
+    }
+}
+
+/// How long a change may take to show up in the status of a resource, see
+/// `Target::after_change`.
+pub const CHANGE_GRACE: Duration = Duration::from_secs(60);
+
+/// The state a resource is expected to end up in.
+#[derive(Clone, Debug)]
+pub struct Target {
+    /// Any of these statuses will do.
+    pub statuses: Vec<String>,
+    /// If set, the resource must also report this health.
+    pub health: Option<Health>,
+    /// If set, the target only counts once the resource was seen in another
+    /// status, or once this much time has passed. This is for calls changing
+    /// a resource which is already in the target state, such as resizing an
+    /// available cluster, which the API may keep reporting for a moment.
+    pub change_grace: Option<Duration>,
+}
+
+impl Target {
+    /// The resource is ready to be used.
+    pub fn ready<W: Waitable>() -> Self {
+        Target::statuses(W::READY.iter().map(|s| s.to_string()).collect())
+    }
+
+    pub fn statuses(statuses: Vec<String>) -> Self {
+        Target {
+            statuses,
+            health: None,
+            change_grace: None,
+        }
+    }
+
+    /// Waits for the resource to go through a change first, see
+    /// `change_grace`.
+    pub fn after_change(self) -> Self {
+        Target {
+            change_grace: Some(CHANGE_GRACE),
+            ..self
+        }
+    }
+
+    /// A cluster which is running and healthy.
+    pub fn healthy_cluster() -> Self {
+        Target {
+            health: Some(Health::Ok),
+            ..Target::ready::<Cluster>()
+        }
+    }
+
+    /// A cluster whose compute has been shut down but whose disks remain.
+    pub fn stopped_cluster() -> Self {
+        Target::statuses(vec![
+            ClusterStatus::DisksAvailable.to_string(),
+            ClusterStatus::InstancesDeleted.to_string(),
+        ])
+    }
+
+    pub fn check<W: Waitable>(&self, value: &W) -> Progress {
+        let status = value.status();
+
+        let health_reached = match (&self.health, value.health()) {
+            (Some(expected), Some(health)) => expected == health,
+            _ => true,
+        };
+
+        if self.statuses.contains(&status) && health_reached {
+            return Progress::Reached;
+        }
+
+        let deleted = status == "deleted" && !self.statuses.iter().any(|s| s == "deleted");
+
+        if deleted || W::FAILED.contains(&status.as_str()) {
+            Progress::Failed(value.describe())
+        } else {
+            Progress::Pending(value.describe())
+        }
+    }
+}
+
+async fn wait_for<W, F, Fut, P>(
+    options: &WaitOptions,
+    target: &Target,
+    fetch: F,
+    mut on_poll: P,
+) -> Result<W, WaitError>
+where
+    W: Waitable,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = crate::Result<W>>,
+    P: FnMut(&W),
+{
+    let started = Instant::now();
+    let mut changed = false;
+
+    poll(options, fetch, |value| {
+        on_poll(value);
+        match target.check(value) {
+            Progress::Reached => match target.change_grace {
+                Some(grace) if !changed && started.elapsed() < grace => {
+                    Progress::Pending(value.describe())
+                }
+                _ => Progress::Reached,
+            },
+            progress => {
+                changed = true;
+                progress
+            }
+        }
+    })
+    .await
+}
+
+/// Polls a cluster until it matches `target`. `on_poll` is called with every
+/// version of the cluster which is fetched, which is handy to report progress.
+pub async fn wait_for_cluster<P>(
+    client: &crate::Client,
+    organization_id: OrgId,
+    project_id: ProjectId,
+    cluster_id: ClusterId,
+    target: &Target,
+    options: &WaitOptions,
+    on_poll: P,
+) -> Result<Cluster, WaitError>
+where
+    P: FnMut(&Cluster),
+{
+    wait_for(
+        options,
+        target,
+        || {
+            let organization_id = organization_id.clone();
+            let project_id = project_id.clone();
+            let cluster_id = cluster_id.clone();
+            async move {
+                let resp =
+                    crate::mesdb::get_cluster(client, organization_id, project_id, cluster_id)
+                        .await?;
+                Ok(resp.cluster)
+            }
+        },
+        on_poll,
+    )
+    .await
+}
+
+/// Same as `wait_for_cluster` for shared clusters.
+pub async fn wait_for_shared_cluster<P>(
+    client: &crate::Client,
+    organization_id: OrgId,
+    project_id: ProjectId,
+    cluster_id: ClusterId,
+    target: &Target,
+    options: &WaitOptions,
+    on_poll: P,
+) -> Result<SharedCluster, WaitError>
+where
+    P: FnMut(&SharedCluster),
+{
+    wait_for(
+        options,
+        target,
+        || {
+            let organization_id = organization_id.clone();
+            let project_id = project_id.clone();
+            let cluster_id = cluster_id.clone();
+            async move {
+                let resp = crate::mesdb::get_shared_cluster(
+                    client,
+                    organization_id,
+                    project_id,
+                    cluster_id,
+                )
+                .await?;
+                Ok(resp.cluster)
+            }
+        },
+        on_poll,
+    )
+    .await
+}
+
+/// Same as `wait_for_cluster` for networks.
+pub async fn wait_for_network<P>(
+    client: &crate::Client,
+    organization_id: OrgId,
+    project_id: ProjectId,
+    network_id: NetworkId,
+    target: &Target,
+    options: &WaitOptions,
+    on_poll: P,
+) -> Result<Network, WaitError>
+where
+    P: FnMut(&Network),
+{
+    wait_for(
+        options,
+        target,
+        || {
+            let organization_id = organization_id.clone();
+            let project_id = project_id.clone();
+            let network_id = network_id.clone();
+            async move {
+                let resp =
+                    crate::infra::get_network(client, organization_id, project_id, network_id)
+                        .await?;
+                Ok(resp.network)
+            }
+        },
+        on_poll,
+    )
+    .await
+}
+
+/// Same as `wait_for_cluster` for peerings.
+pub async fn wait_for_peering<P>(
+    client: &crate::Client,
+    organization_id: OrgId,
+    project_id: ProjectId,
+    peering_id: PeeringId,
+    target: &Target,
+    options: &WaitOptions,
+    on_poll: P,
+) -> Result<Peering, WaitError>
+where
+    P: FnMut(&Peering),
+{
+    wait_for(
+        options,
+        target,
+        || {
+            let organization_id = organization_id.clone();
+            let project_id = project_id.clone();
+            let peering_id = peering_id.clone();
+            async move {
+                let resp =
+                    crate::infra::get_peering(client, organization_id, project_id, peering_id)
+                        .await?;
+                Ok(resp.peering)
+            }
+        },
+        on_poll,
+    )
+    .await
+}
+
+/// Same as `wait_for_cluster` for ACLs.
+pub async fn wait_for_acl<P>(
+    client: &crate::Client,
+    organization_id: OrgId,
+    project_id: ProjectId,
+    acl_id: AclId,
+    target: &Target,
+    options: &WaitOptions,
+    on_poll: P,
+) -> Result<Acl, WaitError>
+where
+    P: FnMut(&Acl),
+{
+    wait_for(
+        options,
+        target,
+        || {
+            let organization_id = organization_id.clone();
+            let project_id = project_id.clone();
+            let acl_id = acl_id.clone();
+            async move {
+                let resp =
+                    crate::infra::get_acl(client, organization_id, project_id, acl_id).await?;
+                Ok(resp.acl)
+            }
+        },
+        on_poll,
+    )
+    .await
+}
+
+/// Same as `wait_for_cluster` for backups.
+pub async fn wait_for_backup<P>(
+    client: &crate::Client,
+    organization_id: OrgId,
+    project_id: ProjectId,
+    backup_id: BackupId,
+    target: &Target,
+    options: &WaitOptions,
+    on_poll: P,
+) -> Result<Backup, WaitError>
+where
+    P: FnMut(&Backup),
+{
+    wait_for(
+        options,
+        target,
+        || {
+            let organization_id = organization_id.clone();
+            let project_id = project_id.clone();
+            let backup_id = backup_id.clone();
+            async move {
+                let resp = crate::mesdb::get_backup(client, organization_id, project_id, backup_id)
+                    .await?;
+                Ok(resp.backup)
+            }
+        },
+        on_poll,
+    )
+    .await
+}
+
+/// Whether a job run which has ended went wrong. History statuses aren't an
+/// enum in the API, so this errs on the side of reporting a failure.
+pub fn job_run_failed(item: &HistoryItem) -> bool {
+    let status = item.status.to_lowercase();
+    ["fail", "error", "cancel", "abort"]
+        .iter()
+        .any(|word| status.contains(word))
+}
+
+/// Polls the history of a job until the run `history_id` has ended.
+pub async fn wait_for_job_run<P>(
+    client: &crate::Client,
+    organization_id: OrgId,
+    project_id: ProjectId,
+    job_id: JobId,
+    history_id: String,
+    options: &WaitOptions,
+    mut on_poll: P,
+) -> Result<HistoryItem, WaitError>
+where
+    P: FnMut(Option<&HistoryItem>),
+{
+    let item = poll(
+        options,
+        || {
+            let organization_id = organization_id.clone();
+            let project_id = project_id.clone();
+            let job_id = job_id.clone();
+            let history_id = history_id.clone();
+            async move {
+                let resp = crate::orchestrate::list_history(
+                    client,
+                    organization_id,
+                    project_id,
+                    Some(job_id),
+                )
+                .await?;
+                Ok(resp.items.into_iter().find(|item| item.id == history_id))
+            }
+        },
+        |item: &Option<HistoryItem>| {
+            on_poll(item.as_ref());
+            match item {
+                None => Progress::Pending("not started".to_string()),
+                Some(item) if item.end_time.is_none() => Progress::Pending(item.status.clone()),
+                Some(item) if job_run_failed(item) => {
+                    Progress::Failed(format!("{}: {}", item.status, item.details))
+                }
+                Some(_) => Progress::Reached,
+            }
+        },
+    )
+    .await?;
+
+    Ok(item.expect("a job run is only reached once it shows up in the history"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+
+    struct Fake(ClusterStatus);
+
+    impl Waitable for Fake {
+        type Status = ClusterStatus;
+        const READY: &'static [&'static str] = Cluster::READY;
+        const FAILED: &'static [&'static str] = Cluster::FAILED;
+
+        fn status(&self) -> String {
+            self.0.to_string()
+        }
+    }
+
+    fn options() -> WaitOptions {
+        WaitOptions {
+            timeout: Duration::from_secs(600),
+            initial_interval: Duration::from_secs(1),
+            max_interval: Duration::from_secs(1),
+        }
+    }
+
+    // Polls through `statuses` one at a time, sticking to the last one, and
+    // returns the outcome along with how many polls it took.
+    async fn wait(
+        target: &Target,
+        statuses: Vec<ClusterStatus>,
+    ) -> (Result<String, String>, usize) {
+        let statuses = RefCell::new(statuses.into_iter().collect::<VecDeque<_>>());
+        let mut polls = 0;
+        let result = wait_for(
+            &options(),
+            target,
+            || {
+                let mut statuses = statuses.borrow_mut();
+                let status = if statuses.len() > 1 {
+                    statuses.pop_front().unwrap()
+                } else {
+                    statuses[0].clone()
+                };
+                async move { Ok(Fake(status)) }
+            },
+            |_: &Fake| polls += 1,
+        )
+        .await;
+        let result = result.map(|f| f.status()).map_err(|e| e.to_string());
+        (result, polls)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn poll_stops_at_the_first_ready_status() {
+        use ClusterStatus::*;
+        let (result, polls) = wait(
+            &Target::ready::<Fake>(),
+            vec![Provisioning, Provisioning, Available, Restarting],
+        )
+        .await;
+        assert_eq!(result, Ok("available".to_string()));
+        assert_eq!(polls, 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn poll_fails_on_a_failure_status() {
+        use ClusterStatus::*;
+        let (result, polls) = wait(&Target::ready::<Fake>(), vec![Resizing, Defunct]).await;
+        assert_eq!(
+            result,
+            Err("Resource entered failure state \"defunct\"".to_string())
+        );
+        assert_eq!(polls, 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn poll_after_change_skips_the_status_from_before_the_call() {
+        use ClusterStatus::*;
+        let (result, polls) = wait(
+            &Target::ready::<Fake>().after_change(),
+            vec![Available, Available, Resizing, Resizing, Available],
+        )
+        .await;
+        assert_eq!(result, Ok("available".to_string()));
+        assert_eq!(polls, 5);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn poll_after_change_gives_up_on_seeing_the_change_after_the_grace() {
+        let (result, polls) = wait(
+            &Target::ready::<Fake>().after_change(),
+            vec![ClusterStatus::Available],
+        )
+        .await;
+        assert_eq!(result, Ok("available".to_string()));
+        assert_eq!(polls as u64, CHANGE_GRACE.as_secs() + 1);
+    }
+
+    fn api_error(status: u16) -> crate::Error {
+        crate::Error::ApiResponse(Box::new(esc_client_base::errors::ApiResponseError {
+            status_code: reqwest::StatusCode::from_u16(status).unwrap(),
+            problem_details: esc_client_base::errors::ProblemDetails {
+                detail: None,
+                fields: None,
+                instance: String::new(),
+                status: status as i32,
+                title: "failed".to_string(),
+                _type: String::new(),
+            },
+        }))
+    }
+
+    // Polls until ready, failing with the API error `status` on the first poll.
+    async fn wait_failing_once(status: u16) -> (Result<String, String>, usize) {
+        let mut polls = 0;
+        let result = poll(
+            &options(),
+            || {
+                polls += 1;
+                let result = if polls == 1 {
+                    Err(api_error(status))
+                } else {
+                    Ok(Fake(ClusterStatus::Available))
+                };
+                async move { result }
+            },
+            |value: &Fake| Target::ready::<Fake>().check(value),
+        )
+        .await;
+        let result = result.map(|f| f.status()).map_err(|e| e.to_string());
+        (result, polls)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn poll_retries_after_a_server_error() {
+        let (result, polls) = wait_failing_once(502).await;
+        assert_eq!(result, Ok("available".to_string()));
+        assert_eq!(polls, 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn poll_stops_at_a_client_error() {
+        let (result, polls) = wait_failing_once(404).await;
+        assert!(result.unwrap_err().contains("404"));
+        assert_eq!(polls, 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn poll_gives_up_on_server_errors_at_the_deadline() {
+        let started = Instant::now();
+        let result = poll(
+            &options(),
+            || async { Err::<Fake, _>(api_error(503)) },
+            |value: &Fake| Target::ready::<Fake>().check(value),
+        )
+        .await;
+        assert!(matches!(result, Err(WaitError::Api(_))));
+        assert_eq!(started.elapsed(), options().timeout);
+    }
+
+    #[test]
+    fn statuses_come_from_the_generated_enum() {
+        let statuses = Cluster::statuses();
+        assert!(statuses.contains(&"disks available"));
+        assert!(statuses.contains(&"deleting"));
+        assert_eq!(
+            Network::statuses(),
+            &[
+                "provisioning",
+                "defunct",
+                "available",
+                "deleting",
+                "deleted"
+            ]
+        );
+    }
+}
@@ -0,0 +1,73 @@
+use esc_api::integrate::{
+    CreateAwsCloudWatchLogsIntegrationData, CreateIntegrationData, CreateIntegrationRequest,
+    CreatePagerDutyIntegrationData, CreateSlackIntegrationData,
+};
+use esc_api::mesdb::{Credentials, GetSharedClusterInitialCredentialsResponse};
+
+const SECRET: &str = "very-secret";
+
+fn redact<T: serde::Serialize>(body: &T) -> String {
+    esc_api::redactor().redact(&serde_json::to_string(body).unwrap())
+}
+
+fn create_integration(data: CreateIntegrationData) -> String {
+    redact(&CreateIntegrationRequest {
+        data,
+        description: "alerts".to_string(),
+    })
+}
+
+fn assert_redacted(body: &str, kept: &str) {
+    assert!(!body.contains(SECRET), "leaked secret: {}", body);
+    assert!(body.contains("<redacted>"), "nothing redacted: {}", body);
+    assert!(body.contains(kept), "redacted too much: {}", body);
+}
+
+#[test]
+fn test_aws_cloud_watch_keys_are_redacted() {
+    let body = create_integration(CreateIntegrationData::AwsCloudWatchLogs(
+        CreateAwsCloudWatchLogsIntegrationData {
+            access_key_id: SECRET.to_string(),
+            cluster_ids: None,
+            group_name: "esdb".to_string(),
+            region: "eu-west-1".to_string(),
+            secret_access_key: SECRET.to_string(),
+            source: "logs".to_string(),
+        },
+    ));
+    assert_redacted(&body, "eu-west-1");
+}
+
+#[test]
+fn test_slack_token_is_redacted() {
+    let body = create_integration(CreateIntegrationData::Slack(CreateSlackIntegrationData {
+        channel_id: "C012".to_string(),
+        token: SECRET.to_string(),
+        source: None,
+    }));
+    assert_redacted(&body, "C012");
+}
+
+#[test]
+fn test_pager_duty_auth_token_is_redacted() {
+    let body = create_integration(CreateIntegrationData::PagerDuty(
+        CreatePagerDutyIntegrationData {
+            auth_token: SECRET.to_string(),
+            service: "PSERVICE".to_string(),
+            user: "PUSER".to_string(),
+            source: None,
+        },
+    ));
+    assert_redacted(&body, "PSERVICE");
+}
+
+#[test]
+fn test_shared_cluster_password_is_redacted() {
+    let body = redact(&GetSharedClusterInitialCredentialsResponse {
+        credentials: Some(Credentials {
+            username: "admin".to_string(),
+            password: SECRET.to_string(),
+        }),
+    });
+    assert_redacted(&body, "admin");
+}
@@ -1,5 +1,6 @@
 use super::api_response_error::ApiResponseError;
 use super::communication_error::CommunicationError;
+use super::not_sent::NotSent;
 
 /// Represents any error that can come back from an API operation
 pub enum EscError {
@@ -55,6 +56,18 @@ impl EscError {
             EscError::Other(_) => Err(self),
         }
     }
+
+    /// The curl command of a request which wasn't sent since the sender is in
+    /// `SendMode::Curl`.
+    pub fn not_sent(&self) -> Option<&str> {
+        match self {
+            EscError::Other(err) => err
+                .source
+                .downcast_ref::<NotSent>()
+                .map(|not_sent| not_sent.0.as_str()),
+            EscError::ApiResponse(_) => None,
+        }
+    }
 }
 
 impl From<CommunicationError> for EscError {
@@ -70,7 +83,6 @@ impl From<ApiResponseError> for EscError {
 }
 
 impl PartialEq for EscError {
-    #[must_use]
     fn eq(&self, other: &EscError) -> bool {
         match self {
             EscError::ApiResponse(err) => match other {
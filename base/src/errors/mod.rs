@@ -1,10 +1,12 @@
 mod api_response_error;
 mod communication_error;
 mod esc_error;
+mod not_sent;
 mod problem_details;
 
 pub use api_response_error::ApiResponseError;
 pub use communication_error::CommunicationError;
 pub use esc_error::EscError;
 pub use esc_error::Result;
+pub use not_sent::NotSent;
 pub use problem_details::ProblemDetails;
@@ -0,0 +1,14 @@
+/// Why a request failed when the sender is in `SendMode::Curl`: it wasn't
+/// sent, and this holds the equivalent curl command instead. It's the source
+/// of the `EscError::Other` such requests fail with, which
+/// `EscError::not_sent` reads it back from.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NotSent(pub String);
+
+impl std::fmt::Display for NotSent {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "Request not sent: {}", self.0)
+    }
+}
+
+impl std::error::Error for NotSent {}
@@ -204,7 +204,7 @@ pub async fn refresh(
 
     let req = client.post(url.as_str()).form(&form);
 
-    debug!("Token refresh on : {:?}", req);
+    debug!("Token refresh on {}", url);
 
     let resp = req.send().await?;
 
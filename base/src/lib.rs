@@ -16,7 +16,9 @@ pub use client::Client;
 pub use errors::EscError as Error;
 pub use errors::Result;
 pub use identity::tokens::Token;
+pub use requests::Redactor;
 pub use requests::RequestObserver;
 pub use requests::RequestSender;
+pub use requests::SendMode;
 pub use utils::urlencode;
 pub use version::user_agent;
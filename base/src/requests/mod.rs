@@ -1,5 +1,9 @@
 mod observer;
+mod redactor;
 mod sender;
+mod synthetic;
 
 pub use observer::Observer as RequestObserver;
+pub use redactor::Redactor;
+pub use sender::SendMode;
 pub use sender::Sender as RequestSender;
@@ -2,4 +2,8 @@
 pub trait Observer {
     fn on_request(&self, method: &str, url: &str, body: &str);
     fn on_response(&self, status: &str, body: &str);
+
+    /// Called instead of the above for requests `SendMode::DryRun` didn't
+    /// send.
+    fn on_skipped(&self, _method: &str, _url: &str, _body: &str) {}
 }
@@ -0,0 +1,61 @@
+const REDACTED: &str = "<redacted>";
+
+/// Replaces the values of sensitive JSON fields in request and response
+/// bodies before observers see them. Fields are matched by name at any depth,
+/// and their whole value is replaced whatever its type, so secrets sent as
+/// objects or arrays don't leak through their leaves. The default redactor
+/// has no fields and leaves bodies untouched.
+#[derive(Clone, Debug, Default)]
+pub struct Redactor {
+    fields: Vec<&'static str>,
+}
+
+impl Redactor {
+    pub fn new<I: IntoIterator<Item = &'static str>>(fields: I) -> Self {
+        Self {
+            fields: fields.into_iter().collect(),
+        }
+    }
+
+    fn redact_value(&self, value: &mut serde_json::Value) -> bool {
+        let mut redacted = false;
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, field) in map.iter_mut() {
+                    if self.fields.contains(&key.as_str()) {
+                        *field = serde_json::Value::String(REDACTED.to_string());
+                        redacted = true;
+                    } else {
+                        redacted |= self.redact_value(field);
+                    }
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for item in items.iter_mut() {
+                    redacted |= self.redact_value(item);
+                }
+            }
+            _ => {}
+        }
+        redacted
+    }
+
+    /// Returns `body` with the sensitive fields redacted. Bodies which aren't
+    /// JSON or hold no sensitive fields are returned as they are.
+    pub fn redact(&self, body: &str) -> String {
+        if self.fields.is_empty() {
+            return body.to_string();
+        }
+
+        let mut value: serde_json::Value = match serde_json::from_str(body) {
+            Ok(value) => value,
+            Err(_) => return body.to_string(),
+        };
+
+        if self.redact_value(&mut value) {
+            serde_json::to_string(&value).unwrap_or_else(|_| REDACTED.to_string())
+        } else {
+            body.to_string()
+        }
+    }
+}
@@ -4,20 +4,83 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 
 use super::observer::Observer;
-use crate::errors::{ApiResponseError, CommunicationError, EscError, ProblemDetails, Result};
+use super::redactor::Redactor;
+use super::synthetic::Synthetic;
+use crate::errors::{
+    ApiResponseError, CommunicationError, EscError, NotSent, ProblemDetails, Result,
+};
+
+/// What the sender does with requests.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SendMode {
+    #[default]
+    Send,
+    /// Sends nothing, every request fails with an error whose
+    /// `EscError::not_sent` is the equivalent curl command instead. The access token is replaced by
+    /// `$ESC_TOKEN`, and the body goes through the redactor, so it only
+    /// replays as is when the redactor has no fields.
+    Curl,
+    /// Only sends requests which read, such as GETs. Others are reported to
+    /// the observer's `on_skipped` and answered with a synthetic result, ids
+    /// being "dry-run".
+    DryRun,
+}
+
+fn is_read(method: &reqwest::Method) -> bool {
+    matches!(
+        *method,
+        reqwest::Method::GET | reqwest::Method::HEAD | reqwest::Method::OPTIONS
+    )
+}
+
+fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+fn curl(authorization_header: &str, method: &reqwest::Method, url: &str, body: &str) -> String {
+    let scheme = authorization_header.split(' ').next().unwrap_or("Bearer");
+    let mut args = vec![
+        format!("curl -X {} {}", method, shell_quote(url)),
+        format!("-H \"Authorization: {} $ESC_TOKEN\"", scheme),
+        "-H 'Content-Type: application/json'".to_string(),
+        "-H 'Accept: application/json'".to_string(),
+        format!(
+            "-H {}",
+            shell_quote(&format!("User-Agent: {}", crate::version::user_agent()))
+        ),
+    ];
+    if !body.is_empty() {
+        args.push(format!("-d {}", shell_quote(body)));
+    }
+    args.join(" \\\n  ")
+}
 
 /// Wraps a reqwest::Client and an observer with a helper function which accepts
 /// a token to make authenticated requests. This makes it possible to observe
-/// all parts of the request / response apart from authentication.
+/// all parts of the request / response apart from authentication. Bodies go
+/// through the redactor before the observer sees them. Empty response bodies
+/// are read as JSON `null`.
 #[derive(Clone)]
 pub struct Sender {
     pub client: reqwest::Client,
     pub observer: Option<Arc<dyn Observer + Send + Sync>>,
+    pub redactor: Redactor,
+    pub mode: SendMode,
 }
 
 impl Sender {
     pub fn new(client: reqwest::Client, observer: Option<Arc<dyn Observer + Send + Sync>>) -> Self {
-        Self { client, observer }
+        Self {
+            client,
+            observer,
+            redactor: Redactor::default(),
+            mode: SendMode::Send,
+        }
+    }
+
+    /// Redacts the bodies the observer sees with `redactor`.
+    pub fn with_redactor(self, redactor: Redactor) -> Self {
+        Self { redactor, ..self }
     }
 
     pub async fn send_request<B: Serialize + ?Sized, R: DeserializeOwned>(
@@ -28,15 +91,45 @@ impl Sender {
         body: Option<&B>,
         use_return_value: Option<R>,
     ) -> Result<R> {
-        if let Some(o) = &self.observer {
-            let body_string: String = match body {
-                Some(b) => match serde_json::to_string(b) {
-                    Ok(s) => s,
-                    _ => "<err!>".to_string(),
-                },
-                None => "".to_string(),
+        let body_string = || match body {
+            Some(b) => match serde_json::to_string(b) {
+                Ok(s) => s,
+                _ => "<err!>".to_string(),
+            },
+            None => "".to_string(),
+        };
+
+        if let SendMode::Curl = self.mode {
+            return Err(EscError::Other(CommunicationError {
+                debug: format!("method={}, url={}", method, url),
+                message: "Request not sent".to_string(),
+                source: Box::new(NotSent(curl(
+                    authorization_header,
+                    &method,
+                    &url,
+                    &self.redactor.redact(&body_string()),
+                ))),
+            }));
+        }
+
+        if self.mode == SendMode::DryRun && !is_read(&method) {
+            if let Some(o) = &self.observer {
+                o.on_skipped(method.as_str(), &url, &self.redactor.redact(&body_string()));
+            }
+            return match use_return_value {
+                Some(r) => Ok(r),
+                None => R::deserialize(Synthetic).map_err(|err| {
+                    EscError::Other(CommunicationError {
+                        debug: format!("method={}, url={}", method, url),
+                        message: "Error making up a result for the dry run".to_string(),
+                        source: Box::new(err),
+                    })
+                }),
             };
-            o.on_request(method.as_str(), &url, &body_string);
+        }
+
+        if let Some(o) = &self.observer {
+            o.on_request(method.as_str(), &url, &self.redactor.redact(&body_string()));
         }
 
         let req = self
@@ -70,7 +163,7 @@ impl Sender {
                         message: "Error reading request response".to_string(),
                         source: Box::new(r),
                     })?;
-                    o.on_response(status_code.as_str(), &text);
+                    o.on_response(status_code.as_str(), &self.redactor.redact(&text));
                     let r: R = match use_return_value {
                         Some(r) => r,
                         None => {
@@ -83,13 +176,20 @@ impl Sender {
                             // should only be applied to problematic models as
                             // it's a little inefficient.
                             let from_str_result: serde_json::Result<serde_json::Value> =
-                                serde_json::from_str(&text);
+                                if text.is_empty() {
+                                    Ok(serde_json::Value::Null)
+                                } else {
+                                    serde_json::from_str(&text)
+                                };
                             match from_str_result {
                                 Err(err) => {
                                     return Err(EscError::Other(CommunicationError {
                                         debug: format!(
                                             "method={}, url={}, status code={} response text={}",
-                                            method, url, status_code, text
+                                            method,
+                                            url,
+                                            status_code,
+                                            self.redactor.redact(&text)
                                         ),
                                         message: "Error deserializing response text".to_string(),
                                         source: Box::new(err),
@@ -101,7 +201,7 @@ impl Sender {
                                     match from_value_result {
                                         Err(err) => {
                                             return Err(EscError::Other(CommunicationError{
-                                                debug: format!("method={}, url={}, status code={} response text={}", method, url, status_code, text),
+                                                debug: format!("method={}, url={}, status code={} response text={}", method, url, status_code, self.redactor.redact(&text)),
                                                 message: "Error deserializing response text into specific client type".to_string(),
                                                 source: Box::new(err),
                                             }));
@@ -118,16 +218,26 @@ impl Sender {
                     let r: R = match use_return_value {
                         Some(r) => r,
                         None => {
-                            let d: serde_json::Value =
-                                resp.json().await.map_err(|s| CommunicationError {
+                            let bytes = resp.bytes().await.map_err(|s| CommunicationError {
+                                debug: format!(
+                                    "method={}, url={}, status code={}",
+                                    method, url, status_code
+                                ),
+                                message: "Error reading the request response".to_string(),
+                                source: Box::new(s),
+                            })?;
+                            let d: serde_json::Value = if bytes.is_empty() {
+                                serde_json::Value::Null
+                            } else {
+                                serde_json::from_slice(&bytes).map_err(|s| CommunicationError {
                                     debug: format!(
                                         "method={}, url={}, status code={}",
                                         method, url, status_code
                                     ),
-                                    message: "Error reading or deserializing the request response"
-                                        .to_string(),
+                                    message: "Error deserializing the request response".to_string(),
                                     source: Box::new(s),
-                                })?;
+                                })?
+                            };
                             let r: R = serde_json::from_value(d).map_err(|s| CommunicationError {
                                 debug: format!(
                                     "method={}, url={}, status code={}",
@@ -155,11 +265,14 @@ impl Sender {
                     message: "Error reading request response".to_string(),
                     source: Box::new(r),
                 })?;
-                o.on_response(status_code.as_str(), &text);
+                o.on_response(status_code.as_str(), &self.redactor.redact(&text));
                 serde_json::from_str(&text).map_err(|s| CommunicationError {
                     debug: format!(
                         "method={}, url={}, status code={}, response text={}",
-                        method, url, status_code, text
+                        method,
+                        url,
+                        status_code,
+                        self.redactor.redact(&text)
                     ),
                     message: "Error deserializing response text into specific client type"
                         .to_string(),
@@ -0,0 +1,137 @@
+use esc_client_base::{Error, Redactor, RequestObserver, RequestSender, SendMode};
+use serde_derive::Deserialize;
+use std::sync::{Arc, Mutex};
+
+#[tokio::test]
+async fn test_curl_mode_returns_the_command_without_sending() {
+    let mut sender = RequestSender::new(reqwest::Client::new(), None)
+        .with_redactor(Redactor::new(vec!["password"]));
+    sender.mode = SendMode::Curl;
+    let request = serde_json::json!({
+        "description": "it's a cluster",
+        "password": "hunter2",
+    });
+
+    // Nothing listens on this port, so sending would fail differently.
+    let result: Result<(), Error> = sender
+        .send_request(
+            "Bearer very-secret",
+            reqwest::Method::POST,
+            "http://127.0.0.1:9/clusters".to_string(),
+            Some(&request),
+            None,
+        )
+        .await;
+
+    let err = result.unwrap_err();
+    let curl = err.not_sent().expect("the request not to be sent");
+    assert!(curl.starts_with("curl -X POST 'http://127.0.0.1:9/clusters'"));
+    assert!(curl.contains("-H \"Authorization: Bearer $ESC_TOKEN\""));
+    assert!(!curl.contains("very-secret"));
+    assert!(!curl.contains("hunter2"));
+    assert!(curl.contains(r#"it'\''s a cluster"#));
+}
+
+#[tokio::test]
+async fn test_curl_mode_keeps_secrets_without_redactor() {
+    let mut sender = RequestSender::new(reqwest::Client::new(), None);
+    sender.mode = SendMode::Curl;
+    let request = serde_json::json!({ "password": "hunter2" });
+
+    let result: Result<(), Error> = sender
+        .send_request(
+            "Bearer very-secret",
+            reqwest::Method::POST,
+            "http://127.0.0.1:9/clusters".to_string(),
+            Some(&request),
+            None,
+        )
+        .await;
+
+    let err = result.unwrap_err();
+    let curl = err.not_sent().expect("the request not to be sent");
+    assert!(curl.contains(r#"-d '{"password":"hunter2"}'"#), "{}", curl);
+    assert!(!curl.contains("very-secret"));
+}
+
+#[derive(Default)]
+struct Skipped(Mutex<Vec<String>>);
+
+impl RequestObserver for Skipped {
+    fn on_request(&self, _method: &str, _url: &str, _body: &str) {}
+
+    fn on_response(&self, _status: &str, _body: &str) {}
+
+    fn on_skipped(&self, method: &str, url: &str, body: &str) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("{} {} {}", method, url, body));
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+enum Status {
+    Provisioning,
+    Available,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Created {
+    id: String,
+    status: Status,
+    tags: Vec<String>,
+    retries: u32,
+    expires: Option<String>,
+}
+
+#[tokio::test]
+async fn test_dry_run_mode_skips_writes_with_a_synthetic_result() {
+    let skipped = Arc::new(Skipped::default());
+    let mut sender = RequestSender::new(reqwest::Client::new(), Some(skipped.clone()))
+        .with_redactor(Redactor::new(vec!["password"]));
+    sender.mode = SendMode::DryRun;
+    let request = serde_json::json!({ "password": "hunter2" });
+
+    // Nothing listens on this port, so sending would fail.
+    let created: Created = sender
+        .send_request(
+            "Bearer very-secret",
+            reqwest::Method::POST,
+            "http://127.0.0.1:9/clusters".to_string(),
+            Some(&request),
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(created.id, "dry-run");
+    assert_eq!(created.status, Status::Provisioning);
+    assert!(created.tags.is_empty());
+    assert_eq!(created.retries, 0);
+    assert_eq!(created.expires, None);
+
+    let skipped = skipped.0.lock().unwrap();
+    assert_eq!(skipped.len(), 1);
+    assert!(skipped[0].starts_with("POST http://127.0.0.1:9/clusters"));
+    assert!(!skipped[0].contains("hunter2"));
+}
+
+#[tokio::test]
+async fn test_dry_run_mode_still_sends_reads() {
+    let mut sender = RequestSender::new(reqwest::Client::new(), None);
+    sender.mode = SendMode::DryRun;
+
+    let result: Result<serde_json::Value, Error> = sender
+        .send_request::<(), _>(
+            "Bearer very-secret",
+            reqwest::Method::GET,
+            "http://127.0.0.1:9/clusters".to_string(),
+            None,
+            None,
+        )
+        .await;
+
+    assert!(matches!(result, Err(Error::Other(_))));
+}
@@ -0,0 +1,216 @@
+// Helpers for `esc api`, which sends requests to any endpoint through the same
+// client as the typed commands. Paths may hold `{org}` and `{project}`
+// placeholders, and bodies are built from `-f`/`-F` fields or read from a file.
+use serde_json::{Map, Value};
+use std::io::Read;
+use std::path::Path;
+
+/// Placeholders replaced by the organization id. The long forms match the
+/// API reference.
+pub const ORG_PLACEHOLDERS: &[&str] = &["{org}", "{organizationId}"];
+pub const PROJECT_PLACEHOLDERS: &[&str] = &["{project}", "{projectId}"];
+
+pub fn parse_method(src: &str) -> Result<reqwest::Method, String> {
+    reqwest::Method::from_bytes(src.to_uppercase().as_bytes())
+        .map_err(|_| format!("Invalid HTTP method \"{}\"", src))
+}
+
+pub fn has_placeholder(path: &str, placeholders: &[&str]) -> bool {
+    placeholders.iter().any(|p| path.contains(p))
+}
+
+pub fn fill(path: &str, placeholders: &[&str], value: &str) -> String {
+    placeholders
+        .iter()
+        .fold(path.to_string(), |path, p| path.replace(p, value))
+}
+
+/// A `key=value` field of the request body. Dotted keys such as
+/// `acl.description` set nested fields.
+#[derive(Clone, Debug)]
+pub struct Field {
+    key: String,
+    value: Value,
+}
+
+fn split_field(src: &str) -> Result<(String, String), String> {
+    match src.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("Invalid field \"{}\", expected KEY=VALUE", src)),
+    }
+}
+
+impl Field {
+    /// Parses a field whose value is always a string.
+    pub fn parse_raw(src: &str) -> Result<Field, String> {
+        let (key, value) = split_field(src)?;
+        Ok(Field {
+            key,
+            value: Value::String(value),
+        })
+    }
+
+    /// Parses a field whose value is JSON, such as a number, `true` or
+    /// `["10.0.0.0/16"]`, falling back to a string otherwise.
+    pub fn parse_typed(src: &str) -> Result<Field, String> {
+        let (key, value) = split_field(src)?;
+        let value = serde_json::from_str(&value).unwrap_or(Value::String(value));
+        Ok(Field { key, value })
+    }
+}
+
+fn insert(body: &mut Map<String, Value>, field: Field) -> Result<(), String> {
+    let mut keys: Vec<&str> = field.key.split('.').collect();
+    let last = keys.pop().expect("split always gives a key");
+    let mut object = body;
+    for key in keys {
+        let entry = object
+            .entry(key.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+        object = entry
+            .as_object_mut()
+            .ok_or_else(|| format!("Field \"{}\" conflicts with another field", field.key))?;
+    }
+    object.insert(last.to_string(), field.value);
+    Ok(())
+}
+
+/// The request body, read from the input file (or stdin for `-`) if there's
+/// one, or else made of the fields. Requests without either have no body.
+pub fn body(
+    fields: Vec<Field>,
+    input: Option<&Path>,
+) -> Result<Option<Value>, Box<dyn std::error::Error>> {
+    if let Some(input) = input {
+        let text = if input == Path::new("-") {
+            let mut text = String::new();
+            std::io::stdin().read_to_string(&mut text)?;
+            text
+        } else {
+            std::fs::read_to_string(input)
+                .map_err(|e| format!("Could not read {}: {}", input.display(), e))?
+        };
+        let body = serde_json::from_str(&text)
+            .map_err(|e| format!("{} isn't valid JSON: {}", input.display(), e))?;
+        return Ok(Some(body));
+    }
+    if fields.is_empty() {
+        return Ok(None);
+    }
+    let mut body = Map::new();
+    for field in fields {
+        insert(&mut body, field)?;
+    }
+    Ok(Some(Value::Object(body)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fields(raw: &[&str], typed: &[&str]) -> Option<Value> {
+        let fields = raw
+            .iter()
+            .map(|src| Field::parse_raw(src).unwrap())
+            .chain(typed.iter().map(|src| Field::parse_typed(src).unwrap()))
+            .collect();
+        body(fields, None).unwrap()
+    }
+
+    #[test]
+    fn test_methods() {
+        assert_eq!(parse_method("get"), Ok(reqwest::Method::GET));
+        assert_eq!(parse_method("Delete"), Ok(reqwest::Method::DELETE));
+        assert_eq!(parse_method("PATCH"), Ok(reqwest::Method::PATCH));
+        assert_eq!(
+            parse_method("not a method"),
+            Err("Invalid HTTP method \"not a method\"".to_string())
+        );
+    }
+
+    #[test]
+    fn test_placeholders() {
+        let path = "/mesdb/v1/organizations/{org}/projects/{projectId}/clusters";
+        assert!(has_placeholder(path, ORG_PLACEHOLDERS));
+        assert!(has_placeholder(path, PROJECT_PLACEHOLDERS));
+        assert!(!has_placeholder(
+            "/resources/v1/organizations",
+            ORG_PLACEHOLDERS
+        ));
+
+        let path = fill(path, ORG_PLACEHOLDERS, "org-1");
+        let path = fill(&path, PROJECT_PLACEHOLDERS, "project-1");
+        assert_eq!(
+            path,
+            "/mesdb/v1/organizations/org-1/projects/project-1/clusters"
+        );
+        assert_eq!(
+            fill("/{organizationId}/{org}", ORG_PLACEHOLDERS, "o"),
+            "/o/o"
+        );
+    }
+
+    #[test]
+    fn test_raw_fields_are_strings() {
+        assert_eq!(
+            fields(&["diskSizeGb=16", "protected=true", "note=a=b"], &[]),
+            Some(json!({"diskSizeGb": "16", "protected": "true", "note": "a=b"}))
+        );
+    }
+
+    #[test]
+    fn test_typed_fields_are_json() {
+        assert_eq!(
+            fields(
+                &[],
+                &[
+                    "diskSizeGb=16",
+                    "protected=true",
+                    "cidrBlocks=[\"10.0.0.0/16\"]",
+                    "description=not json",
+                ]
+            ),
+            Some(json!({
+                "diskSizeGb": 16,
+                "protected": true,
+                "cidrBlocks": ["10.0.0.0/16"],
+                "description": "not json",
+            }))
+        );
+    }
+
+    #[test]
+    fn test_dotted_keys_nest() {
+        assert_eq!(
+            fields(
+                &["acl.description=office"],
+                &["acl.cidrBlocks=[]", "size=1"]
+            ),
+            Some(json!({
+                "acl": {"description": "office", "cidrBlocks": []},
+                "size": 1,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_field_errors() {
+        assert!(Field::parse_raw("description").is_err());
+        assert!(Field::parse_typed("=16").is_err());
+
+        let fields = vec![
+            Field::parse_raw("acl=office").unwrap(),
+            Field::parse_raw("acl.description=office").unwrap(),
+        ];
+        assert_eq!(
+            body(fields, None).unwrap_err().to_string(),
+            "Field \"acl.description\" conflicts with another field"
+        );
+    }
+
+    #[test]
+    fn test_no_fields_means_no_body() {
+        assert_eq!(fields(&[], &[]), None);
+    }
+}
@@ -0,0 +1,189 @@
+// A small on-disk cache of recently listed resources, kept in ~/.esc/cache and
+// keyed by environment, profile, organization and project. It lets the resolver skip a
+// round trip for values it has already seen and lets shell completion offer
+// resource ids without talking to the API.
+use crate::resolve::{Candidate, Kind, Scope};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// How long a background refresh is given before another one may start.
+pub const REFRESH_TIMEOUT: Duration = Duration::from_secs(30);
+
+lazy_static! {
+    pub static ref CACHE_DIR: PathBuf = crate::config::ESC_DIR.join("cache");
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Entry {
+    pub updated: u64,
+    pub candidates: Vec<Candidate>,
+}
+
+impl Entry {
+    pub fn is_stale(&self) -> bool {
+        self.is_stale_at(now())
+    }
+
+    fn is_stale_at(&self, now: u64) -> bool {
+        now.saturating_sub(self.updated) > CACHE_TTL.as_secs()
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// Keeps ids and host names from escaping the cache directory.
+fn sanitize(segment: &str) -> String {
+    segment
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn path(base_url: &str, kind: Kind, scope: &Scope) -> PathBuf {
+    // Profiles may belong to different accounts, which see different
+    // organizations under the same names.
+    let profile = crate::config::SETTINGS
+        .get_current_profile()
+        .map(|p| p.name.as_str())
+        .unwrap_or("default");
+
+    CACHE_DIR.join(key(base_url, profile, kind, scope))
+}
+
+// Where the entry lives within the cache directory.
+fn key(base_url: &str, profile: &str, kind: Kind, scope: &Scope) -> PathBuf {
+    let env = url::Url::parse(base_url)
+        .ok()
+        .and_then(|url| url.host_str().map(|host| host.to_string()))
+        .unwrap_or_else(|| base_url.to_string());
+
+    let mut path = PathBuf::from(sanitize(&env)).join(sanitize(profile));
+
+    if let Some(org_id) = scope.org_id.as_ref() {
+        path = path.join(sanitize(org_id.as_ref()));
+    }
+
+    if let Some(project_id) = scope.project_id.as_ref() {
+        path = path.join(sanitize(project_id.as_ref()));
+    }
+
+    path.join(format!("{}.json", kind))
+}
+
+/// Records that a refresh of the entry is starting. Returns false if another
+/// one started less than `REFRESH_TIMEOUT` ago, so completing repeatedly
+/// doesn't start a process every time.
+pub fn claim_refresh(base_url: &str, kind: Kind, scope: &Scope) -> bool {
+    let path = path(base_url, kind, scope).with_extension("refreshing");
+
+    let started = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|text| text.trim().parse::<u64>().ok());
+    if let Some(started) = started {
+        if now().saturating_sub(started) < REFRESH_TIMEOUT.as_secs() {
+            return false;
+        }
+    }
+
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(&path, now().to_string()));
+    if let Err(err) = result {
+        log::debug!("Could not write {}: {}", path.display(), err);
+    }
+    true
+}
+
+pub async fn load(base_url: &str, kind: Kind, scope: &Scope) -> Option<Entry> {
+    let bytes = tokio::fs::read(path(base_url, kind, scope)).await.ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+pub async fn store(base_url: &str, kind: Kind, scope: &Scope, candidates: &[Candidate]) {
+    let path = path(base_url, kind, scope);
+    let entry = Entry {
+        updated: now(),
+        candidates: candidates.to_vec(),
+    };
+
+    let result: Result<(), Box<dyn std::error::Error>> = async {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&path, serde_json::to_vec(&entry)?).await?;
+        Ok(())
+    }
+    .await;
+
+    if let Err(err) = result {
+        log::debug!("Could not write cache file {}: {}", path.display(), err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use esc_api::resources::{OrganizationId, ProjectId};
+
+    #[test]
+    fn test_entries_go_stale_after_the_ttl() {
+        let entry = Entry {
+            updated: 1_000,
+            candidates: vec![],
+        };
+        let ttl = CACHE_TTL.as_secs();
+        assert!(!entry.is_stale_at(1_000));
+        assert!(!entry.is_stale_at(1_000 + ttl));
+        assert!(entry.is_stale_at(1_000 + ttl + 1));
+        // A clock going backwards doesn't make the entry stale.
+        assert!(!entry.is_stale_at(0));
+    }
+
+    #[test]
+    fn test_entries_are_keyed_by_env_profile_org_and_project() {
+        let org_id = OrganizationId("org-1".to_string());
+        let project_id = ProjectId("project-1".to_string());
+        let base_url = "https://api.eventstore.cloud";
+
+        assert_eq!(
+            key(base_url, "default", Kind::Organization, &Scope::default()),
+            PathBuf::from("api_eventstore_cloud/default/organization.json")
+        );
+        assert_eq!(
+            key(base_url, "work", Kind::Project, &Scope::org(&org_id)),
+            PathBuf::from("api_eventstore_cloud/work/org-1/project.json")
+        );
+        assert_eq!(
+            key(
+                "https://api.staging.example:8443",
+                "default",
+                Kind::Cluster,
+                &Scope::project(&org_id, &project_id)
+            ),
+            PathBuf::from("api_staging_example/default/org-1/project-1/cluster.json")
+        );
+    }
+
+    #[test]
+    fn test_keys_stay_inside_the_cache_dir() {
+        let org_id = OrganizationId("../../etc".to_string());
+        assert_eq!(
+            key("not a url", "../x", Kind::Project, &Scope::org(&org_id)),
+            PathBuf::from("not_a_url/___x/______etc/project.json")
+        );
+    }
+}
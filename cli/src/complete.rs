@@ -0,0 +1,335 @@
+// Backs the hidden `esc __complete` command. The generated Bash and Zsh
+// completion scripts call it whenever the word being completed is the value of
+// an id flag, and it answers from the local cache. Stale or missing entries are
+// refreshed by a detached `esc __complete --refresh` process so the shell never
+// waits on the API.
+use crate::cache;
+use crate::resolve::{self, Kind, Scope};
+use esc_api::resources::{OrganizationId, ProjectId};
+
+// Flags whose values can be completed. `None` means the kind of resource
+// depends on the subcommand, as is the case for `--id`.
+const FLAGS: &[(&str, Option<Kind>)] = &[
+    ("--id", None),
+    ("--org-id", Some(Kind::Organization)),
+    ("--organization-id", Some(Kind::Organization)),
+    ("--project-id", Some(Kind::Project)),
+    ("--source-project-id", Some(Kind::Project)),
+    ("--network-id", Some(Kind::Network)),
+    ("--acl-id", Some(Kind::Acl)),
+    ("--cluster-id", Some(Kind::Cluster)),
+    ("--source-cluster-id", Some(Kind::Cluster)),
+    ("--source-backup-id", Some(Kind::Backup)),
+    ("--job-id", Some(Kind::Job)),
+    ("--integration-id", Some(Kind::Integration)),
+];
+
+const SUBCOMMANDS: &[(&str, Kind)] = &[
+    ("organizations", Kind::Organization),
+    ("projects", Kind::Project),
+    ("networks", Kind::Network),
+    ("peerings", Kind::Peering),
+    ("acls", Kind::Acl),
+    ("clusters", Kind::Cluster),
+    ("backups", Kind::Backup),
+    ("jobs", Kind::Job),
+    ("integrations", Kind::Integration),
+    ("groups", Kind::Group),
+    ("members", Kind::Member),
+];
+
+fn kind_for(flag: &str, words: &[String]) -> Option<Kind> {
+    let (_, kind) = FLAGS.iter().find(|(name, _)| *name == flag)?;
+
+    if kind.is_some() {
+        return *kind;
+    }
+
+    words.iter().rev().find_map(|word| {
+        SUBCOMMANDS
+            .iter()
+            .find(|(name, _)| name == word)
+            .map(|(_, kind)| *kind)
+    })
+}
+
+fn flag_value(words: &[String], names: &[&str]) -> Option<String> {
+    let mut iter = words.iter();
+
+    while let Some(word) = iter.next() {
+        for name in names {
+            if word == name {
+                return iter.next().cloned();
+            }
+
+            if let Some(value) = word.strip_prefix(&format!("{}=", name)) {
+                return Some(value.to_string());
+            }
+        }
+    }
+
+    None
+}
+
+fn requested_org(words: &[String]) -> Option<String> {
+    flag_value(words, &["--org-id", "--organization-id"]).or_else(|| {
+        crate::config::SETTINGS
+            .get_current_profile()
+            .and_then(|p| p.org_id.as_ref())
+            .map(|id| id.0.clone())
+    })
+}
+
+fn requested_project(words: &[String]) -> Option<String> {
+    flag_value(words, &["--project-id"]).or_else(|| {
+        crate::config::SETTINGS
+            .get_current_profile()
+            .and_then(|p| p.project_id.as_ref())
+            .map(|id| id.0.clone())
+    })
+}
+
+fn needs_project(kind: Kind) -> bool {
+    !matches!(
+        kind,
+        Kind::Organization | Kind::Project | Kind::Group | Kind::Member
+    )
+}
+
+// Same as the resolver, but only consults the cache.
+async fn cached_id(base_url: &str, kind: Kind, scope: &Scope, value: String) -> String {
+    match cache::load(base_url, kind, scope).await {
+        Some(entry) => match resolve::select(kind, &value, &entry.candidates) {
+            Ok(Some(id)) => id,
+            _ => value,
+        },
+        None => value,
+    }
+}
+
+async fn cached_scope(base_url: &str, kind: Kind, words: &[String]) -> Option<Scope> {
+    if kind == Kind::Organization {
+        return Some(Scope::default());
+    }
+
+    let org_id = requested_org(words)?;
+    let org_id =
+        OrganizationId(cached_id(base_url, Kind::Organization, &Scope::default(), org_id).await);
+
+    if !needs_project(kind) {
+        return Some(Scope::org(&org_id));
+    }
+
+    let project_id = requested_project(words)?;
+    let project_id =
+        ProjectId(cached_id(base_url, Kind::Project, &Scope::org(&org_id), project_id).await);
+
+    Some(Scope::project(&org_id, &project_id))
+}
+
+/// Returns the ids matching `prefix`, either by id or by name. When the cache
+/// has nothing or is out of date a background refresh is started.
+pub async fn complete(base_url: &str, flag: &str, prefix: &str, words: &[String]) -> Vec<String> {
+    let kind = match kind_for(flag, words) {
+        Some(kind) => kind,
+        None => return vec![],
+    };
+
+    let scope = match cached_scope(base_url, kind, words).await {
+        Some(scope) => scope,
+        None => return vec![],
+    };
+
+    let entry = cache::load(base_url, kind, &scope).await;
+
+    let stale = match entry.as_ref() {
+        Some(entry) => entry.is_stale(),
+        None => true,
+    };
+
+    if stale && cache::claim_refresh(base_url, kind, &scope) {
+        spawn_refresh(flag, words);
+    }
+
+    let mut ids: Vec<String> = entry
+        .map(|entry| entry.candidates)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|c| c.id.starts_with(prefix) || c.names.iter().any(|n| n.starts_with(prefix)))
+        .map(|c| c.id)
+        .collect();
+
+    ids.sort();
+    ids
+}
+
+fn spawn_refresh(flag: &str, words: &[String]) {
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(_) => return,
+    };
+
+    let result = std::process::Command::new(exe)
+        .args(["--noninteractive", "__complete", "--refresh"])
+        .arg(format!("--flag={}", flag))
+        .arg("--")
+        .args(words)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn();
+
+    if let Err(err) = result {
+        log::debug!("Could not start the cache refresh: {}", err);
+    }
+}
+
+/// Lists the resources `flag` refers to and stores them in the cache.
+pub async fn refresh(
+    client: &esc_api::Client,
+    flag: &str,
+    words: &[String],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let kind = match kind_for(flag, words) {
+        Some(kind) => kind,
+        None => return Ok(()),
+    };
+
+    let scope = if kind == Kind::Organization {
+        Scope::default()
+    } else {
+        let org_id = requested_org(words).ok_or("no organization")?;
+        let org_id = resolve::org(client, OrganizationId(org_id)).await?;
+
+        if needs_project(kind) {
+            let project_id = requested_project(words).ok_or("no project")?;
+            let project_id = resolve::project(client, &org_id, ProjectId(project_id)).await?;
+            Scope::project(&org_id, &project_id)
+        } else {
+            Scope::org(&org_id)
+        }
+    };
+
+    let candidates = resolve::list(client, kind, &scope).await?;
+    cache::store(&client.base_url, kind, &scope, &candidates).await;
+
+    Ok(())
+}
+
+fn flag_pattern() -> String {
+    FLAGS
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+/// Wraps the script clap generates so id flags are completed by `esc __complete`.
+pub fn bash_script(generated: &str) -> String {
+    format!(
+        r#"{generated}
+_esc_dynamic() {{
+    local cur prev
+    cur="${{COMP_WORDS[COMP_CWORD]}}"
+    prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+    case "${{prev}}" in
+        {flags})
+            COMPREPLY=( $(esc __complete --flag="${{prev}}" --prefix="${{cur}}" -- "${{COMP_WORDS[@]:1:COMP_CWORD-2}}" 2>/dev/null) )
+            return 0
+            ;;
+    esac
+    _esc "$@"
+}}
+
+complete -F _esc_dynamic -o bashdefault -o default esc
+"#,
+        generated = generated.trim_end(),
+        flags = flag_pattern(),
+    )
+}
+
+/// Same as `bash_script` for Zsh. The generated `_esc` function is renamed so
+/// the wrapper can take its place.
+pub fn zsh_script(generated: &str) -> String {
+    let generated = generated
+        .replacen("\n_esc() {", "\n_esc_static() {", 1)
+        .trim_end()
+        .trim_end_matches("_esc \"$@\"")
+        .trim_end()
+        .to_string();
+
+    format!(
+        r#"{generated}
+
+_esc() {{
+    local prev="${{words[CURRENT-1]}}"
+    case "${{prev}}" in
+        {flags})
+            local -a ids
+            ids=(${{(f)"$(esc __complete --flag="${{prev}}" --prefix="${{PREFIX}}" -- "${{(@)words[2,CURRENT-2]}}" 2>/dev/null)"}})
+            compadd -a ids
+            return
+            ;;
+    esac
+    _esc_static "$@"
+}}
+
+_esc "$@"
+"#,
+        generated = generated,
+        flags = flag_pattern(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(line: &str) -> Vec<String> {
+        line.split_whitespace().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn test_flags_with_a_fixed_kind() {
+        let words = words("mesdb clusters create");
+        assert_eq!(kind_for("--org-id", &words), Some(Kind::Organization));
+        assert_eq!(kind_for("--project-id", &words), Some(Kind::Project));
+        assert_eq!(kind_for("--network-id", &words), Some(Kind::Network));
+        assert_eq!(kind_for("--source-backup-id", &words), Some(Kind::Backup));
+        assert_eq!(kind_for("--description", &words), None);
+    }
+
+    #[test]
+    fn test_id_takes_the_kind_of_the_subcommand() {
+        assert_eq!(
+            kind_for("--id", &words("mesdb clusters delete")),
+            Some(Kind::Cluster)
+        );
+        assert_eq!(
+            kind_for("--id", &words("access groups get")),
+            Some(Kind::Group)
+        );
+        // The innermost subcommand wins.
+        assert_eq!(
+            kind_for("--id", &words("resources projects clusters get")),
+            Some(Kind::Cluster)
+        );
+        assert_eq!(kind_for("--id", &words("profiles set")), None);
+    }
+
+    #[test]
+    fn test_flag_values() {
+        let words = words("mesdb clusters get --org-id org-1 --project-id=project-1 --id");
+        assert_eq!(
+            flag_value(&words, &["--org-id", "--organization-id"]),
+            Some("org-1".to_string())
+        );
+        assert_eq!(
+            flag_value(&words, &["--project-id"]),
+            Some("project-1".to_string())
+        );
+        // The flag being completed has no value yet.
+        assert_eq!(flag_value(&words, &["--id"]), None);
+        assert_eq!(flag_value(&words, &["--network-id"]), None);
+    }
+}
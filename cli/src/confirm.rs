@@ -0,0 +1,71 @@
+// The `--yes` flag of commands deleting resources which can't be brought back,
+// and the prompt asking to type the name of the resource otherwise.
+use std::io::IsTerminal;
+use structopt::StructOpt;
+
+type Result<A> = std::result::Result<A, Box<dyn std::error::Error>>;
+
+#[derive(StructOpt, Clone, Debug)]
+pub struct ConfirmArgs {
+    #[structopt(long, short = "y", help = "Deletes without asking for confirmation")]
+    pub yes: bool,
+}
+
+/// Whether the deletion has to be confirmed. Fails when it does but
+/// `--noninteractive` is set or stdin isn't a terminal, since nobody could
+/// answer the prompt. Requests which aren't really sent, such as with
+/// `--dry-run`, don't need confirming.
+pub fn needed(
+    client: &esc_api::Client,
+    args: &ConfirmArgs,
+    kind: &str,
+    noninteractive: bool,
+) -> Result<bool> {
+    if args.yes || client.sender.mode != esc_api::SendMode::Send {
+        return Ok(false);
+    }
+    if noninteractive || !std::io::stdin().is_terminal() {
+        return Err(format!(
+            "Deleting the {} needs confirming, pass --yes when --noninteractive is set or stdin isn't a terminal",
+            kind
+        )
+        .into());
+    }
+    Ok(true)
+}
+
+/// Asks to type the name of the resource, or its id if it doesn't have one,
+/// and fails unless it matches.
+pub fn delete(kind: &str, id: &str, name: &str) -> Result<()> {
+    let (expected, what) = if name.trim().is_empty() {
+        (id, "id")
+    } else {
+        (name, "name")
+    };
+    eprintln!(
+        "This deletes the {} \"{}\" ({}) and can't be undone.",
+        kind, name, id
+    );
+    let mut editor = rustyline::Editor::<()>::new();
+    let line = editor.readline(&format!("Type its {} to confirm: ", what))?;
+    if line.trim() != expected.trim() {
+        return Err(format!(
+            "\"{}\" doesn't match the {}'s {}, the {} was not deleted",
+            line.trim(),
+            kind,
+            what,
+            kind
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Explains how to delete a cluster which is protected from deletion.
+pub fn protected(cluster: &esc_api::mesdb::Cluster) -> Box<dyn std::error::Error> {
+    format!(
+        "Cluster \"{}\" ({}) is protected from accidental deletion. Unprotect it first with:\n  esc mesdb clusters update --id {} --protected false",
+        cluster.description, cluster.id, cluster.id
+    )
+    .into()
+}
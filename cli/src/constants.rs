@@ -1 +1,11 @@
 pub static ES_CLOUD_API_URL: &str = "https://api.eventstore.cloud";
+
+// Exit codes used when waiting for a resource doesn't work out.
+pub const EXIT_CODE_WAIT_FAILED: i32 = 2;
+pub const EXIT_CODE_WAIT_TIMED_OUT: i32 = 3;
+
+// Exit code of `esc drift` when the project no longer matches the baseline.
+pub const EXIT_CODE_DRIFT_DETECTED: i32 = 4;
+
+// Exit code of `esc access policies check` when any of the checks is denied.
+pub const EXIT_CODE_POLICY_DENIED: i32 = 5;
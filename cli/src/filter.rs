@@ -0,0 +1,260 @@
+// Client side filtering of list responses for `--filter`. Filters look like
+// `status=available`, `description~=^dev-` or `created<2026-01-01`, and name
+// fields of the API response whatever the output format. Nested fields are
+// dotted and, like `--columns`, names match regardless of case, dashes and
+// underscores.
+use crate::table;
+use regex::Regex;
+use serde_json::Value;
+use std::cmp::Ordering;
+
+#[derive(Clone, Debug)]
+enum Op {
+    Eq,
+    Ne,
+    Matches(Regex),
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Clone, Debug)]
+pub struct Filter {
+    key: String,
+    op: Op,
+    value: String,
+}
+
+// Longer operators first, so `<=` isn't read as `<`.
+const OPERATORS: &[&str] = &["~=", "!=", "<=", ">=", "=", "<", ">"];
+
+impl Filter {
+    pub fn parse(src: &str) -> Result<Filter, String> {
+        let (idx, operator) = src
+            .char_indices()
+            .find_map(|(idx, _)| {
+                OPERATORS
+                    .iter()
+                    .find(|op| src[idx..].starts_with(*op))
+                    .map(|op| (idx, *op))
+            })
+            .ok_or_else(|| {
+                format!(
+                    "Invalid filter \"{}\", expected KEY=VALUE, KEY!=VALUE, KEY~=REGEX, KEY<VALUE or KEY>VALUE",
+                    src
+                )
+            })?;
+        let key = src[..idx].trim();
+        if key.is_empty() {
+            return Err(format!("Invalid filter \"{}\", the key is missing", src));
+        }
+        let value = src[idx + operator.len()..].trim().to_string();
+        let op = match operator {
+            "~=" => Op::Matches(
+                Regex::new(&value)
+                    .map_err(|e| format!("Invalid regex in filter \"{}\": {}", src, e))?,
+            ),
+            "!=" => Op::Ne,
+            "<=" => Op::Le,
+            ">=" => Op::Ge,
+            "<" => Op::Lt,
+            ">" => Op::Gt,
+            _ => Op::Eq,
+        };
+        Ok(Filter {
+            key: key.to_string(),
+            op,
+            value,
+        })
+    }
+
+    // Numbers compare as numbers, anything else as text. Dates are compared
+    // as text too, which works since the API uses RFC 3339 timestamps.
+    fn compare(&self, text: &str) -> Ordering {
+        match (text.parse::<f64>(), self.value.parse::<f64>()) {
+            (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+            _ => text.cmp(self.value.as_str()),
+        }
+    }
+
+    fn matches(&self, field: Option<&Value>) -> bool {
+        let field = match field {
+            None | Some(Value::Null) => return matches!(self.op, Op::Ne),
+            Some(field) => field,
+        };
+        let text = table::cell(Some(field));
+        // Lists of scalars equal a value if any of their items do.
+        let equals = match field {
+            Value::Array(items) => items
+                .iter()
+                .any(|item| table::cell(Some(item)).eq_ignore_ascii_case(&self.value)),
+            _ => text.eq_ignore_ascii_case(&self.value),
+        };
+        match &self.op {
+            Op::Eq => equals,
+            Op::Ne => !equals,
+            Op::Matches(regex) => regex.is_match(&text),
+            Op::Lt => self.compare(&text) == Ordering::Less,
+            Op::Le => self.compare(&text) != Ordering::Greater,
+            Op::Gt => self.compare(&text) == Ordering::Greater,
+            Op::Ge => self.compare(&text) != Ordering::Less,
+        }
+    }
+}
+
+fn retain(items: Vec<Value>, filters: &[Filter]) -> Result<Vec<Value>, String> {
+    let rows: Vec<table::Row> = items.iter().cloned().map(table::flatten).collect();
+    let keys = filters
+        .iter()
+        .map(|filter| table::resolve_column(&rows, &filter.key))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(items
+        .into_iter()
+        .zip(rows)
+        .filter(|(_, row)| {
+            filters
+                .iter()
+                .zip(keys.iter())
+                .all(|(filter, key)| filter.matches(row.get(key)))
+        })
+        .map(|(item, _)| item)
+        .collect())
+}
+
+/// Keeps the items of a list response matching every filter. Envelopes such
+/// as `{"clusters": [...]}` are kept around the remaining items, and
+/// responses which aren't lists are left alone.
+pub fn apply(value: Value, filters: &[Filter]) -> Result<Value, String> {
+    if filters.is_empty() {
+        return Ok(value);
+    }
+    match value {
+        Value::Array(items) => Ok(Value::Array(retain(items, filters)?)),
+        Value::Object(mut fields) if fields.len() == 1 => {
+            if let Some(Value::Array(items)) = fields.values_mut().next() {
+                *items = retain(std::mem::take(items), filters)?;
+            }
+            Ok(Value::Object(fields))
+        }
+        value => Ok(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn keep(value: Value, filters: &[&str]) -> Value {
+        let filters = filters
+            .iter()
+            .map(|src| Filter::parse(src).unwrap())
+            .collect::<Vec<_>>();
+        apply(value, &filters).unwrap()
+    }
+
+    #[test]
+    fn test_parse_prefers_longer_operators() {
+        let filter = Filter::parse("size<=16").unwrap();
+        assert_eq!(filter.key, "size");
+        assert!(matches!(filter.op, Op::Le));
+        assert_eq!(filter.value, "16");
+
+        let filter = Filter::parse("size >= 16").unwrap();
+        assert_eq!(filter.key, "size");
+        assert!(matches!(filter.op, Op::Ge));
+        assert_eq!(filter.value, "16");
+    }
+
+    #[test]
+    fn test_parse_splits_on_the_first_operator() {
+        let filter = Filter::parse("description~=a=b").unwrap();
+        assert_eq!(filter.key, "description");
+        assert!(matches!(&filter.op, Op::Matches(regex) if regex.as_str() == "a=b"));
+
+        let filter = Filter::parse("tags=a!=b").unwrap();
+        assert_eq!(filter.key, "tags");
+        assert!(matches!(filter.op, Op::Eq));
+        assert_eq!(filter.value, "a!=b");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Filter::parse("status").is_err());
+        assert!(Filter::parse("=available").is_err());
+        assert!(Filter::parse("name~=(").is_err());
+    }
+
+    #[test]
+    fn test_numbers_compare_as_numbers() {
+        let value = json!([{"size": 9}, {"size": 10}, {"size": 100}]);
+        assert_eq!(
+            keep(value.clone(), &["size>9"]),
+            json!([{"size": 10}, {"size": 100}])
+        );
+        assert_eq!(
+            keep(value, &["size<=10"]),
+            json!([{"size": 9}, {"size": 10}])
+        );
+    }
+
+    #[test]
+    fn test_text_compares_as_text() {
+        let value = json!([
+            {"created": "2025-12-31T10:00:00Z"},
+            {"created": "2026-01-02T10:00:00Z"},
+        ]);
+        assert_eq!(
+            keep(value, &["created<2026-01-01"]),
+            json!([{"created": "2025-12-31T10:00:00Z"}])
+        );
+        // "10a" sorts before "9" once one side isn't a number.
+        let value = json!([{"version": "10a"}, {"version": "9x"}]);
+        assert_eq!(keep(value, &["version>9"]), json!([{"version": "9x"}]));
+    }
+
+    #[test]
+    fn test_lists_equal_a_value_if_any_item_does() {
+        let value = json!([
+            {"name": "a", "regions": ["eu-west-1", "us-east-1"]},
+            {"name": "b", "regions": ["us-east-1"]},
+        ]);
+        assert_eq!(
+            keep(value.clone(), &["regions=EU-WEST-1"]),
+            json!([{"name": "a", "regions": ["eu-west-1", "us-east-1"]}])
+        );
+        assert_eq!(
+            keep(value, &["regions!=eu-west-1"]),
+            json!([{"name": "b", "regions": ["us-east-1"]}])
+        );
+    }
+
+    #[test]
+    fn test_missing_fields_only_match_not_equal() {
+        let value = json!({"clusters": [
+            {"name": "a", "description": "dev"},
+            {"name": "b", "description": null},
+        ]});
+        assert_eq!(
+            keep(value.clone(), &["description=dev"]),
+            json!({"clusters": [{"name": "a", "description": "dev"}]})
+        );
+        assert_eq!(
+            keep(value, &["description!=dev"]),
+            json!({"clusters": [{"name": "b", "description": null}]})
+        );
+    }
+
+    #[test]
+    fn test_keys_match_like_columns() {
+        let value = json!([
+            {"cloudProvider": {"name": "aws"}},
+            {"cloudProvider": {"name": "gcp"}},
+        ]);
+        assert_eq!(
+            keep(value, &["cloud-provider.name=gcp"]),
+            json!([{"cloudProvider": {"name": "gcp"}}])
+        );
+    }
+}
@@ -6,11 +6,25 @@ extern crate lazy_static;
 #[macro_use]
 extern crate serde_derive;
 
+mod api;
+mod cache;
+mod complete;
 mod config;
+mod confirm;
 mod constants;
+mod filter;
+mod manifest;
 mod output;
+mod query;
+mod resolve;
+mod secret;
+mod table;
+mod template;
 mod utils;
 mod v1;
+mod versions;
+mod wait;
+mod watch;
 
 use cidr::Cidr;
 use esc_api::resources::MfaStatus;
@@ -18,6 +32,8 @@ use esc_client_base::build_http_client;
 
 use esc_api::{GroupId, MemberId, OrgId};
 use output::OutputFormat;
+use secret::Secret;
+use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::collections::HashMap;
 use std::str::FromStr;
@@ -50,10 +66,11 @@ pub struct Opt {
 
     #[structopt(
         long,
-        help = "Refresh token, useful if you intend to use esc in a CI/scripting setting for example",
+        parse(try_from_str = secret::parse),
+        help = "Refresh token, useful if you intend to use esc in a CI/scripting setting for example. Accepts @FILE, env:VAR or - to read it from stdin",
         global = true
     )]
-    refresh_token: Option<String>,
+    refresh_token: Option<Secret>,
 
     #[structopt(
         long,
@@ -62,6 +79,75 @@ pub struct Opt {
     )]
     noninteractive: bool,
 
+    #[structopt(
+        long,
+        help = "Shows secrets such as tokens, keys and passwords in the API output instead of redacting them",
+        global = true
+    )]
+    show_secrets: bool,
+
+    #[structopt(
+        long,
+        help = "Prints the request the command would send as a curl command instead of sending it. The access token is read from $ESC_TOKEN, see esc access tokens print-access-token. Secrets in the body are redacted unless --show-secrets is passed too",
+        global = true
+    )]
+    as_curl: bool,
+
+    #[structopt(
+        long,
+        conflicts_with = "as-curl",
+        help = "Only sends the requests which read. Others are printed instead, and the command carries on as if they had worked",
+        global = true
+    )]
+    dry_run: bool,
+
+    #[structopt(
+        long,
+        use_delimiter = true,
+        help = "Comma separated columns shown by the table and csv formats, such as id,description,status",
+        global = true
+    )]
+    columns: Option<Vec<String>>,
+
+    #[structopt(
+        long,
+        help = "Sorts the rows of the table and csv formats by a column",
+        global = true
+    )]
+    sort_by: Option<String>,
+
+    #[structopt(
+        long,
+        help = "Leaves out the header row of the table and csv formats",
+        global = true
+    )]
+    no_headers: bool,
+
+    #[structopt(
+        long = "filter",
+        parse(try_from_str = filter::Filter::parse),
+        number_of_values = 1,
+        help = "Only shows the items of list responses matching KEY=VALUE, KEY!=VALUE, KEY~=REGEX, KEY<VALUE or KEY>VALUE. Repeat it to combine filters",
+        global = true
+    )]
+    filters: Vec<filter::Filter>,
+
+    #[structopt(
+        long,
+        parse(try_from_str = query::Query::parse),
+        help = "JMESPath expression applied to the API response before it is rendered, such as \"clusters[?status=='available'].id\"",
+        global = true
+    )]
+    query: Option<query::Query>,
+
+    #[structopt(
+        long,
+        parse(try_from_str = template::Template::parse),
+        help = "Renders every item of the response on its own line using a template such as '{{.id}}\\t{{.status}}'",
+        global = true
+    )]
+    template: Option<template::Template>,
+
     #[structopt(subcommand)]
     cmd: Command,
 }
@@ -80,8 +166,185 @@ enum Command {
     GenerateBashCompletion,
     #[structopt(about = "Prints Zsh completion script in STDOUT")]
     GenerateZshCompletion,
-    #[structopt(about = "Prints Powershell completion script in STDOUT")]
+    #[structopt(
+        about = "Prints Powershell completion script in STDOUT. Unlike the Bash and Zsh scripts, it doesn't complete resource ids"
+    )]
     GeneratePowershellCompletion,
+    #[structopt(name = "__complete", setting = structopt::clap::AppSettings::Hidden)]
+    Complete(Complete),
+    Wait(Wait),
+    Plan(PlanManifest),
+    Apply(ApplyManifest),
+    Export(ExportManifest),
+    Drift(DriftManifest),
+    Api(ApiRequest),
+}
+
+#[derive(StructOpt, Debug)]
+#[structopt(about = "Completes resource ids from the local cache, used by the completion scripts")]
+struct Complete {
+    #[structopt(long, help = "The flag whose value is being completed")]
+    flag: String,
+
+    #[structopt(long, default_value = "", help = "What has been typed so far")]
+    prefix: String,
+
+    #[structopt(long, help = "Refreshes the cache instead of completing")]
+    refresh: bool,
+
+    #[structopt(last = true, help = "The words on the command line before the flag")]
+    words: Vec<String>,
+}
+
+#[derive(StructOpt, Debug)]
+#[structopt(about = "Waits until a resource reaches a given status")]
+struct Wait {
+    #[structopt(parse(try_from_str = parse_waitable_kind), help = "The kind of resource: cluster, network, peering, acl or backup")]
+    resource: resolve::Kind,
+
+    #[structopt(help = "The resource's id")]
+    id: String,
+
+    #[structopt(
+        long = "for",
+        help = "The status to wait for. Defaults to the status in which the resource is ready to be used"
+    )]
+    status: Option<String>,
+
+    #[structopt(long, parse(try_from_str = parse_org_id), default_value = "", help = "The organization id the resource relates to")]
+    org_id: esc_api::resources::OrganizationId,
+
+    #[structopt(long, parse(try_from_str = parse_project_id), default_value = "", help = "The project id the resource relates to")]
+    project_id: esc_api::resources::ProjectId,
+
+    #[structopt(
+        long,
+        parse(try_from_str = humantime::parse_duration),
+        default_value = "30m",
+        help = "How long to wait before giving up, for example 90s, 10m or 1h"
+    )]
+    timeout: std::time::Duration,
+}
+
+#[derive(StructOpt, Debug)]
+#[structopt(about = "Shows the changes needed to make a project match a manifest")]
+struct PlanManifest {
+    #[structopt(
+        long,
+        short,
+        help = "The manifest describing the project, in YAML or TOML"
+    )]
+    file: std::path::PathBuf,
+
+    #[structopt(long, parse(try_from_str = parse_org_id), default_value = "", help = "The organization id the project relates to")]
+    org_id: esc_api::resources::OrganizationId,
+
+    #[structopt(long, parse(try_from_str = parse_project_id), default_value = "", help = "The id of the project the manifest describes")]
+    project_id: esc_api::resources::ProjectId,
+}
+
+#[derive(StructOpt, Debug)]
+#[structopt(about = "Prints the resources of a project as a manifest")]
+struct ExportManifest {
+    #[structopt(long, parse(try_from_str = parse_org_id), default_value = "", help = "The organization id the project relates to")]
+    org_id: esc_api::resources::OrganizationId,
+
+    #[structopt(long, parse(try_from_str = parse_project_id), default_value = "", help = "The id of the project to export")]
+    project_id: esc_api::resources::ProjectId,
+}
+
+#[derive(StructOpt, Debug)]
+#[structopt(
+    about = "Reports how a project changed since a baseline manifest, such as an earlier export. Exits with 4 if it did"
+)]
+struct DriftManifest {
+    #[structopt(
+        long,
+        help = "The manifest to compare the project with, in YAML or TOML"
+    )]
+    baseline: std::path::PathBuf,
+
+    #[structopt(long, parse(try_from_str = parse_org_id), default_value = "", help = "The organization id the project relates to")]
+    org_id: esc_api::resources::OrganizationId,
+
+    #[structopt(long, parse(try_from_str = parse_project_id), default_value = "", help = "The id of the project the baseline describes")]
+    project_id: esc_api::resources::ProjectId,
+}
+
+#[derive(StructOpt, Debug)]
+#[structopt(
+    about = "Sends an authenticated request to any API endpoint, such as GET /mesdb/v1/organizations/{org}/projects/{project}/clusters"
+)]
+struct ApiRequest {
+    #[structopt(parse(try_from_str = api::parse_method), help = "The HTTP method, such as GET, POST, PUT or DELETE")]
+    method: reqwest::Method,
+
+    #[structopt(
+        help = "The path of the endpoint. {org} and {project} are replaced by the organization and project ids"
+    )]
+    path: String,
+
+    #[structopt(
+        short = "f",
+        long = "raw-field",
+        parse(try_from_str = api::Field::parse_raw),
+        number_of_values = 1,
+        help = "Adds a KEY=VALUE string field to the request body. Dotted keys set nested fields"
+    )]
+    raw_fields: Vec<api::Field>,
+
+    #[structopt(
+        short = "F",
+        long = "field",
+        parse(try_from_str = api::Field::parse_typed),
+        number_of_values = 1,
+        help = "Adds a KEY=VALUE field to the request body, reading the value as JSON if it is, such as 8, true or [\"a\"]"
+    )]
+    fields: Vec<api::Field>,
+
+    #[structopt(
+        long,
+        conflicts_with_all = &["raw-fields", "fields"],
+        help = "Reads the request body from a JSON file, or from stdin with -"
+    )]
+    input: Option<std::path::PathBuf>,
+
+    #[structopt(long, parse(try_from_str = parse_org_id), help = "The organization id replacing {org}, defaults to the one of the profile")]
+    org_id: Option<esc_api::resources::OrganizationId>,
+
+    #[structopt(long, parse(try_from_str = parse_project_id), help = "The project id replacing {project}, defaults to the one of the profile")]
+    project_id: Option<esc_api::resources::ProjectId>,
+}
+
+#[derive(StructOpt, Debug)]
+#[structopt(about = "Makes a project match a manifest")]
+struct ApplyManifest {
+    #[structopt(
+        long,
+        short,
+        help = "The manifest describing the project, in YAML or TOML"
+    )]
+    file: std::path::PathBuf,
+
+    #[structopt(long, parse(try_from_str = parse_org_id), default_value = "", help = "The organization id the project relates to")]
+    org_id: esc_api::resources::OrganizationId,
+
+    #[structopt(long, parse(try_from_str = parse_project_id), default_value = "", help = "The id of the project the manifest describes")]
+    project_id: esc_api::resources::ProjectId,
+
+    #[structopt(
+        long,
+        help = "Allows deleting resources missing from the manifest and replacing those which can't be updated in place"
+    )]
+    allow_delete: bool,
+
+    #[structopt(
+        long,
+        parse(try_from_str = humantime::parse_duration),
+        default_value = "30m",
+        help = "How long to wait on each resource before giving up, for example 90s, 10m or 1h"
+    )]
+    timeout: std::time::Duration,
 }
 
 #[derive(StructOpt, Debug)]
@@ -100,6 +363,7 @@ enum AccessCommand {
     Invites(Invites),
     Policies(Policies),
     Members(Members),
+    Settings(Settings),
 }
 
 #[derive(Debug, StructOpt)]
@@ -188,6 +452,7 @@ struct Tokens {
 enum TokensCommand {
     Create(CreateToken),
     Display(Display),
+    PrintAccessToken(PrintAccessToken),
 }
 
 #[derive(StructOpt, Debug)]
@@ -198,15 +463,22 @@ struct CreateToken {
 
     #[structopt(
         long,
-        help = "Set this parameter if you don't want to give your password safely (non-interactive)"
+        parse(try_from_str = secret::parse),
+        help = "Set this parameter if you don't want to be prompted for your password (non-interactive). Accepts @FILE, env:VAR or - to read it from stdin"
     )]
-    unsafe_password: Option<String>,
+    unsafe_password: Option<Secret>,
 }
 
 #[derive(StructOpt, Debug)]
 #[structopt(about = "Display your current refresh token")]
 struct Display {}
 
+#[derive(StructOpt, Debug)]
+#[structopt(
+    about = "Prints a currently valid access token, for example to set $ESC_TOKEN for the commands printed by --as-curl"
+)]
+struct PrintAccessToken {}
+
 #[derive(StructOpt, Debug)]
 #[structopt(about = "Gathers groups management commands")]
 struct Groups {
@@ -221,6 +493,13 @@ struct Members {
     members_command: MembersCommand,
 }
 
+#[derive(StructOpt, Debug)]
+#[structopt(about = "Gathers organization access settings commands")]
+struct Settings {
+    #[structopt(subcommand)]
+    settings_command: SettingsCommand,
+}
+
 #[derive(StructOpt, Debug)]
 #[structopt(about = "Gathers invites management commands")]
 struct Invites {
@@ -248,6 +527,7 @@ enum PoliciesCommand {
     Get(GetPolicy),
     Delete(DeletePolicy),
     List(ListPolicies),
+    Check(CheckPolicy),
 }
 
 #[derive(StructOpt, Debug)]
@@ -308,6 +588,30 @@ struct ListPolicies {
     org_id: OrgId,
 }
 
+#[derive(StructOpt, Debug)]
+#[structopt(
+    about = "Checks whether you are allowed to perform an action on a resource. Exits with 5 if you aren't"
+)]
+struct CheckPolicy {
+    #[structopt(long, parse(try_from_str = utils::parse_action), required_unless = "file", help = "The action: create, read, modify or delete")]
+    action: Option<esc_api::access::Action>,
+
+    #[structopt(
+        long,
+        required_unless = "file",
+        help = "The resource the action applies to"
+    )]
+    resource: Option<String>,
+
+    #[structopt(
+        long,
+        short,
+        conflicts_with_all = &["action", "resource"],
+        help = "A file with one \"<action> <resource>\" pair per line, or - to read from stdin. Blank lines and lines starting with # are skipped"
+    )]
+    file: Option<String>,
+}
+
 #[derive(StructOpt, Debug)]
 enum GroupsCommand {
     Create(CreateGroup),
@@ -317,6 +621,39 @@ enum GroupsCommand {
     List(ListGroups),
 }
 
+#[derive(StructOpt, Debug)]
+enum SettingsCommand {
+    Get(GetSettings),
+    Update(UpdateSettings),
+}
+
+#[derive(StructOpt, Debug)]
+#[structopt(about = "Read the access settings of an organization")]
+struct GetSettings {
+    #[structopt(long, short, parse(try_from_str = parse_org_id), default_value = "", help = "The organization id the settings relate to")]
+    org_id: OrgId,
+}
+
+#[derive(StructOpt, Debug)]
+#[structopt(about = "Update the access settings of an organization")]
+struct UpdateSettings {
+    #[structopt(long, short, parse(try_from_str = parse_org_id), default_value = "", help = "The organization id the settings relate to")]
+    org_id: OrgId,
+
+    #[structopt(
+        long,
+        parse(try_from_str),
+        help = "Whether members must use MFA to sign in. Turning it on first lists the members who may not have MFA set up"
+    )]
+    require_mfa: Option<bool>,
+
+    #[structopt(
+        long,
+        help = "Only allow invites to email addresses of this domain. An empty value lifts the restriction"
+    )]
+    restrict_invite_domain: Option<String>,
+}
+
 #[derive(StructOpt, Debug)]
 enum MembersCommand {
     Get(GetMember),
@@ -520,6 +857,9 @@ struct CreateAcl {
 
     #[structopt(long, help = "Human-readable description of the acl")]
     description: String,
+
+    #[structopt(flatten)]
+    wait: wait::WaitArgs,
 }
 
 #[derive(StructOpt, Debug)]
@@ -619,6 +959,9 @@ struct CreateNetwork {
         help = "Networks with public access enabled can have clusters with public access enabled, whereas networks without can only be accessed via peering. Defaults to false."
     )]
     public_access: bool,
+
+    #[structopt(flatten)]
+    wait: wait::WaitArgs,
 }
 
 #[derive(StructOpt, Debug)]
@@ -632,6 +975,9 @@ struct DeleteNetwork {
 
     #[structopt(long, short, parse(try_from_str = parse_network_id), help = "A network's id")]
     id: esc_api::infra::NetworkId,
+
+    #[structopt(flatten)]
+    confirm: confirm::ConfirmArgs,
 }
 
 #[derive(StructOpt, Debug)]
@@ -645,6 +991,9 @@ struct GetNetwork {
 
     #[structopt(long, short, parse(try_from_str = parse_network_id), help = "A network's id")]
     id: esc_api::infra::NetworkId,
+
+    #[structopt(flatten)]
+    watch: watch::WatchArgs,
 }
 
 #[derive(StructOpt, Debug)]
@@ -655,6 +1004,9 @@ struct ListNetworks {
 
     #[structopt(long, parse(try_from_str = parse_project_id), default_value = "", help = "The project id the networks relate to")]
     project_id: esc_api::resources::ProjectId,
+
+    #[structopt(flatten)]
+    watch: watch::WatchArgs,
 }
 
 #[derive(StructOpt, Debug)]
@@ -715,6 +1067,9 @@ struct CreatePeering {
 
     #[structopt(long, help = "Your network routes")]
     routes: Vec<String>,
+
+    #[structopt(flatten)]
+    wait: wait::WaitArgs,
 }
 
 #[derive(StructOpt, Debug)]
@@ -741,6 +1096,9 @@ struct GetPeering {
 
     #[structopt(long, short, parse(try_from_str = parse_peering_id), help = "The peering's id")]
     id: esc_api::PeeringId,
+
+    #[structopt(flatten)]
+    watch: watch::WatchArgs,
 }
 
 #[derive(StructOpt, Debug)]
@@ -751,6 +1109,9 @@ struct ListPeerings {
 
     #[structopt(long, parse(try_from_str = parse_project_id), default_value = "", help = "The project id the peerings relate to")]
     project_id: esc_api::resources::ProjectId,
+
+    #[structopt(flatten)]
+    watch: watch::WatchArgs,
 }
 
 #[derive(StructOpt, Debug)]
@@ -932,6 +1293,9 @@ struct UpdateOrganizationMfaStatus {
 struct DeleteOrganization {
     #[structopt(short, long, parse(try_from_str = parse_org_id), help = "The id of the organization you want to delete")]
     id: OrgId,
+
+    #[structopt(flatten)]
+    confirm: confirm::ConfirmArgs,
 }
 
 #[derive(Debug, StructOpt)]
@@ -995,6 +1359,38 @@ struct DeleteProject {
 
     #[structopt(long, short, parse(try_from_str = parse_project_id), help = "The id of the project you want to delete")]
     id: esc_api::resources::ProjectId,
+
+    #[structopt(
+        long,
+        help = "Also deletes everything in the project, jobs, integrations, clusters, backups, peerings, ACLs and networks, in that order. Run it again to resume if it was interrupted"
+    )]
+    cascade: bool,
+
+    #[structopt(
+        long,
+        parse(try_from_str = parse_on_protected),
+        default_value = "abort",
+        help = "What --cascade does about protected clusters: abort, skip them, which keeps the project as well, or unprotect and delete them"
+    )]
+    protected_clusters: esc_api::teardown::OnProtected,
+
+    #[structopt(
+        long,
+        default_value = "4",
+        help = "How many resources of the same kind --cascade deletes at once"
+    )]
+    concurrency: usize,
+
+    #[structopt(
+        long,
+        parse(try_from_str = humantime::parse_duration),
+        default_value = "30m",
+        help = "How long --cascade waits on each resource before giving up, for example 90s, 10m or 1h"
+    )]
+    timeout: std::time::Duration,
+
+    #[structopt(flatten)]
+    confirm: confirm::ConfirmArgs,
 }
 
 #[derive(Debug, StructOpt)]
@@ -1016,6 +1412,7 @@ enum MesdbCommand {
     Clusters(Clusters),
     Backups(Backups),
     SharedClusters(SharedClusters),
+    Versions(Versions),
 }
 
 #[derive(Debug, StructOpt)]
@@ -1036,7 +1433,9 @@ enum ClustersCommand {
     Stop(StopCluster),
     Start(StartCluster),
     Resize(ResizeCluster),
+    Restart(RestartCluster),
     Upgrade(UpgradeCluster),
+    UpgradeOptions(UpgradeOptions),
 }
 
 #[derive(Debug, StructOpt)]
@@ -1111,6 +1510,9 @@ struct CreateCluster {
 
     #[structopt(long, help = "The region of the cluster")]
     region: Option<String>,
+
+    #[structopt(flatten)]
+    wait: wait::WaitArgs,
 }
 
 #[derive(Debug, StructOpt)]
@@ -1124,6 +1526,9 @@ struct GetCluster {
 
     #[structopt(long, short, parse(try_from_str = parse_cluster_id), help = "Cluster's id")]
     id: esc_api::ClusterId,
+
+    #[structopt(flatten)]
+    watch: watch::WatchArgs,
 }
 
 #[derive(Debug, StructOpt)]
@@ -1134,6 +1539,9 @@ struct ListClusters {
 
     #[structopt(long, parse(try_from_str = parse_project_id), default_value = "", help = "An project id that belongs to an organization pointed by --org-id")]
     project_id: esc_api::resources::ProjectId,
+
+    #[structopt(flatten)]
+    watch: watch::WatchArgs,
 }
 
 #[derive(Debug, StructOpt)]
@@ -1169,6 +1577,9 @@ struct DeleteCluster {
 
     #[structopt(long, short, parse(try_from_str = parse_cluster_id), help = "Id of the cluster you want to delete")]
     id: esc_api::ClusterId,
+
+    #[structopt(flatten)]
+    confirm: confirm::ConfirmArgs,
 }
 
 #[derive(Debug, StructOpt)]
@@ -1194,6 +1605,9 @@ struct ExpandCluster {
 
     #[structopt(long, help = "Optional disk type")]
     disk_type: Option<String>,
+
+    #[structopt(flatten)]
+    wait: wait::WaitArgs,
 }
 
 #[derive(Debug, StructOpt)]
@@ -1210,6 +1624,9 @@ struct ResizeCluster {
 
     #[structopt(long, help = "The target instance size. (C4, M8, etc)")]
     target_size: String,
+
+    #[structopt(flatten)]
+    wait: wait::WaitArgs,
 }
 
 #[derive(Debug, StructOpt)]
@@ -1223,6 +1640,9 @@ struct StopCluster {
 
     #[structopt(long, short, parse(try_from_str = parse_cluster_id), help = "Id of the cluster you want to resize")]
     id: esc_api::ClusterId,
+
+    #[structopt(flatten)]
+    wait: wait::WaitArgs,
 }
 
 #[derive(Debug, StructOpt)]
@@ -1236,6 +1656,38 @@ struct StartCluster {
 
     #[structopt(long, short, parse(try_from_str = parse_cluster_id), help = "Id of the cluster you want to resize")]
     id: esc_api::ClusterId,
+
+    #[structopt(flatten)]
+    wait: wait::WaitArgs,
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "Restarts a cluster")]
+struct RestartCluster {
+    #[structopt(long, parse(try_from_str = parse_org_id), default_value = "", help = "The organization id the cluster relates to")]
+    org_id: OrgId,
+
+    #[structopt(long, parse(try_from_str = parse_project_id), default_value = "", help = "The project id the cluster relates to")]
+    project_id: esc_api::resources::ProjectId,
+
+    #[structopt(long, short, parse(try_from_str = parse_cluster_id), help = "Id of the cluster you want to restart")]
+    id: esc_api::ClusterId,
+
+    #[structopt(flatten)]
+    wait: wait::WaitArgs,
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "Lists the versions a cluster can be upgraded to")]
+struct UpgradeOptions {
+    #[structopt(long, parse(try_from_str = parse_org_id), default_value = "", help = "The organization id the cluster relates to")]
+    org_id: OrgId,
+
+    #[structopt(long, parse(try_from_str = parse_project_id), default_value = "", help = "The project id the cluster relates to")]
+    project_id: esc_api::resources::ProjectId,
+
+    #[structopt(long, short, parse(try_from_str = parse_cluster_id), help = "Id of the cluster you want to upgrade")]
+    id: esc_api::ClusterId,
 }
 
 #[derive(Debug, StructOpt)]
@@ -1255,6 +1707,9 @@ struct UpgradeCluster {
         help = "The target tag you want to upgrade to. This must include the full version (23.10.1)."
     )]
     target_tag: String,
+
+    #[structopt(flatten)]
+    wait: wait::WaitArgs,
 }
 
 #[derive(Debug, StructOpt)]
@@ -1270,6 +1725,9 @@ enum SharedClustersCommand {
     List(ListSharedClusters),
     Delete(DeleteSharedCluster),
     Get(GetSharedCluster),
+    Update(UpdateSharedCluster),
+    Credentials(SharedClusterCredentials),
+    Certificate(SharedClusterCertificate),
 }
 
 #[derive(Debug, StructOpt)]
@@ -1320,6 +1778,9 @@ struct ListSharedClusters {
 
     #[structopt(long, parse(try_from_str = parse_project_id), default_value = "", help = "The project id the cluster relates to")]
     project_id: esc_api::resources::ProjectId,
+
+    #[structopt(flatten)]
+    watch: watch::WatchArgs,
 }
 
 #[derive(Debug, StructOpt)]
@@ -1346,30 +1807,179 @@ struct GetSharedCluster {
 
     #[structopt(long, short, parse(try_from_str = parse_cluster_id), help = "Id of the cluster you want to get")]
     id: esc_api::ClusterId,
-}
-
-#[derive(Debug, StructOpt)]
-#[structopt(about = "Gathers backup management commands")]
-struct Backups {
-    #[structopt(subcommand)]
-    backups_command: BackupsCommand,
-}
 
-#[derive(Debug, StructOpt)]
-enum BackupsCommand {
-    Create(CreateBackup),
-    Get(GetBackup),
-    List(ListBackups),
-    Delete(DeleteBackup),
+    #[structopt(flatten)]
+    watch: watch::WatchArgs,
 }
 
 #[derive(Debug, StructOpt)]
-#[structopt(about = "Create a backup")]
-struct CreateBackup {
-    #[structopt(long, parse(try_from_str = parse_org_id), default_value = "", help = "The organization id the backup will relate to")]
+#[structopt(about = "Update a shared cluster")]
+struct UpdateSharedCluster {
+    #[structopt(long, parse(try_from_str = parse_org_id), default_value = "", help = "The organization id the cluster relates to")]
     org_id: OrgId,
 
-    #[structopt(long, parse(try_from_str = parse_project_id), default_value = "", help = "The project id the backup will relate to")]
+    #[structopt(long, parse(try_from_str = parse_project_id), default_value = "", help = "The project id the cluster relates to")]
+    project_id: esc_api::resources::ProjectId,
+
+    #[structopt(long, short, parse(try_from_str = parse_cluster_id), help = "Id of the cluster you want to update")]
+    id: esc_api::ClusterId,
+
+    #[structopt(long, help = "Whether mutual TLS is enabled for the cluster")]
+    mutual_tls_enabled: Option<bool>,
+
+    #[structopt(long, parse(try_from_str = parse_acl_id), help = "The acl id the cluster relates to")]
+    acl_id: Option<esc_api::infra::AclId>,
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "Gathers shared cluster initial credentials commands")]
+struct SharedClusterCredentials {
+    #[structopt(subcommand)]
+    credentials_command: SharedClusterCredentialsCommand,
+}
+
+#[derive(Debug, StructOpt)]
+enum SharedClusterCredentialsCommand {
+    Get(GetSharedClusterCredentials),
+    Clear(ClearSharedClusterCredentials),
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "Saves the initial credentials of a shared cluster")]
+struct GetSharedClusterCredentials {
+    #[structopt(long, parse(try_from_str = parse_org_id), default_value = "", help = "The organization id the cluster relates to")]
+    org_id: OrgId,
+
+    #[structopt(long, parse(try_from_str = parse_project_id), default_value = "", help = "The project id the cluster relates to")]
+    project_id: esc_api::resources::ProjectId,
+
+    #[structopt(long, short, parse(try_from_str = parse_cluster_id), help = "Id of the cluster")]
+    id: esc_api::ClusterId,
+
+    #[structopt(flatten)]
+    out: SecretOutput,
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    about = "Clears the initial credentials of a shared cluster once they're no longer needed"
+)]
+struct ClearSharedClusterCredentials {
+    #[structopt(long, parse(try_from_str = parse_org_id), default_value = "", help = "The organization id the cluster relates to")]
+    org_id: OrgId,
+
+    #[structopt(long, parse(try_from_str = parse_project_id), default_value = "", help = "The project id the cluster relates to")]
+    project_id: esc_api::resources::ProjectId,
+
+    #[structopt(long, short, parse(try_from_str = parse_cluster_id), help = "Id of the cluster")]
+    id: esc_api::ClusterId,
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "Gathers shared cluster certificate commands")]
+struct SharedClusterCertificate {
+    #[structopt(subcommand)]
+    certificate_command: SharedClusterCertificateCommand,
+}
+
+#[derive(Debug, StructOpt)]
+enum SharedClusterCertificateCommand {
+    Get(GetSharedClusterCertificate),
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "Saves the certificate bundle of a shared cluster")]
+struct GetSharedClusterCertificate {
+    #[structopt(long, parse(try_from_str = parse_org_id), default_value = "", help = "The organization id the cluster relates to")]
+    org_id: OrgId,
+
+    #[structopt(long, parse(try_from_str = parse_project_id), default_value = "", help = "The project id the cluster relates to")]
+    project_id: esc_api::resources::ProjectId,
+
+    #[structopt(long, short, parse(try_from_str = parse_cluster_id), help = "Id of the cluster")]
+    id: esc_api::ClusterId,
+
+    #[structopt(flatten)]
+    out: SecretOutput,
+}
+
+// Where commands which fetch secrets put them. Writing to stdout has to be
+// asked for explicitly.
+#[derive(Debug, StructOpt)]
+struct SecretOutput {
+    #[structopt(
+        long,
+        required_unless = "stdout",
+        help = "The file to write to. It is created with permissions 0600"
+    )]
+    out: Option<std::path::PathBuf>,
+
+    #[structopt(
+        long,
+        conflicts_with = "out",
+        help = "Writes to stdout instead of a file"
+    )]
+    stdout: bool,
+}
+
+impl SecretOutput {
+    fn write(&self, contents: &str) -> Result<(), Box<dyn std::error::Error>> {
+        match (self.out.as_ref(), self.stdout) {
+            (Some(path), _) => {
+                utils::write_secret_file(path, contents)?;
+                eprintln!("Written to {}", path.display());
+            }
+            (None, true) => println!("{}", contents),
+            (None, false) => return Err("Either --out or --stdout is required".into()),
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "Gathers server version commands")]
+struct Versions {
+    #[structopt(subcommand)]
+    versions_command: VersionsCommand,
+}
+
+#[derive(Debug, StructOpt)]
+enum VersionsCommand {
+    List(ListVersions),
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "Lists the server versions available to new clusters")]
+struct ListVersions {
+    #[structopt(long, parse(try_from_str = parse_org_id), default_value = "", help = "The organization id the clusters would relate to")]
+    org_id: OrgId,
+
+    #[structopt(long, help = "Lists the versions new clusters can be created with")]
+    for_create: bool,
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "Gathers backup management commands")]
+struct Backups {
+    #[structopt(subcommand)]
+    backups_command: BackupsCommand,
+}
+
+#[derive(Debug, StructOpt)]
+enum BackupsCommand {
+    Create(CreateBackup),
+    Get(GetBackup),
+    List(ListBackups),
+    Delete(DeleteBackup),
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "Create a backup")]
+struct CreateBackup {
+    #[structopt(long, parse(try_from_str = parse_org_id), default_value = "", help = "The organization id the backup will relate to")]
+    org_id: OrgId,
+
+    #[structopt(long, parse(try_from_str = parse_project_id), default_value = "", help = "The project id the backup will relate to")]
     project_id: esc_api::resources::ProjectId,
 
     #[structopt(long, parse(try_from_str = parse_cluster_id), help = "The id of the cluster to create backup of")]
@@ -1377,6 +1987,9 @@ struct CreateBackup {
 
     #[structopt(long, help = "A human-readable description of the backup")]
     description: String,
+
+    #[structopt(flatten)]
+    wait: wait::WaitArgs,
 }
 
 #[derive(Debug, StructOpt)]
@@ -1390,6 +2003,9 @@ struct GetBackup {
 
     #[structopt(long, short, parse(try_from_str = parse_backup_id), help = "Backup's id")]
     id: esc_api::BackupId,
+
+    #[structopt(flatten)]
+    watch: watch::WatchArgs,
 }
 
 #[derive(Debug, StructOpt)]
@@ -1400,6 +2016,9 @@ struct ListBackups {
 
     #[structopt(long, parse(try_from_str = parse_project_id), default_value = "", help = "An project id that belongs to an organization pointed by --org-id")]
     project_id: esc_api::resources::ProjectId,
+
+    #[structopt(flatten)]
+    watch: watch::WatchArgs,
 }
 
 #[derive(Debug, StructOpt)]
@@ -1413,6 +2032,9 @@ struct DeleteBackup {
 
     #[structopt(long, short, parse(try_from_str = parse_backup_id), help = "Id of the backup you want to delete")]
     id: esc_api::BackupId,
+
+    #[structopt(flatten)]
+    confirm: confirm::ConfirmArgs,
 }
 
 #[derive(Debug, StructOpt)]
@@ -1441,6 +2063,31 @@ enum JobsCommand {
     Get(GetJob),
     List(ListJobs),
     Delete(DeleteJob),
+    Run(RunJob),
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "Runs a job immediately")]
+struct RunJob {
+    #[structopt(long, parse(try_from_str = parse_org_id), default_value = "", help = "The organization id the job relates to")]
+    org_id: OrgId,
+
+    #[structopt(long, parse(try_from_str = parse_project_id), default_value = "", help = "The project id the job relates to")]
+    project_id: esc_api::resources::ProjectId,
+
+    #[structopt(long, short, parse(try_from_str = parse_job_id), help = "The id of the job")]
+    id: esc_api::JobId,
+
+    #[structopt(long, help = "Waits until the run has ended and shows its outcome")]
+    follow: bool,
+
+    #[structopt(
+        long,
+        parse(try_from_str = humantime::parse_duration),
+        default_value = "30m",
+        help = "How long --follow waits before giving up, for example 90s, 10m or 1h"
+    )]
+    timeout: std::time::Duration,
 }
 
 #[derive(Debug, StructOpt)]
@@ -1537,6 +2184,9 @@ struct ListHistory {
 
     #[structopt(long, parse(try_from_str = parse_job_id), help = "A job ID")]
     job_id: Option<esc_api::JobId>,
+
+    #[structopt(flatten)]
+    watch: watch::WatchArgs,
 }
 
 #[derive(StructOpt, Debug)]
@@ -1547,6 +2197,7 @@ enum IntegrationsCommand {
     Get(GetIntegration),
     Update(UpdateIntegration),
     TestIntegration(TestIntegration),
+    Options(IntegrationsOptions),
 }
 
 #[derive(Debug, StructOpt)]
@@ -1566,13 +2217,23 @@ pub struct CreateIntegration {
 pub enum CreateIntegrationData {
     OpsGenie(CreateOpsGenieIntegrationData),
     Slack(CreateSlackIntegrationData),
+    PagerDuty(CreatePagerDutyIntegrationData),
+    AwsCloudWatchLogs(CreateAwsCloudWatchLogsIntegrationData),
+    AwsCloudWatchMetrics(CreateAwsCloudWatchMetricsIntegrationData),
+    GcpLogging(CreateGcpLoggingIntegrationData),
 }
 
 #[derive(Debug, StructOpt)]
 #[structopt()]
 pub struct CreateOpsGenieIntegrationData {
-    #[structopt(long, help = "API key used with the Ops Genie integration API")]
-    pub api_key: String,
+    #[structopt(
+        long,
+        parse(try_from_str = secret::parse),
+        help = "API key used with the Ops Genie integration API. Accepts @FILE, env:VAR or - to read it from stdin"
+    )]
+    pub api_key: Option<Secret>,
+    #[structopt(long, help = "Integration source")]
+    pub source: Option<String>,
 }
 
 #[derive(Debug, StructOpt)]
@@ -1582,8 +2243,99 @@ pub struct CreateSlackIntegrationData {
     pub channel_id: String,
     #[structopt(long, help = "Integration source")]
     pub source: Option<String>,
-    #[structopt(long, help = "API token for the Slack bot")]
-    pub token: String,
+    #[structopt(
+        long,
+        parse(try_from_str = secret::parse),
+        help = "API token for the Slack bot. Accepts @FILE, env:VAR or - to read it from stdin"
+    )]
+    pub token: Option<Secret>,
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt()]
+pub struct CreatePagerDutyIntegrationData {
+    #[structopt(
+        long,
+        parse(try_from_str = secret::parse),
+        help = "Auth token used with the PagerDuty API. Accepts @FILE, env:VAR or - to read it from stdin"
+    )]
+    pub auth_token: Option<Secret>,
+    #[structopt(long, help = "PagerDuty service to raise incidents in")]
+    pub service: String,
+    #[structopt(long, help = "PagerDuty user incidents are raised as")]
+    pub user: String,
+    #[structopt(long, help = "Integration source")]
+    pub source: Option<String>,
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt()]
+pub struct CreateAwsCloudWatchLogsIntegrationData {
+    #[structopt(
+        long,
+        parse(try_from_str = secret::parse),
+        help = "AWS access key id. Accepts @FILE, env:VAR or - to read it from stdin"
+    )]
+    pub access_key_id: Option<Secret>,
+    #[structopt(
+        long,
+        parse(try_from_str = secret::parse),
+        help = "AWS secret access key. Accepts @FILE, env:VAR or - to read it from stdin"
+    )]
+    pub secret_access_key: Option<Secret>,
+    #[structopt(long, help = "Clusters whose logs are sent. Defaults to all clusters")]
+    pub cluster_ids: Vec<String>,
+    #[structopt(long, help = "CloudWatch log group")]
+    pub group_name: String,
+    #[structopt(long, help = "AWS region")]
+    pub region: String,
+    #[structopt(long, help = "Integration source")]
+    pub source: String,
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt()]
+pub struct CreateAwsCloudWatchMetricsIntegrationData {
+    #[structopt(
+        long,
+        parse(try_from_str = secret::parse),
+        help = "AWS access key id. Accepts @FILE, env:VAR or - to read it from stdin"
+    )]
+    pub access_key_id: Option<Secret>,
+    #[structopt(
+        long,
+        parse(try_from_str = secret::parse),
+        help = "AWS secret access key. Accepts @FILE, env:VAR or - to read it from stdin"
+    )]
+    pub secret_access_key: Option<Secret>,
+    #[structopt(
+        long,
+        help = "Clusters whose metrics are sent. Defaults to all clusters"
+    )]
+    pub cluster_ids: Vec<String>,
+    #[structopt(long, help = "CloudWatch metrics namespace")]
+    pub namespace: String,
+    #[structopt(long, help = "AWS region")]
+    pub region: String,
+    #[structopt(long, help = "Integration source")]
+    pub source: String,
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt()]
+pub struct CreateGcpLoggingIntegrationData {
+    #[structopt(long, help = "GCP project logs are written to")]
+    pub gcp_project_id: String,
+    #[structopt(
+        long,
+        parse(try_from_str = secret::parse),
+        help = "Private key of the GCP service account used to write logs. Accepts @FILE, env:VAR or - to read it from stdin"
+    )]
+    pub gcp_service_account_private_key: Option<Secret>,
+    #[structopt(long, help = "Name of the log")]
+    pub log_id: String,
+    #[structopt(long, help = "Integration source")]
+    pub source: Option<String>,
 }
 
 #[derive(Debug, StructOpt)]
@@ -1641,17 +2393,160 @@ pub struct UpdateIntegration {
     pub data: Option<UpdateIntegrationData>,
     #[structopt(long)]
     pub description: Option<String>,
+    // The flags update took before it had a subcommand per sink, kept so
+    // existing scripts don't break.
+    #[structopt(
+        long,
+        parse(try_from_str = secret::parse),
+        help = "Deprecated, use the ops-genie subcommand instead"
+    )]
+    pub api_key: Option<Secret>,
+    #[structopt(long, help = "Deprecated, use the slack subcommand instead")]
+    pub channel_id: Option<String>,
+    #[structopt(
+        long,
+        parse(try_from_str = secret::parse),
+        help = "Deprecated, use the slack subcommand instead"
+    )]
+    pub token: Option<Secret>,
+}
+
+impl UpdateIntegration {
+    /// The sink to update given the deprecated flags, which were told apart
+    /// by whether an API key was passed.
+    fn deprecated_data(&mut self) -> Option<UpdateIntegrationData> {
+        if let Some(api_key) = self.api_key.take() {
+            return Some(UpdateIntegrationData::OpsGenie(
+                UpdateOpsGenieIntegrationData {
+                    api_key: Some(api_key),
+                },
+            ));
+        }
+        if self.channel_id.is_some() || self.token.is_some() {
+            return Some(UpdateIntegrationData::Slack(UpdateSlackIntegrationData {
+                channel_id: self.channel_id.take(),
+                token: self.token.take(),
+            }));
+        }
+        None
+    }
+}
+
+#[derive(Debug, StructOpt)]
+pub enum UpdateIntegrationData {
+    OpsGenie(UpdateOpsGenieIntegrationData),
+    Slack(UpdateSlackIntegrationData),
+    PagerDuty(UpdatePagerDutyIntegrationData),
+    AwsCloudWatchLogs(UpdateAwsCloudWatchLogsIntegrationData),
+    AwsCloudWatchMetrics(UpdateAwsCloudWatchMetricsIntegrationData),
+    GcpLogging(UpdateGcpLoggingIntegrationData),
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt()]
+pub struct UpdateOpsGenieIntegrationData {
+    #[structopt(
+        long,
+        parse(try_from_str = secret::parse),
+        help = "API key used with the Ops Genie integration API. Accepts @FILE, env:VAR or - to read it from stdin"
+    )]
+    pub api_key: Option<Secret>,
 }
 
 #[derive(Debug, StructOpt)]
 #[structopt()]
-pub struct UpdateIntegrationData {
-    #[structopt(long, help = "API key used with the Ops Genie integration API")]
-    pub api_key: Option<String>,
+pub struct UpdateSlackIntegrationData {
     #[structopt(long, help = "Slack Channel to send messages to")]
     pub channel_id: Option<String>,
-    #[structopt(long, help = "API token for the Slack bot")]
-    pub token: Option<String>,
+    #[structopt(
+        long,
+        parse(try_from_str = secret::parse),
+        help = "API token for the Slack bot. Accepts @FILE, env:VAR or - to read it from stdin"
+    )]
+    pub token: Option<Secret>,
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt()]
+pub struct UpdatePagerDutyIntegrationData {
+    #[structopt(
+        long,
+        parse(try_from_str = secret::parse),
+        help = "Auth token used with the PagerDuty API. Accepts @FILE, env:VAR or - to read it from stdin"
+    )]
+    pub auth_token: Option<Secret>,
+    #[structopt(long, help = "PagerDuty service to raise incidents in")]
+    pub service: Option<String>,
+    #[structopt(long, help = "PagerDuty user incidents are raised as")]
+    pub user: Option<String>,
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt()]
+pub struct UpdateAwsCloudWatchLogsIntegrationData {
+    #[structopt(
+        long,
+        parse(try_from_str = secret::parse),
+        help = "AWS access key id. Accepts @FILE, env:VAR or - to read it from stdin"
+    )]
+    pub access_key_id: Option<Secret>,
+    #[structopt(
+        long,
+        parse(try_from_str = secret::parse),
+        help = "AWS secret access key. Accepts @FILE, env:VAR or - to read it from stdin"
+    )]
+    pub secret_access_key: Option<Secret>,
+    #[structopt(long, help = "Clusters whose logs are sent")]
+    pub cluster_ids: Option<Vec<String>>,
+    #[structopt(long, help = "CloudWatch log group")]
+    pub group_name: Option<String>,
+    #[structopt(long, help = "AWS region")]
+    pub region: Option<String>,
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt()]
+pub struct UpdateAwsCloudWatchMetricsIntegrationData {
+    #[structopt(
+        long,
+        parse(try_from_str = secret::parse),
+        help = "AWS access key id. Accepts @FILE, env:VAR or - to read it from stdin"
+    )]
+    pub access_key_id: Option<Secret>,
+    #[structopt(
+        long,
+        parse(try_from_str = secret::parse),
+        help = "AWS secret access key. Accepts @FILE, env:VAR or - to read it from stdin"
+    )]
+    pub secret_access_key: Option<Secret>,
+    #[structopt(long, help = "Clusters whose metrics are sent")]
+    pub cluster_ids: Option<Vec<String>>,
+    #[structopt(long, help = "CloudWatch metrics namespace")]
+    pub namespace: Option<String>,
+    #[structopt(long, help = "AWS region")]
+    pub region: Option<String>,
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt()]
+pub struct UpdateGcpLoggingIntegrationData {
+    #[structopt(long, help = "GCP project logs are written to")]
+    pub gcp_project_id: Option<String>,
+    #[structopt(
+        long,
+        parse(try_from_str = secret::parse),
+        help = "Private key of the GCP service account used to write logs. Accepts @FILE, env:VAR or - to read it from stdin"
+    )]
+    pub gcp_service_account_private_key: Option<Secret>,
+    #[structopt(long, help = "Name of the log")]
+    pub log_id: Option<String>,
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "Lists the integration sources and sinks and what they support")]
+pub struct IntegrationsOptions {
+    #[structopt(long, help="The id of the organization",  parse(try_from_str = parse_org_id), default_value = "")]
+    pub organization_id: OrgId,
 }
 
 lazy_static! {
@@ -1664,6 +2559,28 @@ lazy_static! {
     };
 }
 
+lazy_static! {
+    static ref WAITABLE_KINDS: HashMap<&'static str, resolve::Kind> = {
+        let mut map = HashMap::new();
+        map.insert("cluster", resolve::Kind::Cluster);
+        map.insert("network", resolve::Kind::Network);
+        map.insert("peering", resolve::Kind::Peering);
+        map.insert("acl", resolve::Kind::Acl);
+        map.insert("backup", resolve::Kind::Backup);
+        map
+    };
+}
+
+lazy_static! {
+    static ref ON_PROTECTED: HashMap<&'static str, esc_api::teardown::OnProtected> = {
+        let mut map = HashMap::new();
+        map.insert("abort", esc_api::teardown::OnProtected::Abort);
+        map.insert("skip", esc_api::teardown::OnProtected::Skip);
+        map.insert("unprotect", esc_api::teardown::OnProtected::Unprotect);
+        map
+    };
+}
+
 lazy_static! {
     static ref CONTEXT_PROP_NAMES: HashMap<&'static str, ProfilePropName> = {
         let mut map = HashMap::new();
@@ -1704,7 +2621,7 @@ fn parse_output_format(src: &str) -> Result<OutputFormat, String> {
         if let Some(value) = profile_opt.and_then(|p| p.output_format.as_ref()) {
             return Ok(value.clone());
         }
-        return Ok(OutputFormat::Cli);
+        return Ok(OutputFormat::Table);
     }
     OutputFormat::from_str(src)
 }
@@ -1777,8 +2694,16 @@ fn parse_provider(src: &str) -> Result<esc_api::infra::Provider, String> {
     parse_enum(&PROVIDERS, src)
 }
 
-fn parse_context_prop_name(src: &str) -> Result<ProfilePropName, String> {
-    parse_enum(&CONTEXT_PROP_NAMES, src)
+fn parse_waitable_kind(src: &str) -> Result<resolve::Kind, String> {
+    parse_enum(&WAITABLE_KINDS, src)
+}
+
+fn parse_on_protected(src: &str) -> Result<esc_api::teardown::OnProtected, String> {
+    parse_enum(&ON_PROTECTED, src)
+}
+
+fn parse_context_prop_name(src: &str) -> Result<ProfilePropName, String> {
+    parse_enum(&CONTEXT_PROP_NAMES, src)
 }
 
 fn parse_topology(src: &str) -> Result<esc_api::mesdb::Topology, String> {
@@ -1853,19 +2778,68 @@ fn parse_cidr(src: &str) -> Result<cidr::Ipv4Cidr, cidr::NetworkParseError> {
 }
 
 struct Printer {
+    pub format: OutputFormat,
     pub render_in_json: bool,
-    pub render_as_v1: bool,
+    /// Used by the table and CSV formats.
+    pub table: table::TableOptions,
+    pub filters: Vec<filter::Filter>,
+    pub query: Option<query::Query>,
+    pub template: Option<template::Template>,
 }
 
 impl Printer {
-    pub fn print<A: std::fmt::Debug + Serialize + v1::ToV1>(
+    /// Whether responses are reshaped by `--query` or `--template`, in which
+    /// case they're rendered from the API types whatever the format.
+    pub fn shapes_output(&self) -> bool {
+        self.query.is_some() || self.template.is_some()
+    }
+
+    /// Whether the api formats render responses as JSON instead of showing
+    /// the traffic, since the traffic can't be filtered or reshaped.
+    pub fn replaces_traffic(&self) -> bool {
+        self.format.shows_traffic() && (self.shapes_output() || !self.filters.is_empty())
+    }
+
+    fn filtered<A: Serialize>(
+        &self,
+        value: &A,
+    ) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        Ok(filter::apply(serde_json::to_value(value)?, &self.filters)?)
+    }
+
+    pub fn print<A: std::fmt::Debug + Serialize + DeserializeOwned + v1::ToV1>(
         &self,
         value: A,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        if self.render_as_v1 {
+        if self.shapes_output() || self.replaces_traffic() {
+            return self.print_shaped(&value);
+        }
+        if self.format.is_structured() {
+            return self.print_structured(self.filtered(&value)?);
+        }
+        if self.format.is_v1() {
+            // Filters name the fields of the API response in every format, so
+            // it's filtered before being turned into its v1 counterpart.
+            let value = if self.filters.is_empty() {
+                value
+            } else {
+                serde_json::from_value::<A>(self.filtered(&value)?)?
+            };
             let value = value.to_v1();
             if self.render_in_json {
                 serde_json::to_writer_pretty(std::io::stdout(), &value)?;
+            } else if let (OutputFormat::Table, v1::Table::Columns(columns)) =
+                (&self.format, A::TABLE)
+            {
+                let value = serde_json::to_value(&value)?;
+                match table::rows(value.clone()) {
+                    Some(rows) => print!("{}", table::render(rows, columns, &self.table)?),
+                    None => println!("{}", table::cell(Some(&value))),
+                }
+            } else if !self.filters.is_empty() {
+                return Err(
+                    "This output can't be filtered, try --json or --fmt yaml instead".into(),
+                );
             } else {
                 println!("{:?}", value);
             }
@@ -1877,8 +2851,68 @@ impl Printer {
         &self,
         value: A,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        if self.render_as_v1 {
+        if self.shapes_output() || self.replaces_traffic() {
+            return self.print_shaped(&value);
+        }
+        if self.format.is_structured() {
+            return self.print_structured(self.filtered(&value)?);
+        }
+        if self.format.is_v1() {
+            serde_json::to_writer_pretty(std::io::stdout(), &self.filtered(&value)?)?;
+        }
+        Ok(())
+    }
+
+    // Renders the API types themselves, rather than their v1 counterparts.
+    fn print_structured(&self, value: serde_json::Value) -> Result<(), Box<dyn std::error::Error>> {
+        match self.format {
+            OutputFormat::Yaml => print!("{}", serde_yaml::to_string(&value)?),
+            OutputFormat::Csv => match table::rows(value.clone()) {
+                Some(rows) => print!("{}", table::csv(rows, &self.table)?),
+                None => println!("{}", table::cell(Some(&value))),
+            },
+            _ => {
+                for item in table::items(value) {
+                    println!("{}", serde_json::to_string(&item)?);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The API response once filtered and queried, as the structured formats
+    /// render it.
+    pub fn shaped<A: Serialize>(
+        &self,
+        value: &A,
+    ) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        let value = self.filtered(value)?;
+        match self.query.as_ref() {
+            Some(query) => Ok(query.search(&value)?),
+            None => Ok(value),
+        }
+    }
+
+    // Filters the API response and applies the query, then renders the result
+    // with the template, or failing that the format. The v1 formats don't
+    // apply to query results, so they show every column or plain JSON instead.
+    fn print_shaped<A: Serialize>(&self, value: &A) -> Result<(), Box<dyn std::error::Error>> {
+        let value = self.shaped(value)?;
+
+        if let Some(template) = self.template.as_ref() {
+            for item in table::items(value) {
+                println!("{}", template.render(&item));
+            }
+        } else if self.format.is_structured() {
+            self.print_structured(value)?;
+        } else if let OutputFormat::Table = self.format {
+            match table::rows(value.clone()) {
+                Some(rows) => print!("{}", table::render(rows, &[], &self.table)?),
+                None => println!("{}", table::cell(Some(&value))),
+            }
+        } else {
             serde_json::to_writer_pretty(std::io::stdout(), &value)?;
+            println!();
         }
         Ok(())
     }
@@ -1950,11 +2984,37 @@ impl esc_api::RequestObserver for TrafficSpy {
             println!("{}", body);
         };
     }
+
+    fn on_skipped(&self, method: &str, url: &str, body: &str) {
+        println!("dry run, not sent: {} {}", method, url);
+        if !body.is_empty() {
+            println!("{}", body);
+        }
+    }
+}
+
+// Tells about the requests --dry-run skipped when the output format doesn't
+// show the traffic. Goes to stderr to keep the output parseable.
+struct DryRunReporter;
+
+impl esc_api::RequestObserver for DryRunReporter {
+    fn on_request(&self, _method: &str, _url: &str, _body: &str) {}
+
+    fn on_response(&self, _status: &str, _body: &str) {}
+
+    fn on_skipped(&self, method: &str, url: &str, body: &str) {
+        eprintln!("Dry run, not sending: {} {}", method, url);
+        if !body.is_empty() {
+            eprintln!("{}", body);
+        }
+    }
 }
 
 struct ClientBuilder {
     base_url: String,
     observer: Option<Arc<dyn esc_api::RequestObserver + Send + Sync>>,
+    redactor: esc_api::Redactor,
+    mode: esc_api::SendMode,
     refresh_token: Option<String>,
     token_config: esc_api::TokenConfig,
     noninteractive: bool,
@@ -1969,6 +3029,8 @@ impl ClientBuilder {
         let sender = esc_api::RequestSender {
             client: build_http_client(),
             observer: self.observer,
+            redactor: self.redactor,
+            mode: self.mode,
         };
         let client = esc_api::Client {
             authorization: std::sync::Arc::new(authorization),
@@ -1997,21 +3059,37 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         })
         .unwrap_or_else(|| constants::ES_CLOUD_API_URL.to_string());
 
-    let observer: Option<Arc<dyn esc_api::RequestObserver + Send + Sync>> =
-        if !opt.output_format.is_v1() {
-            Some(Arc::new(TrafficSpy {
-                verbose: matches!(opt.output_format, OutputFormat::ApiVerbose),
-            }))
-        } else {
-            None
-        };
-
     let printer = Printer {
+        format: opt.output_format.clone(),
         render_in_json: match opt.output_format {
             OutputFormat::CliJson => true,
             _ => opt.render_in_json,
         },
-        render_as_v1: opt.output_format.is_v1(),
+        table: table::TableOptions {
+            columns: opt.columns.clone(),
+            sort_by: opt.sort_by.clone(),
+            no_headers: opt.no_headers,
+            width: match opt.output_format {
+                OutputFormat::Table => {
+                    terminal_size::terminal_size().map(|(width, _)| width.0 as usize)
+                }
+                _ => None,
+            },
+        },
+        filters: opt.filters.clone(),
+        query: opt.query.clone(),
+        template: opt.template.clone(),
+    };
+
+    let shows_traffic = opt.output_format.shows_traffic() && !printer.replaces_traffic();
+    let observer: Option<Arc<dyn esc_api::RequestObserver + Send + Sync>> = if shows_traffic {
+        Some(Arc::new(TrafficSpy {
+            verbose: matches!(opt.output_format, OutputFormat::ApiVerbose),
+        }))
+    } else if opt.dry_run {
+        Some(Arc::new(DryRunReporter))
+    } else {
+        None
     };
 
     config::Settings::configure().await?;
@@ -2045,13 +3123,34 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let client_builder = ClientBuilder {
         base_url,
         observer,
-        refresh_token: opt.refresh_token.clone(),
+        redactor: if opt.show_secrets {
+            esc_api::Redactor::default()
+        } else {
+            esc_api::redactor()
+        },
+        mode: if opt.as_curl {
+            esc_api::SendMode::Curl
+        } else if opt.dry_run {
+            esc_api::SendMode::DryRun
+        } else {
+            esc_api::SendMode::Send
+        },
+        refresh_token: opt.refresh_token.clone().map(Secret::into_inner),
         token_config: token_config.clone(),
         noninteractive: opt.noninteractive,
     };
 
-    let silence_errors = !opt.output_format.is_v1();
+    let silence_errors = shows_traffic;
     let result = call_api(clap_app, opt, client_builder, printer, token_config).await;
+    if let Err(err) = result.as_ref() {
+        if let Some(curl) = err
+            .downcast_ref::<esc_api::Error>()
+            .and_then(esc_api::Error::not_sent)
+        {
+            println!("{}", curl);
+            return Ok(());
+        }
+    }
     if !silence_errors {
         result
     } else if let Err(err) = result {
@@ -2068,6 +3167,126 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 }
 
+// The API only reports whether MFA is enforced for the organization as a whole,
+// so when it isn't every active member is listed as possibly lacking MFA.
+async fn warn_members_without_mfa(
+    client: &esc_api::Client,
+    org_id: &OrgId,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let client = utils::behind_the_scenes(client);
+    let settings = esc_api::access::get_settings(&client, org_id.clone()).await?;
+    let mfa = esc_api::resources::get_mfa_status(&client, org_id.clone()).await?;
+
+    if settings.settings.require_mfa || mfa.mfa_enabled {
+        return Ok(());
+    }
+
+    let members: Vec<esc_api::access::Member> =
+        esc_api::access::list_members(&client, org_id.clone())
+            .await?
+            .members
+            .into_iter()
+            .filter(|m| m.active)
+            .collect();
+
+    if members.is_empty() {
+        return Ok(());
+    }
+
+    eprintln!(
+        "Warning: MFA isn't enforced in this organization yet. Members who haven't set up MFA \
+         won't be able to sign in until they do. Make sure these {} members have:",
+        members.len()
+    );
+    for member in members {
+        eprintln!("  {} <{}>", member.name, member.email);
+    }
+
+    Ok(())
+}
+
+// Reads the "<action> <resource>" pairs of `esc access policies check --file`.
+fn read_policy_checks(
+    file: &str,
+) -> Result<Vec<(esc_api::access::Action, String)>, Box<dyn std::error::Error>> {
+    let contents = if file == "-" {
+        let mut contents = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut contents)?;
+        contents
+    } else {
+        std::fs::read_to_string(file)?
+    };
+
+    let mut checks = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (action, resource) = match line.split_once(char::is_whitespace) {
+            Some((action, resource)) => (action, resource.trim()),
+            None => {
+                return Err(
+                    format!("{}:{}: expected \"<action> <resource>\"", file, index + 1).into(),
+                )
+            }
+        };
+
+        let action =
+            utils::parse_action(action).map_err(|e| format!("{}:{}: {}", file, index + 1, e))?;
+        checks.push((action, resource.to_string()));
+    }
+
+    Ok(checks)
+}
+
+// Refuses to test integrations whose sink can't receive test messages, as
+// reported by the integration options. Sinks the options don't mention are let
+// through.
+async fn ensure_supports_test_api(
+    client: &esc_api::Client,
+    organization_id: &OrgId,
+    project_id: &esc_api::ProjectId,
+    integration_id: &esc_api::IntegrationId,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let client = utils::behind_the_scenes(client);
+    let integration = esc_api::integrate::get_integration(
+        &client,
+        organization_id.clone(),
+        project_id.clone(),
+        integration_id.clone(),
+    )
+    .await?
+    .integration;
+    let options =
+        esc_api::integrate::get_integrations_options(&client, organization_id.clone()).await?;
+
+    // The sink is the tag of the integration data and the source one of its fields.
+    let data = serde_json::to_value(&integration.data)?;
+    let sink_id = data["sink"].as_str().unwrap_or_default();
+    let source_id = data["source"].as_str();
+
+    let sink = options
+        .sources
+        .iter()
+        .filter(|source| match source_id {
+            Some(id) => id == source.id,
+            None => true,
+        })
+        .flat_map(|source| source.sinks.iter())
+        .find(|sink| sink.id == sink_id);
+
+    match sink {
+        Some(sink) if !sink.supports_test_api => Err(format!(
+            "Integration {} can't be tested, {} sinks don't support test messages",
+            integration_id, sink.name
+        )
+        .into()),
+        _ => Ok(()),
+    }
+}
+
 async fn call_api<'a, 'b>(
     mut clap_app: clap::App<'a, 'b>,
     opt: Opt,
@@ -2080,6 +3299,7 @@ async fn call_api<'a, 'b>(
             AccessCommand::Groups(groups) => match groups.groups_command {
                 GroupsCommand::Create(params) => {
                     let client = client_builder.create().await?;
+                    let org_id = resolve::org(&client, params.org_id).await?;
                     let create_params = esc_api::access::CreateGroupRequest {
                         name: params.name,
                         members: Some(
@@ -2090,39 +3310,44 @@ async fn call_api<'a, 'b>(
                                 .collect(),
                         ),
                     };
-                    let resp = esc_api::access::create_group(&client, params.org_id, create_params)
-                        .await?;
+                    let resp =
+                        esc_api::access::create_group(&client, org_id, create_params).await?;
 
                     printer.print(resp)?;
                 }
 
                 GroupsCommand::Update(params) => {
                     let client = client_builder.create().await?;
+                    let org_id = resolve::org(&client, params.org_id).await?;
+                    let id = resolve::group(&client, &org_id, params.id).await?;
                     let body = esc_api::access::UpdateGroupRequest {
                         members: params.members,
                         name: params.name,
                     };
-                    esc_api::access::update_group(&client, params.org_id, params.id, body).await?;
+                    esc_api::access::update_group(&client, org_id, id, body).await?;
                 }
 
                 GroupsCommand::Get(params) => {
                     let client = client_builder.create().await?;
-                    let resp =
-                        esc_api::access::get_group(&client, params.org_id, params.id).await?;
+                    let org_id = resolve::org(&client, params.org_id).await?;
+                    let id = resolve::group(&client, &org_id, params.id).await?;
+                    let resp = esc_api::access::get_group(&client, org_id, id).await?;
                     printer.print(resp)?;
                 }
 
                 GroupsCommand::Delete(params) => {
                     let client = client_builder.create().await?;
-                    esc_api::access::delete_group(&client, params.org_id, params.id).await?;
+                    let org_id = resolve::org(&client, params.org_id).await?;
+                    let id = resolve::group(&client, &org_id, params.id).await?;
+                    esc_api::access::delete_group(&client, org_id, id).await?;
                 }
 
                 GroupsCommand::List(params) => {
                     let client = client_builder.create().await?;
+                    let org_id = resolve::org(&client, params.org_id).await?;
                     let linked_resource = None; // TODO: add this as a parameter
                     let resp =
-                        esc_api::access::list_groups(&client, params.org_id, linked_resource)
-                            .await?;
+                        esc_api::access::list_groups(&client, org_id, linked_resource).await?;
                     printer.print(resp)?;
                 }
             },
@@ -2130,9 +3355,10 @@ async fn call_api<'a, 'b>(
             AccessCommand::Invites(invites) => match invites.invites_command {
                 InvitesCommand::Create(params) => {
                     let client = client_builder.create().await?;
+                    let org_id = resolve::org(&client, params.org_id).await?;
                     let resp = esc_api::access::create_invite(
                         &client,
-                        params.org_id,
+                        org_id,
                         esc_api::access::CreateInviteRequest {
                             groups: params.group,
                             user_email: params.email,
@@ -2145,9 +3371,10 @@ async fn call_api<'a, 'b>(
 
                 InvitesCommand::Resend(params) => {
                     let client = client_builder.create().await?;
+                    let org_id = resolve::org(&client, params.org_id).await?;
                     esc_api::access::resend_invite(
                         &client,
-                        params.org_id,
+                        org_id,
                         esc_api::access::ResendInviteRequest { id: params.id },
                     )
                     .await?;
@@ -2155,12 +3382,14 @@ async fn call_api<'a, 'b>(
 
                 InvitesCommand::Delete(params) => {
                     let client = client_builder.create().await?;
-                    esc_api::access::delete_invite(&client, params.org_id, params.id).await?;
+                    let org_id = resolve::org(&client, params.org_id).await?;
+                    esc_api::access::delete_invite(&client, org_id, params.id).await?;
                 }
 
                 InvitesCommand::List(params) => {
                     let client = client_builder.create().await?;
-                    let resp = esc_api::access::list_invites(&client, params.org_id).await?;
+                    let org_id = resolve::org(&client, params.org_id).await?;
+                    let resp = esc_api::access::list_invites(&client, org_id).await?;
                     printer.print(resp)?;
                 }
             },
@@ -2179,7 +3408,9 @@ async fn call_api<'a, 'b>(
                             let token = match params.email {
                                 Some(email) => match params.unsafe_password {
                                     Some(password) => {
-                                        store.create_token(&client, email, password).await
+                                        store
+                                            .create_token(&client, email, password.into_inner())
+                                            .await
                                     }
                                     None => {
                                         store
@@ -2193,6 +3424,16 @@ async fn call_api<'a, 'b>(
                         }
                     }
                 }
+                TokensCommand::PrintAccessToken(_params) => {
+                    let token = get_token(
+                        token_config,
+                        client_builder.refresh_token,
+                        client_builder.noninteractive,
+                    )
+                    .await?;
+                    println!("{}", token.access_token());
+                }
+
                 TokensCommand::Display(_params) => {
                     let store = esc_client_store::token_store(token_config).await?;
 
@@ -2209,9 +3450,10 @@ async fn call_api<'a, 'b>(
             AccessCommand::Policies(policies) => match policies.policies_command {
                 PoliciesCommand::Create(params) => {
                     let client = client_builder.create().await?;
+                    let org_id = resolve::org(&client, params.org_id).await?;
                     let resp = esc_api::access::create_policy(
                         &client,
-                        params.org_id,
+                        org_id,
                         esc_api::access::CreatePolicyRequest {
                             policy: esc_api::access::CreatePolicy {
                                 actions: utils::actions_from_str_vec(params.actions),
@@ -2228,9 +3470,10 @@ async fn call_api<'a, 'b>(
 
                 PoliciesCommand::Update(params) => {
                     let client = client_builder.create().await?;
+                    let org_id = resolve::org(&client, params.org_id).await?;
                     esc_api::access::update_policy(
                         &client,
-                        params.org_id,
+                        org_id,
                         params.policy,
                         esc_api::access::UpdatePolicyRequest {
                             policy: esc_api::access::UpdatePolicy {
@@ -2247,43 +3490,119 @@ async fn call_api<'a, 'b>(
 
                 PoliciesCommand::Delete(params) => {
                     let client = client_builder.create().await?;
-                    esc_api::access::delete_policy(&client, params.org_id, params.policy).await?;
+                    let org_id = resolve::org(&client, params.org_id).await?;
+                    esc_api::access::delete_policy(&client, org_id, params.policy).await?;
                 }
 
                 PoliciesCommand::Get(params) => {
                     let client = client_builder.create().await?;
-                    let resp =
-                        esc_api::access::get_policy(&client, params.org_id, params.policy).await?;
+                    let org_id = resolve::org(&client, params.org_id).await?;
+                    let resp = esc_api::access::get_policy(&client, org_id, params.policy).await?;
                     printer.print(resp)?;
                 }
 
                 PoliciesCommand::List(params) => {
                     let client = client_builder.create().await?;
-                    let resp = esc_api::access::list_policies(&client, params.org_id).await?;
+                    let org_id = resolve::org(&client, params.org_id).await?;
+                    let resp = esc_api::access::list_policies(&client, org_id).await?;
                     printer.print(resp)?;
                 }
+
+                PoliciesCommand::Check(params) => {
+                    let checks = match (params.file, params.action, params.resource) {
+                        (Some(file), _, _) => read_policy_checks(&file)?,
+                        (None, Some(action), Some(resource)) => vec![(action, resource)],
+                        _ => {
+                            return Err(
+                                "Either --file or both --action and --resource are required".into(),
+                            )
+                        }
+                    };
+
+                    let client = client_builder.create().await?;
+                    let mut results = Vec::new();
+                    for (action, resource) in checks {
+                        let resp = esc_api::access::policy_allowed(
+                            &client,
+                            esc_api::access::PolicyAllowedRequest {
+                                action: action.clone(),
+                                resource: resource.clone(),
+                            },
+                        )
+                        .await?;
+                        results.push(v1::PolicyCheck {
+                            action,
+                            resource,
+                            allowed: resp.allowed,
+                        });
+                    }
+
+                    let all_allowed = results.iter().all(|r| r.allowed);
+                    printer.print(v1::List(results))?;
+
+                    if !all_allowed {
+                        std::process::exit(constants::EXIT_CODE_POLICY_DENIED);
+                    }
+                }
             },
 
+            AccessCommand::Settings(settings) => match settings.settings_command {
+                SettingsCommand::Get(params) => {
+                    let client = client_builder.create().await?;
+                    let org_id = resolve::org(&client, params.org_id).await?;
+                    let resp = esc_api::access::get_settings(&client, org_id).await?;
+                    printer.print(resp)?;
+                }
+                SettingsCommand::Update(params) => {
+                    let client = client_builder.create().await?;
+                    let org_id = resolve::org(&client, params.org_id).await?;
+
+                    if params.require_mfa == Some(true) {
+                        // The warning is only a courtesy, so failing to look
+                        // up the members mustn't stop the update.
+                        if let Err(err) = warn_members_without_mfa(&client, &org_id).await {
+                            eprintln!(
+                                "Warning: could not check which members would be locked out: {}",
+                                err
+                            );
+                        }
+                    }
+
+                    esc_api::access::update_setting(
+                        &client,
+                        org_id,
+                        esc_api::access::UpdateSettingsRequest {
+                            require_mfa: params.require_mfa,
+                            restrict_invite_domain: params.restrict_invite_domain,
+                        },
+                    )
+                    .await?;
+                }
+            },
             AccessCommand::Members(members) => match members.members_command {
                 MembersCommand::List(params) => {
                     let client = client_builder.create().await?;
-                    let resp = esc_api::access::list_members(&client, params.org_id).await?;
+                    let org_id = resolve::org(&client, params.org_id).await?;
+                    let resp = esc_api::access::list_members(&client, org_id).await?;
                     printer.print(resp)?;
                 }
 
                 MembersCommand::Get(params) => {
                     let client = client_builder.create().await?;
-                    let resp =
-                        esc_api::access::get_member(&client, params.org_id, params.id).await?;
+                    let org_id = resolve::org(&client, params.org_id).await?;
+                    let id = resolve::member(&client, &org_id, params.id).await?;
+                    let resp = esc_api::access::get_member(&client, org_id, id).await?;
                     printer.print(resp)?;
                 }
 
                 MembersCommand::Update(params) => {
                     let client = client_builder.create().await?;
+                    let org_id = resolve::org(&client, params.org_id).await?;
+                    let id = resolve::member(&client, &org_id, params.id).await?;
                     esc_api::access::update_member(
                         &client,
-                        params.org_id,
-                        params.id,
+                        org_id,
+                        id,
                         esc_api::access::UpdateMemberRequest {
                             active: params.active,
                         },
@@ -2293,7 +3612,9 @@ async fn call_api<'a, 'b>(
 
                 MembersCommand::Delete(params) => {
                     let client = client_builder.create().await?;
-                    esc_api::access::delete_member(&client, params.org_id, params.id).await?;
+                    let org_id = resolve::org(&client, params.org_id).await?;
+                    let id = resolve::member(&client, &org_id, params.id).await?;
+                    esc_api::access::delete_member(&client, org_id, id).await?;
                 }
             },
         },
@@ -2318,14 +3639,10 @@ async fn call_api<'a, 'b>(
                         None => {}
                     }
                     let client = client_builder.create().await?;
-                    let resp = esc_api::audit::get_audit_by_org(
-                        &client,
-                        params.org_id,
-                        before,
-                        after,
-                        limit,
-                    )
-                    .await?;
+                    let org_id = resolve::org(&client, params.org_id).await?;
+                    let resp =
+                        esc_api::audit::get_audit_by_org(&client, org_id, before, after, limit)
+                            .await?;
                     printer.print(resp)?;
                 }
             },
@@ -2365,52 +3682,62 @@ async fn call_api<'a, 'b>(
             InfraCommand::Acls(acls) => match acls.acls_command {
                 AclsCommand::Create(params) => {
                     let client = client_builder.create().await?;
+                    let (org_id, project_id) =
+                        resolve::scope(&client, params.org_id, params.project_id).await?;
                     let resp = esc_api::infra::create_acl(
                         &client,
-                        params.org_id,
-                        params.project_id,
+                        org_id.clone(),
+                        project_id.clone(),
                         esc_api::infra::CreateAclRequest {
                             cidr_blocks: params.cidr_blocks,
                             description: params.description,
                         },
                     )
                     .await?;
+                    let id = resp.id.clone();
                     printer.print_json_only(resp)?;
-                }
-                AclsCommand::Delete(params) => {
-                    let client = client_builder.create().await?;
-                    esc_api::infra::delete_acl(
+                    wait::acl(
                         &client,
-                        params.org_id,
-                        params.project_id,
-                        params.id,
+                        &org_id,
+                        &project_id,
+                        &id,
+                        esc_api::wait::Target::ready::<esc_api::infra::Acl>(),
+                        &params.wait,
                     )
                     .await?;
                 }
+                AclsCommand::Delete(params) => {
+                    let client = client_builder.create().await?;
+                    let (org_id, project_id) =
+                        resolve::scope(&client, params.org_id, params.project_id).await?;
+                    let id = resolve::acl(&client, &org_id, &project_id, params.id).await?;
+                    esc_api::infra::delete_acl(&client, org_id, project_id, id).await?;
+                }
                 AclsCommand::Get(params) => {
                     let client = client_builder.create().await?;
-                    let resp = esc_api::infra::get_acl(
-                        &client,
-                        params.org_id,
-                        params.project_id,
-                        params.id,
-                    )
-                    .await?;
+                    let (org_id, project_id) =
+                        resolve::scope(&client, params.org_id, params.project_id).await?;
+                    let id = resolve::acl(&client, &org_id, &project_id, params.id).await?;
+                    let resp = esc_api::infra::get_acl(&client, org_id, project_id, id).await?;
                     printer.print_json_only(resp)?;
                 }
                 AclsCommand::List(params) => {
                     let client = client_builder.create().await?;
-                    let resp = esc_api::infra::list_acls(&client, params.org_id, params.project_id)
-                        .await?;
+                    let (org_id, project_id) =
+                        resolve::scope(&client, params.org_id, params.project_id).await?;
+                    let resp = esc_api::infra::list_acls(&client, org_id, project_id).await?;
                     printer.print_json_only(resp)?;
                 }
                 AclsCommand::Update(params) => {
                     let client = client_builder.create().await?;
+                    let (org_id, project_id) =
+                        resolve::scope(&client, params.org_id, params.project_id).await?;
+                    let id = resolve::acl(&client, &org_id, &project_id, params.id).await?;
                     esc_api::infra::update_acl(
                         &client,
-                        params.org_id,
-                        params.project_id,
-                        params.id,
+                        org_id,
+                        project_id,
+                        id,
                         esc_api::infra::UpdateAclRequest {
                             cidr_blocks: Some(params.cidr_blocks),
                             description: params.description,
@@ -2423,10 +3750,12 @@ async fn call_api<'a, 'b>(
                 NetworksCommand::Create(params) => {
                     let cidr_block = params.cidr_block.map(|cidr| cidr.to_string());
                     let client = client_builder.create().await?;
+                    let (org_id, project_id) =
+                        resolve::scope(&client, params.org_id, params.project_id).await?;
                     let resp = esc_api::infra::create_network(
                         &client,
-                        params.org_id,
-                        params.project_id,
+                        org_id.clone(),
+                        project_id.clone(),
                         esc_api::infra::CreateNetworkRequest {
                             cidr_block,
                             description: params.description,
@@ -2436,16 +3765,29 @@ async fn call_api<'a, 'b>(
                         },
                     )
                     .await?;
+                    let id = resp.id.clone();
                     printer.print(resp)?;
+                    wait::network(
+                        &client,
+                        &org_id,
+                        &project_id,
+                        &id,
+                        esc_api::wait::Target::ready::<esc_api::infra::Network>(),
+                        &params.wait,
+                    )
+                    .await?;
                 }
 
                 NetworksCommand::Update(params) => {
                     let client = client_builder.create().await?;
+                    let (org_id, project_id) =
+                        resolve::scope(&client, params.org_id, params.project_id).await?;
+                    let id = resolve::network(&client, &org_id, &project_id, params.id).await?;
                     esc_api::infra::update_network(
                         &client,
-                        params.org_id,
-                        params.project_id,
-                        params.id,
+                        org_id,
+                        project_id,
+                        id,
                         esc_api::infra::UpdateNetworkRequest {
                             description: params.description,
                         },
@@ -2454,47 +3796,66 @@ async fn call_api<'a, 'b>(
                 }
 
                 NetworksCommand::Delete(params) => {
+                    let noninteractive = client_builder.noninteractive;
                     let client = client_builder.create().await?;
-                    esc_api::infra::delete_network(
-                        &client,
-                        params.org_id,
-                        params.project_id,
-                        params.id,
-                    )
-                    .await?;
+                    let (org_id, project_id) =
+                        resolve::scope(&client, params.org_id, params.project_id).await?;
+                    let id = resolve::network(&client, &org_id, &project_id, params.id).await?;
+                    if confirm::needed(&client, &params.confirm, "network", noninteractive)? {
+                        let network = esc_api::infra::get_network(
+                            &utils::behind_the_scenes(&client),
+                            org_id.clone(),
+                            project_id.clone(),
+                            id.clone(),
+                        )
+                        .await?
+                        .network;
+                        confirm::delete("network", id.as_ref(), &network.description)?;
+                    }
+                    esc_api::infra::delete_network(&client, org_id, project_id, id).await?;
                 }
 
                 NetworksCommand::Get(params) => {
                     let client = client_builder.create().await?;
-                    let resp = esc_api::infra::get_network(
-                        &client,
-                        params.org_id,
-                        params.project_id,
-                        params.id,
-                    )
+                    let (org_id, project_id) =
+                        resolve::scope(&client, params.org_id, params.project_id).await?;
+                    let id = resolve::network(&client, &org_id, &project_id, params.id).await?;
+                    watch::run(&printer, &params.watch, || {
+                        esc_api::infra::get_network(
+                            &client,
+                            org_id.clone(),
+                            project_id.clone(),
+                            id.clone(),
+                        )
+                    })
                     .await?;
-                    printer.print(resp)?;
                 }
 
                 NetworksCommand::List(params) => {
                     let client = client_builder.create().await?;
-                    let resp =
-                        esc_api::infra::list_networks(&client, params.org_id, params.project_id)
-                            .await?;
-                    printer.print(resp)?;
+                    let (org_id, project_id) =
+                        resolve::scope(&client, params.org_id, params.project_id).await?;
+                    watch::run(&printer, &params.watch, || {
+                        esc_api::infra::list_networks(&client, org_id.clone(), project_id.clone())
+                    })
+                    .await?;
                 }
             },
 
             InfraCommand::Peerings(peerings) => match peerings.peerings_command {
                 PeeringsCommand::Create(params) => {
                     let client = client_builder.create().await?;
+                    let (org_id, project_id) =
+                        resolve::scope(&client, params.org_id, params.project_id).await?;
+                    let network_id =
+                        resolve::network(&client, &org_id, &project_id, params.network_id).await?;
                     let result = esc_api::infra::create_peering(
                         &client,
-                        params.org_id.clone(),
-                        params.project_id.clone(),
+                        org_id.clone(),
+                        project_id.clone(),
                         esc_api::infra::CreatePeeringRequest {
                             description: params.description,
-                            network_id: params.network_id.clone(),
+                            network_id: network_id.clone(),
                             peer_account_id: params.peer_account_id.clone(),
                             peer_network_id: params.peer_network_id.clone(),
                             peer_network_region: params.peer_network_region,
@@ -2503,35 +3864,50 @@ async fn call_api<'a, 'b>(
                     )
                     .await;
 
-                    if let Err(_err) = result {
-                        let network = esc_api::infra::get_network(
-                            &client,
-                            params.org_id.clone(),
-                            params.project_id.clone(),
-                            params.network_id.clone(),
-                        )
-                        .await?;
+                    match result {
+                        Ok(resp) => {
+                            let id = resp.id.clone();
+                            printer.print(resp)?;
+                            wait::peering(
+                                &client,
+                                &org_id,
+                                &project_id,
+                                &id,
+                                esc_api::wait::Target::ready::<esc_api::infra::Peering>(),
+                                &params.wait,
+                            )
+                            .await?;
+                        }
+                        Err(_err) => {
+                            let network = esc_api::infra::get_network(
+                                &client,
+                                org_id.clone(),
+                                project_id.clone(),
+                                network_id.clone(),
+                            )
+                            .await?;
 
-                        let resp = esc_api::infra::create_peering_commands(
-                            &client,
-                            params.org_id,
-                            params.project_id,
-                            esc_api::infra::CreatePeeringCommandsRequest {
-                                provider: network.network.provider,
-                                peer_account_id: params.peer_account_id,
-                                peer_network_id: params.peer_network_id,
-                            },
-                        )
-                        .await?;
+                            let resp = esc_api::infra::create_peering_commands(
+                                &client,
+                                org_id,
+                                project_id,
+                                esc_api::infra::CreatePeeringCommandsRequest {
+                                    provider: network.network.provider,
+                                    peer_account_id: params.peer_account_id,
+                                    peer_network_id: params.peer_network_id,
+                                },
+                            )
+                            .await?;
 
-                        if opt.render_in_json {
-                            printer.print(resp)?;
-                        } else {
-                            println!("Upstream provider requires configuration.");
-                            for command in resp.commands {
-                                println!();
-                                println!("{}:", command.title);
-                                println!("{}", command.value);
+                            if opt.render_in_json || printer.shapes_output() {
+                                printer.print(resp)?;
+                            } else {
+                                println!("Upstream provider requires configuration.");
+                                for command in resp.commands {
+                                    println!();
+                                    println!("{}:", command.title);
+                                    println!("{}", command.value);
+                                }
                             }
                         }
                     }
@@ -2539,11 +3915,14 @@ async fn call_api<'a, 'b>(
 
                 PeeringsCommand::Update(params) => {
                     let client = client_builder.create().await?;
+                    let (org_id, project_id) =
+                        resolve::scope(&client, params.org_id, params.project_id).await?;
+                    let id = resolve::peering(&client, &org_id, &project_id, params.id).await?;
                     esc_api::infra::update_peering(
                         &client,
-                        params.org_id,
-                        params.project_id,
-                        params.id,
+                        org_id,
+                        project_id,
+                        id,
                         esc_api::infra::UpdatePeeringRequest {
                             description: params.description,
                         },
@@ -2553,33 +3932,36 @@ async fn call_api<'a, 'b>(
 
                 PeeringsCommand::Delete(params) => {
                     let client = client_builder.create().await?;
-                    esc_api::infra::delete_peering(
-                        &client,
-                        params.org_id,
-                        params.project_id,
-                        params.id,
-                    )
-                    .await?;
+                    let (org_id, project_id) =
+                        resolve::scope(&client, params.org_id, params.project_id).await?;
+                    let id = resolve::peering(&client, &org_id, &project_id, params.id).await?;
+                    esc_api::infra::delete_peering(&client, org_id, project_id, id).await?;
                 }
 
                 PeeringsCommand::Get(params) => {
                     let client = client_builder.create().await?;
-                    let resp = esc_api::infra::get_peering(
-                        &client,
-                        params.org_id,
-                        params.project_id,
-                        params.id,
-                    )
+                    let (org_id, project_id) =
+                        resolve::scope(&client, params.org_id, params.project_id).await?;
+                    let id = resolve::peering(&client, &org_id, &project_id, params.id).await?;
+                    watch::run(&printer, &params.watch, || {
+                        esc_api::infra::get_peering(
+                            &client,
+                            org_id.clone(),
+                            project_id.clone(),
+                            id.clone(),
+                        )
+                    })
                     .await?;
-                    printer.print(resp)?;
                 }
 
                 PeeringsCommand::List(params) => {
                     let client = client_builder.create().await?;
-                    let resp =
-                        esc_api::infra::list_peerings(&client, params.org_id, params.project_id)
-                            .await?;
-                    printer.print(resp)?;
+                    let (org_id, project_id) =
+                        resolve::scope(&client, params.org_id, params.project_id).await?;
+                    watch::run(&printer, &params.watch, || {
+                        esc_api::infra::list_peerings(&client, org_id.clone(), project_id.clone())
+                    })
+                    .await?;
                 }
             },
         },
@@ -2716,22 +4098,35 @@ async fn call_api<'a, 'b>(
 
                 OrganizationsCommand::Update(params) => {
                     let client = client_builder.create().await?;
+                    let id = resolve::org(&client, params.id).await?;
                     esc_api::resources::update_organization(
                         &client,
-                        params.id,
+                        id,
                         esc_api::resources::UpdateOrganizationRequest { name: params.name },
                     )
                     .await?;
                 }
 
                 OrganizationsCommand::Delete(params) => {
+                    let noninteractive = client_builder.noninteractive;
                     let client = client_builder.create().await?;
-                    esc_api::resources::delete_organization(&client, params.id).await?;
+                    let id = resolve::org(&client, params.id).await?;
+                    if confirm::needed(&client, &params.confirm, "organization", noninteractive)? {
+                        let org = esc_api::resources::get_organization(
+                            &utils::behind_the_scenes(&client),
+                            id.clone(),
+                        )
+                        .await?
+                        .organization;
+                        confirm::delete("organization", id.as_ref(), &org.name)?;
+                    }
+                    esc_api::resources::delete_organization(&client, id).await?;
                 }
 
                 OrganizationsCommand::Get(params) => {
                     let client = client_builder.create().await?;
-                    let resp = esc_api::resources::get_organization(&client, params.id).await?;
+                    let id = resolve::org(&client, params.id).await?;
+                    let resp = esc_api::resources::get_organization(&client, id).await?;
                     printer.print(resp)?;
                 }
 
@@ -2743,15 +4138,17 @@ async fn call_api<'a, 'b>(
 
                 OrganizationsCommand::GetMfaStatus(params) => {
                     let client = client_builder.create().await?;
-                    let resp = esc_api::resources::get_mfa_status(&client, params.id).await?;
+                    let id = resolve::org(&client, params.id).await?;
+                    let resp = esc_api::resources::get_mfa_status(&client, id).await?;
                     printer.print(resp)?;
                 }
 
                 OrganizationsCommand::UpdateMfaStatus(params) => {
                     let client = client_builder.create().await?;
+                    let id = resolve::org(&client, params.id).await?;
                     let resp = esc_api::resources::update_mfa(
                         &client,
-                        params.id,
+                        id,
                         MfaStatus {
                             mfa_enabled: params.enabled,
                         },
@@ -2764,9 +4161,10 @@ async fn call_api<'a, 'b>(
             ResourcesCommand::Projects(projs) => match projs.projects_command {
                 ProjectsCommand::Create(params) => {
                     let client = client_builder.create().await?;
+                    let org_id = resolve::org(&client, params.org_id).await?;
                     let resp = esc_api::resources::create_project(
                         &client,
-                        params.org_id,
+                        org_id,
                         esc_api::resources::CreateProjectRequest { name: params.name },
                     )
                     .await?;
@@ -2775,10 +4173,12 @@ async fn call_api<'a, 'b>(
 
                 ProjectsCommand::Update(params) => {
                     let client = client_builder.create().await?;
+                    let org_id = resolve::org(&client, params.org_id).await?;
+                    let id = resolve::project(&client, &org_id, params.id).await?;
                     esc_api::resources::update_project(
                         &client,
-                        params.org_id,
-                        params.id,
+                        org_id,
+                        id,
                         esc_api::resources::UpdateProjectRequest { name: params.name },
                     )
                     .await?;
@@ -2786,19 +4186,105 @@ async fn call_api<'a, 'b>(
 
                 ProjectsCommand::Get(params) => {
                     let client = client_builder.create().await?;
-                    let resp =
-                        esc_api::resources::get_project(&client, params.org_id, params.id).await?;
+                    let org_id = resolve::org(&client, params.org_id).await?;
+                    let id = resolve::project(&client, &org_id, params.id).await?;
+                    let resp = esc_api::resources::get_project(&client, org_id, id).await?;
                     printer.print(resp)?;
                 }
 
                 ProjectsCommand::Delete(params) => {
+                    let noninteractive = client_builder.noninteractive;
                     let client = client_builder.create().await?;
-                    esc_api::resources::delete_project(&client, params.org_id, params.id).await?;
+                    let org_id = resolve::org(&client, params.org_id).await?;
+                    let id = resolve::project(&client, &org_id, params.id).await?;
+                    if params.cascade {
+                        let resources = esc_api::teardown::inventory(
+                            &utils::behind_the_scenes(&client),
+                            &org_id,
+                            &id,
+                        )
+                        .await?;
+                        let plan = esc_api::teardown::plan(&resources, params.protected_clusters);
+                        printer.print(plan.clone())?;
+
+                        if let Err(err) = esc_api::teardown::check(&plan, params.protected_clusters)
+                        {
+                            eprintln!("Pass --protected-clusters skip to keep protected clusters, or --protected-clusters unprotect to delete them as well");
+                            return Err(err.into());
+                        }
+
+                        if confirm::needed(&client, &params.confirm, "project", noninteractive)? {
+                            let name = resources
+                                .iter()
+                                .find(|r| r.kind == esc_api::teardown::Kind::Project)
+                                .map(|r| r.name.as_str())
+                                .unwrap_or_default();
+                            confirm::delete("project", id.as_ref(), name)?;
+                        }
+
+                        let options = esc_api::teardown::TeardownOptions {
+                            on_protected: params.protected_clusters,
+                            concurrency: params.concurrency,
+                            wait: esc_api::wait::WaitOptions::with_timeout(params.timeout),
+                        };
+                        let mut reporters: HashMap<String, wait::ProgressReporter> = HashMap::new();
+                        let result = esc_api::teardown::run(
+                            &client,
+                            &org_id,
+                            &id,
+                            &plan,
+                            &options,
+                            |event| match event {
+                                esc_api::teardown::TeardownEvent::Started(step) => {
+                                    eprintln!("Deleting {} \"{}\"", step.kind, step.name)
+                                }
+                                esc_api::teardown::TeardownEvent::Waiting(step, status) => {
+                                    reporters.entry(step.id.clone()).or_default().report(
+                                        &step.kind.to_string(),
+                                        &step.name,
+                                        status,
+                                    )
+                                }
+                                esc_api::teardown::TeardownEvent::Finished(step) => {
+                                    eprintln!("Done with {} \"{}\"", step.kind, step.name)
+                                }
+                            },
+                        )
+                        .await;
+
+                        match result {
+                            Err(esc_api::teardown::TeardownError::Wait(step, err)) => {
+                                wait::finish::<()>(&step.kind.to_string(), &step.name, Err(err))?;
+                            }
+                            other => other?,
+                        }
+
+                        let kept = plan.count(esc_api::teardown::Action::Keep);
+                        if kept > 0 {
+                            eprintln!(
+                                "Kept {} resources, including the project. Run the command again once they can be deleted",
+                                kept
+                            );
+                        }
+                    } else {
+                        if confirm::needed(&client, &params.confirm, "project", noninteractive)? {
+                            let project = esc_api::resources::get_project(
+                                &utils::behind_the_scenes(&client),
+                                org_id.clone(),
+                                id.clone(),
+                            )
+                            .await?
+                            .project;
+                            confirm::delete("project", id.as_ref(), &project.name)?;
+                        }
+                        esc_api::resources::delete_project(&client, org_id, id).await?;
+                    }
                 }
 
                 ProjectsCommand::List(params) => {
                     let client = client_builder.create().await?;
-                    let resp = esc_api::resources::list_projects(&client, params.org_id).await?;
+                    let org_id = resolve::org(&client, params.org_id).await?;
+                    let resp = esc_api::resources::list_projects(&client, org_id).await?;
                     printer.print(resp)?;
                 }
             },
@@ -2806,14 +4292,32 @@ async fn call_api<'a, 'b>(
 
         Command::Mesdb(mesdb) => {
             match mesdb.mesdb_command {
+                MesdbCommand::Versions(versions) => match versions.versions_command {
+                    VersionsCommand::List(params) => {
+                        if !params.for_create {
+                            return Err("Pass --for-create to list the versions new clusters can be created with, or use `esc mesdb clusters upgrade-options --id <cluster-id>` for the versions a cluster can be upgraded to".into());
+                        }
+
+                        let client = client_builder.create().await?;
+                        let org_id = resolve::org(&client, params.org_id).await?;
+                        let resp =
+                            esc_api::mesdb::list_versions_for_cluster_create(&client, org_id)
+                                .await?;
+                        printer.print(resp)?;
+                    }
+                },
                 MesdbCommand::SharedClusters(shared_clusters) => {
                     match shared_clusters.shared_clusters_command {
                         SharedClustersCommand::Create(params) => {
                             let client = client_builder.create().await?;
+                            let (org_id, project_id) =
+                                resolve::scope(&client, params.org_id, params.project_id).await?;
+                            let acl_id =
+                                resolve::acl(&client, &org_id, &project_id, params.acl_id).await?;
                             let resp = esc_api::mesdb::create_shared_cluster(
                                 &client,
-                                params.org_id,
-                                params.project_id,
+                                org_id,
+                                project_id,
                                 esc_api::mesdb::CreateSharedClusterDeploymentRequest {
                                     cluster: esc_api::mesdb::CreateSharedClusterRequest {
                                         name: params.name,
@@ -2826,9 +4330,7 @@ async fn call_api<'a, 'b>(
                                         topology: params.topology,
                                     },
                                     acl: esc_api::mesdb::Acl::ResourceIdentifier(
-                                        esc_api::mesdb::ResourceIdentifier {
-                                            id: params.acl_id.0,
-                                        },
+                                        esc_api::mesdb::ResourceIdentifier { id: acl_id.0 },
                                     ),
                                 },
                             )
@@ -2838,36 +4340,118 @@ async fn call_api<'a, 'b>(
 
                         SharedClustersCommand::List(params) => {
                             let client = client_builder.create().await?;
-                            let resp = esc_api::mesdb::list_shared_clusters(
-                                &client,
-                                params.org_id,
-                                params.project_id,
-                            )
+                            let (org_id, project_id) =
+                                resolve::scope(&client, params.org_id, params.project_id).await?;
+                            watch::run(&printer, &params.watch, || {
+                                esc_api::mesdb::list_shared_clusters(
+                                    &client,
+                                    org_id.clone(),
+                                    project_id.clone(),
+                                )
+                            })
                             .await?;
-                            printer.print(resp)?;
                         }
 
                         SharedClustersCommand::Delete(params) => {
                             let client = client_builder.create().await?;
+                            let (org_id, project_id) =
+                                resolve::scope(&client, params.org_id, params.project_id).await?;
                             esc_api::mesdb::delete_shared_cluster(
-                                &client,
-                                params.org_id,
-                                params.project_id,
-                                params.id,
+                                &client, org_id, project_id, params.id,
                             )
                             .await?;
                         }
 
                         SharedClustersCommand::Get(params) => {
                             let client = client_builder.create().await?;
-                            let resp = esc_api::mesdb::get_shared_cluster(
+                            let (org_id, project_id) =
+                                resolve::scope(&client, params.org_id, params.project_id).await?;
+                            let id = params.id;
+                            watch::run(&printer, &params.watch, || {
+                                esc_api::mesdb::get_shared_cluster(
+                                    &client,
+                                    org_id.clone(),
+                                    project_id.clone(),
+                                    id.clone(),
+                                )
+                            })
+                            .await?;
+                        }
+
+                        SharedClustersCommand::Update(params) => {
+                            let client = client_builder.create().await?;
+                            let (org_id, project_id) =
+                                resolve::scope(&client, params.org_id, params.project_id).await?;
+                            let acl_id = match params.acl_id {
+                                Some(acl_id) => Some(
+                                    resolve::acl(&client, &org_id, &project_id, acl_id).await?.0,
+                                ),
+                                None => None,
+                            };
+                            esc_api::mesdb::update_shared_cluster(
                                 &client,
-                                params.org_id,
-                                params.project_id,
+                                org_id,
+                                project_id,
                                 params.id,
+                                esc_api::mesdb::UpdateSharedClusterRequest {
+                                    mutual_tls_enabled: params.mutual_tls_enabled,
+                                    acl_id,
+                                },
                             )
                             .await?;
-                            printer.print(resp)?;
+                        }
+
+                        SharedClustersCommand::Credentials(credentials) => {
+                            match credentials.credentials_command {
+                                SharedClusterCredentialsCommand::Get(params) => {
+                                    let client = client_builder.create().await?;
+                                    let (org_id, project_id) =
+                                        resolve::scope(&client, params.org_id, params.project_id)
+                                            .await?;
+                                    // The traffic observer would print the password.
+                                    let client = utils::without_observer(&client);
+                                    let resp =
+                                        esc_api::mesdb::get_shared_cluster_initial_credentials(
+                                            &client, org_id, project_id, params.id,
+                                        )
+                                        .await?;
+                                    let credentials = resp.credentials.ok_or(
+                                        "The initial credentials of this cluster have been cleared",
+                                    )?;
+                                    params
+                                        .out
+                                        .write(&serde_json::to_string_pretty(&credentials)?)?;
+                                }
+
+                                SharedClusterCredentialsCommand::Clear(params) => {
+                                    let client = client_builder.create().await?;
+                                    let (org_id, project_id) =
+                                        resolve::scope(&client, params.org_id, params.project_id)
+                                            .await?;
+                                    esc_api::mesdb::clear_shared_cluster_initial_credentials(
+                                        &client, org_id, project_id, params.id,
+                                    )
+                                    .await?;
+                                }
+                            }
+                        }
+
+                        SharedClustersCommand::Certificate(certificate) => {
+                            match certificate.certificate_command {
+                                SharedClusterCertificateCommand::Get(params) => {
+                                    let client = client_builder.create().await?;
+                                    let (org_id, project_id) =
+                                        resolve::scope(&client, params.org_id, params.project_id)
+                                            .await?;
+                                    // The bundle may hold a client key when mutual TLS is on.
+                                    let client = utils::without_observer(&client);
+                                    let bundle = esc_api::mesdb::get_shared_cluster_certificate(
+                                        &client, org_id, project_id, params.id,
+                                    )
+                                    .await?;
+                                    params.out.write(&bundle)?;
+                                }
+                            }
                         }
                     }
                 }
@@ -2875,23 +4459,66 @@ async fn call_api<'a, 'b>(
                 MesdbCommand::Clusters(clusters) => match clusters.clusters_command {
                     ClustersCommand::Create(params) => {
                         let client = client_builder.create().await?;
+                        let (org_id, project_id) =
+                            resolve::scope(&client, params.org_id, params.project_id).await?;
+                        let network_id =
+                            resolve::network(&client, &org_id, &project_id, params.network_id)
+                                .await?;
+                        let acl_id = match params.acl_id {
+                            Some(acl_id) => Some(
+                                resolve::acl(
+                                    &client,
+                                    &org_id,
+                                    &project_id,
+                                    esc_api::infra::AclId(acl_id),
+                                )
+                                .await?
+                                .0,
+                            ),
+                            None => None,
+                        };
+                        let source_project_id = match params.source_project_id {
+                            Some(source_project_id) => Some(
+                                resolve::project(
+                                    &client,
+                                    &org_id,
+                                    esc_api::ProjectId(source_project_id),
+                                )
+                                .await?,
+                            ),
+                            None => None,
+                        };
+                        let source_backup_id = match params.source_backup_id {
+                            Some(source_backup_id) => Some(
+                                resolve::backup(
+                                    &client,
+                                    &org_id,
+                                    source_project_id.as_ref().unwrap_or(&project_id),
+                                    esc_api::BackupId(source_backup_id),
+                                )
+                                .await?
+                                .0,
+                            ),
+                            None => None,
+                        };
+                        versions::check_create(&client, &org_id, &params.server_version).await?;
                         let resp = esc_api::mesdb::create_cluster(
                             &client,
-                            params.org_id,
-                            params.project_id,
+                            org_id.clone(),
+                            project_id.clone(),
                             esc_api::mesdb::CreateClusterRequest {
-                                acl_id: params.acl_id,
+                                acl_id,
                                 description: params.description,
                                 disk_iops: params.disk_iops,
                                 disk_size_gb: params.disk_size_in_gb,
                                 disk_throughput: params.disk_throughput,
                                 disk_type: params.disk_type,
                                 instance_type: params.instance_type,
-                                network_id: params.network_id,
+                                network_id,
                                 projection_level: params.projection_level,
                                 server_version: params.server_version,
-                                source_backup_id: params.source_backup_id,
-                                source_backup_project_id: params.source_project_id,
+                                source_backup_id,
+                                source_backup_project_id: source_project_id.map(|id| id.0),
                                 source_node_index: None, // TODO: add source_node_index
                                 topology: params.topology,
                                 protected: params.protected,
@@ -2901,41 +4528,85 @@ async fn call_api<'a, 'b>(
                             },
                         )
                         .await?;
+                        let id = resp.id.clone();
                         printer.print(resp)?;
+                        wait::cluster(
+                            &client,
+                            &org_id,
+                            &project_id,
+                            &id,
+                            esc_api::wait::Target::healthy_cluster(),
+                            &params.wait,
+                        )
+                        .await?;
                     }
 
                     ClustersCommand::Get(params) => {
                         let client = client_builder.create().await?;
-                        let resp = esc_api::mesdb::get_cluster(
-                            &client,
-                            params.org_id,
-                            params.project_id,
-                            params.id,
-                        )
+                        let (org_id, project_id) =
+                            resolve::scope(&client, params.org_id, params.project_id).await?;
+                        let id = resolve::cluster(&client, &org_id, &project_id, params.id).await?;
+                        watch::run(&printer, &params.watch, || {
+                            esc_api::mesdb::get_cluster(
+                                &client,
+                                org_id.clone(),
+                                project_id.clone(),
+                                id.clone(),
+                            )
+                        })
                         .await?;
-                        printer.print(resp)?;
                     }
 
                     ClustersCommand::Delete(params) => {
+                        let noninteractive = client_builder.noninteractive;
                         let client = client_builder.create().await?;
-                        esc_api::mesdb::delete_cluster(
-                            &client,
-                            params.org_id,
-                            params.project_id,
-                            params.id,
+                        let (org_id, project_id) =
+                            resolve::scope(&client, params.org_id, params.project_id).await?;
+                        let id = resolve::cluster(&client, &org_id, &project_id, params.id).await?;
+                        // Checked up front so protected clusters get a better
+                        // message than the API's.
+                        let cluster = esc_api::mesdb::get_cluster(
+                            &utils::behind_the_scenes(&client),
+                            org_id.clone(),
+                            project_id.clone(),
+                            id.clone(),
                         )
-                        .await?;
+                        .await?
+                        .cluster;
+                        if cluster.protected {
+                            return Err(confirm::protected(&cluster));
+                        }
+                        if confirm::needed(&client, &params.confirm, "cluster", noninteractive)? {
+                            confirm::delete("cluster", id.as_ref(), &cluster.description)?;
+                        }
+                        esc_api::mesdb::delete_cluster(&client, org_id, project_id, id).await?;
                     }
 
                     ClustersCommand::Update(params) => {
                         let client = client_builder.create().await?;
+                        let (org_id, project_id) =
+                            resolve::scope(&client, params.org_id, params.project_id).await?;
+                        let id = resolve::cluster(&client, &org_id, &project_id, params.id).await?;
+                        let acl_id = match params.acl_id {
+                            Some(acl_id) => Some(
+                                resolve::acl(
+                                    &client,
+                                    &org_id,
+                                    &project_id,
+                                    esc_api::infra::AclId(acl_id),
+                                )
+                                .await?
+                                .0,
+                            ),
+                            None => None,
+                        };
                         esc_api::mesdb::update_cluster(
                             &client,
-                            params.org_id,
-                            params.project_id,
-                            params.id,
+                            org_id,
+                            project_id,
+                            id,
                             esc_api::mesdb::UpdateClusterRequest {
-                                acl_id: params.acl_id,
+                                acl_id,
                                 description: params.description,
                                 protected: params.protected,
                             },
@@ -2945,22 +4616,28 @@ async fn call_api<'a, 'b>(
 
                     ClustersCommand::List(params) => {
                         let client = client_builder.create().await?;
-                        let resp = esc_api::mesdb::list_clusters(
-                            &client,
-                            params.org_id,
-                            params.project_id,
-                        )
+                        let (org_id, project_id) =
+                            resolve::scope(&client, params.org_id, params.project_id).await?;
+                        watch::run(&printer, &params.watch, || {
+                            esc_api::mesdb::list_clusters(
+                                &client,
+                                org_id.clone(),
+                                project_id.clone(),
+                            )
+                        })
                         .await?;
-                        printer.print(resp)?;
                     }
 
                     ClustersCommand::Expand(params) => {
                         let client = client_builder.create().await?;
+                        let (org_id, project_id) =
+                            resolve::scope(&client, params.org_id, params.project_id).await?;
+                        let id = resolve::cluster(&client, &org_id, &project_id, params.id).await?;
                         esc_api::mesdb::expand_cluster_disk(
                             &client,
-                            params.org_id,
-                            params.project_id,
-                            params.id,
+                            org_id.clone(),
+                            project_id.clone(),
+                            id.clone(),
                             esc_api::mesdb::ExpandClusterDiskRequest {
                                 disk_iops: params.disk_iops,
                                 disk_size_gb: params.disk_size_in_gb,
@@ -2969,103 +4646,241 @@ async fn call_api<'a, 'b>(
                             },
                         )
                         .await?;
+                        wait::cluster(
+                            &client,
+                            &org_id,
+                            &project_id,
+                            &id,
+                            esc_api::wait::Target::healthy_cluster().after_change(),
+                            &params.wait,
+                        )
+                        .await?;
                     }
 
                     ClustersCommand::Resize(params) => {
                         let client = client_builder.create().await?;
+                        let (org_id, project_id) =
+                            resolve::scope(&client, params.org_id, params.project_id).await?;
+                        let id = resolve::cluster(&client, &org_id, &project_id, params.id).await?;
                         esc_api::mesdb::resize_cluster(
                             &client,
-                            params.org_id,
-                            params.project_id,
-                            params.id,
+                            org_id.clone(),
+                            project_id.clone(),
+                            id.clone(),
                             esc_api::mesdb::ResizeClusterRequest {
                                 target_size: params.target_size,
                             },
                         )
                         .await?;
+                        wait::cluster(
+                            &client,
+                            &org_id,
+                            &project_id,
+                            &id,
+                            esc_api::wait::Target::healthy_cluster().after_change(),
+                            &params.wait,
+                        )
+                        .await?;
                     }
 
                     ClustersCommand::Stop(params) => {
                         let client = client_builder.create().await?;
+                        let (org_id, project_id) =
+                            resolve::scope(&client, params.org_id, params.project_id).await?;
+                        let id = resolve::cluster(&client, &org_id, &project_id, params.id).await?;
                         esc_api::mesdb::stop_cluster(
                             &client,
-                            params.org_id,
-                            params.project_id,
-                            params.id,
+                            org_id.clone(),
+                            project_id.clone(),
+                            id.clone(),
+                        )
+                        .await?;
+                        wait::cluster(
+                            &client,
+                            &org_id,
+                            &project_id,
+                            &id,
+                            esc_api::wait::Target::stopped_cluster(),
+                            &params.wait,
                         )
                         .await?;
                     }
 
                     ClustersCommand::Start(params) => {
                         let client = client_builder.create().await?;
+                        let (org_id, project_id) =
+                            resolve::scope(&client, params.org_id, params.project_id).await?;
+                        let id = resolve::cluster(&client, &org_id, &project_id, params.id).await?;
                         esc_api::mesdb::start_cluster(
                             &client,
-                            params.org_id,
-                            params.project_id,
-                            params.id,
+                            org_id.clone(),
+                            project_id.clone(),
+                            id.clone(),
+                        )
+                        .await?;
+                        wait::cluster(
+                            &client,
+                            &org_id,
+                            &project_id,
+                            &id,
+                            esc_api::wait::Target::healthy_cluster().after_change(),
+                            &params.wait,
+                        )
+                        .await?;
+                    }
+
+                    ClustersCommand::Restart(params) => {
+                        let client = client_builder.create().await?;
+                        let (org_id, project_id) =
+                            resolve::scope(&client, params.org_id, params.project_id).await?;
+                        let id = resolve::cluster(&client, &org_id, &project_id, params.id).await?;
+                        esc_api::mesdb::restart_cluster(
+                            &client,
+                            org_id.clone(),
+                            project_id.clone(),
+                            id.clone(),
+                        )
+                        .await?;
+                        wait::cluster(
+                            &client,
+                            &org_id,
+                            &project_id,
+                            &id,
+                            esc_api::wait::Target::healthy_cluster().after_change(),
+                            &params.wait,
+                        )
+                        .await?;
+                    }
+
+                    ClustersCommand::UpgradeOptions(params) => {
+                        let client = client_builder.create().await?;
+                        let (org_id, project_id) =
+                            resolve::scope(&client, params.org_id, params.project_id).await?;
+                        let id = resolve::cluster(&client, &org_id, &project_id, params.id).await?;
+                        let resp = esc_api::mesdb::list_versions_for_cluster_upgrade(
+                            &client, org_id, project_id, id,
                         )
                         .await?;
+                        printer.print(resp)?;
                     }
 
                     ClustersCommand::Upgrade(params) => {
                         let client = client_builder.create().await?;
+                        let (org_id, project_id) =
+                            resolve::scope(&client, params.org_id, params.project_id).await?;
+                        let id = resolve::cluster(&client, &org_id, &project_id, params.id).await?;
+                        versions::check_upgrade(
+                            &client,
+                            &org_id,
+                            &project_id,
+                            &id,
+                            &params.target_tag,
+                        )
+                        .await?;
                         esc_api::mesdb::upgrade_cluster(
                             &client,
-                            params.org_id,
-                            params.project_id,
-                            params.id,
+                            org_id.clone(),
+                            project_id.clone(),
+                            id.clone(),
                             esc_api::mesdb::UpgradeClusterRequest {
                                 target_tag: params.target_tag,
                             },
                         )
                         .await?;
+                        wait::cluster(
+                            &client,
+                            &org_id,
+                            &project_id,
+                            &id,
+                            esc_api::wait::Target::healthy_cluster().after_change(),
+                            &params.wait,
+                        )
+                        .await?;
                     }
                 },
                 MesdbCommand::Backups(clusters) => match clusters.backups_command {
                     BackupsCommand::Create(params) => {
                         let client = client_builder.create().await?;
+                        let (org_id, project_id) =
+                            resolve::scope(&client, params.org_id, params.project_id).await?;
+                        let source_cluster_id = resolve::cluster(
+                            &client,
+                            &org_id,
+                            &project_id,
+                            params.source_cluster_id,
+                        )
+                        .await?;
                         let resp = esc_api::mesdb::create_backup(
                             &client,
-                            params.org_id,
-                            params.project_id,
+                            org_id.clone(),
+                            project_id.clone(),
                             esc_api::mesdb::CreateBackupRequest {
                                 description: params.description,
-                                source_cluster_id: params.source_cluster_id,
+                                source_cluster_id,
                             },
                         )
                         .await?;
+                        let id = resp.id.clone();
                         printer.print(resp)?;
+                        wait::backup(
+                            &client,
+                            &org_id,
+                            &project_id,
+                            &id,
+                            esc_api::wait::Target::ready::<esc_api::mesdb::Backup>(),
+                            &params.wait,
+                        )
+                        .await?;
                     }
 
                     BackupsCommand::Get(params) => {
                         let client = client_builder.create().await?;
-                        let resp = esc_api::mesdb::get_backup(
-                            &client,
-                            params.org_id,
-                            params.project_id,
-                            params.id,
-                        )
+                        let (org_id, project_id) =
+                            resolve::scope(&client, params.org_id, params.project_id).await?;
+                        let id = resolve::backup(&client, &org_id, &project_id, params.id).await?;
+                        watch::run(&printer, &params.watch, || {
+                            esc_api::mesdb::get_backup(
+                                &client,
+                                org_id.clone(),
+                                project_id.clone(),
+                                id.clone(),
+                            )
+                        })
                         .await?;
-                        printer.print(resp)?;
                     }
 
                     BackupsCommand::Delete(params) => {
+                        let noninteractive = client_builder.noninteractive;
                         let client = client_builder.create().await?;
-                        esc_api::mesdb::delete_backup(
-                            &client,
-                            params.org_id,
-                            params.project_id,
-                            params.id,
-                        )
-                        .await?;
+                        let (org_id, project_id) =
+                            resolve::scope(&client, params.org_id, params.project_id).await?;
+                        let id = resolve::backup(&client, &org_id, &project_id, params.id).await?;
+                        if confirm::needed(&client, &params.confirm, "backup", noninteractive)? {
+                            let backup = esc_api::mesdb::get_backup(
+                                &utils::behind_the_scenes(&client),
+                                org_id.clone(),
+                                project_id.clone(),
+                                id.clone(),
+                            )
+                            .await?
+                            .backup;
+                            confirm::delete("backup", id.as_ref(), &backup.description)?;
+                        }
+                        esc_api::mesdb::delete_backup(&client, org_id, project_id, id).await?;
                     }
 
                     BackupsCommand::List(params) => {
                         let client = client_builder.create().await?;
-                        let resp =
-                            esc_api::mesdb::list_backups(&client, params.org_id, params.project_id)
-                                .await?;
-                        printer.print(resp)?;
+                        let (org_id, project_id) =
+                            resolve::scope(&client, params.org_id, params.project_id).await?;
+                        watch::run(&printer, &params.watch, || {
+                            esc_api::mesdb::list_backups(
+                                &client,
+                                org_id.clone(),
+                                project_id.clone(),
+                            )
+                        })
+                        .await?;
                     }
                 },
             }
@@ -3075,11 +4890,19 @@ async fn call_api<'a, 'b>(
             OrchestrateCommand::Jobs(jobs) => match jobs.jobs_command {
                 JobsCommand::Create(params) => {
                     let client = client_builder.create().await?;
+                    let (org_id, project_id) =
+                        resolve::scope(&client, params.org_id, params.project_id).await?;
                     let data = match params.job_type {
                         CreateJobType::ScheduledBackup(args) => {
                             esc_api::orchestrate::JobData::ScheduledBackup(
                                 esc_api::orchestrate::ScheduledBackupData {
-                                    cluster_id: args.cluster_id,
+                                    cluster_id: resolve::cluster(
+                                        &client,
+                                        &org_id,
+                                        &project_id,
+                                        args.cluster_id,
+                                    )
+                                    .await?,
                                     description: args.description,
                                     max_backup_count: args.max_backup_count,
                                 },
@@ -3088,8 +4911,8 @@ async fn call_api<'a, 'b>(
                     };
                     let resp = esc_api::orchestrate::create_job(
                         &client,
-                        params.org_id,
-                        params.project_id,
+                        org_id,
+                        project_id,
                         esc_api::orchestrate::CreateJobRequest {
                             data,
                             description: params.description,
@@ -3102,46 +4925,81 @@ async fn call_api<'a, 'b>(
 
                 JobsCommand::Get(params) => {
                     let client = client_builder.create().await?;
-                    let resp = esc_api::orchestrate::get_job(
-                        &client,
-                        params.org_id,
-                        params.project_id,
-                        params.id,
-                    )
-                    .await?;
+                    let (org_id, project_id) =
+                        resolve::scope(&client, params.org_id, params.project_id).await?;
+                    let id = resolve::job(&client, &org_id, &project_id, params.id).await?;
+                    let resp =
+                        esc_api::orchestrate::get_job(&client, org_id, project_id, id).await?;
                     printer.print(resp)?;
                 }
 
                 JobsCommand::Delete(params) => {
                     let client = client_builder.create().await?;
-                    esc_api::orchestrate::delete_job(
+                    let (org_id, project_id) =
+                        resolve::scope(&client, params.org_id, params.project_id).await?;
+                    let id = resolve::job(&client, &org_id, &project_id, params.id).await?;
+                    esc_api::orchestrate::delete_job(&client, org_id, project_id, id).await?;
+                }
+
+                JobsCommand::Run(params) => {
+                    let client = client_builder.create().await?;
+                    let (org_id, project_id) =
+                        resolve::scope(&client, params.org_id, params.project_id).await?;
+                    let id = resolve::job(&client, &org_id, &project_id, params.id).await?;
+                    let resp = esc_api::orchestrate::run_job(
                         &client,
-                        params.org_id,
-                        params.project_id,
-                        params.id,
+                        org_id.clone(),
+                        project_id.clone(),
+                        id.clone(),
                     )
                     .await?;
+                    let history_id = resp.history_id.clone();
+                    printer.print(resp)?;
+
+                    if params.follow && client.sender.mode != esc_api::SendMode::DryRun {
+                        let history_id = history_id
+                            .ok_or("The job was started but there is no run to follow")?;
+                        let item = wait::job_run(
+                            &client,
+                            &org_id,
+                            &project_id,
+                            &id,
+                            history_id,
+                            params.timeout,
+                        )
+                        .await?;
+                        printer.print(item)?;
+                    }
                 }
 
                 JobsCommand::List(params) => {
                     let client = client_builder.create().await?;
-                    let resp =
-                        esc_api::orchestrate::list_jobs(&client, params.org_id, params.project_id)
-                            .await?;
+                    let (org_id, project_id) =
+                        resolve::scope(&client, params.org_id, params.project_id).await?;
+                    let resp = esc_api::orchestrate::list_jobs(&client, org_id, project_id).await?;
                     printer.print(resp)?;
                 }
             },
             OrchestrateCommand::History(history) => match history.history_command {
                 HistoryCommand::List(params) => {
                     let client = client_builder.create().await?;
-                    let resp = esc_api::orchestrate::list_history(
-                        &client,
-                        params.org_id,
-                        params.project_id,
-                        params.job_id,
-                    )
+                    let (org_id, project_id) =
+                        resolve::scope(&client, params.org_id, params.project_id).await?;
+                    let job_id = match params.job_id {
+                        Some(job_id) => {
+                            Some(resolve::job(&client, &org_id, &project_id, job_id).await?)
+                        }
+                        None => None,
+                    };
+                    watch::run(&printer, &params.watch, || {
+                        esc_api::orchestrate::list_history(
+                            &client,
+                            org_id.clone(),
+                            project_id.clone(),
+                            job_id.clone(),
+                        )
+                    })
                     .await?;
-                    printer.print(resp)?;
                 }
             },
         },
@@ -3149,22 +5007,22 @@ async fn call_api<'a, 'b>(
         Command::Integrations(cmd) => match cmd.integration_command {
             IntegrationsCommand::List(params) => {
                 let client = client_builder.create().await?;
-                let resp = esc_api::integrate::list_integrations(
-                    &client,
-                    params.organization_id,
-                    params.project_id,
-                )
-                .await?;
+                let (organization_id, project_id) =
+                    resolve::scope(&client, params.organization_id, params.project_id).await?;
+                let resp =
+                    esc_api::integrate::list_integrations(&client, organization_id, project_id)
+                        .await?;
                 printer.print(resp)?;
             }
             IntegrationsCommand::Create(params) => {
-                let client = client_builder.create().await?;
+                // Missing secrets are prompted for before authenticating.
+                let noninteractive = client_builder.noninteractive;
                 let data: esc_api::integrate::CreateIntegrationData = match params.data {
                     CreateIntegrationData::OpsGenie(args) => {
                         esc_api::integrate::CreateIntegrationData::OpsGenie(
                             esc_api::integrate::CreateOpsGenieIntegrationData {
-                                api_key: args.api_key,
-                                source: None,
+                                api_key: secret::required(args.api_key, "api-key", noninteractive)?,
+                                source: args.source,
                             },
                         )
                     }
@@ -3173,15 +5031,86 @@ async fn call_api<'a, 'b>(
                             esc_api::integrate::CreateSlackIntegrationData {
                                 channel_id: args.channel_id,
                                 source: args.source,
-                                token: args.token,
+                                token: secret::required(args.token, "token", noninteractive)?,
+                            },
+                        )
+                    }
+                    CreateIntegrationData::PagerDuty(args) => {
+                        esc_api::integrate::CreateIntegrationData::PagerDuty(
+                            esc_api::integrate::CreatePagerDutyIntegrationData {
+                                auth_token: secret::required(
+                                    args.auth_token,
+                                    "auth-token",
+                                    noninteractive,
+                                )?,
+                                service: args.service,
+                                user: args.user,
+                                source: args.source,
+                            },
+                        )
+                    }
+                    CreateIntegrationData::AwsCloudWatchLogs(args) => {
+                        esc_api::integrate::CreateIntegrationData::AwsCloudWatchLogs(
+                            esc_api::integrate::CreateAwsCloudWatchLogsIntegrationData {
+                                access_key_id: secret::required(
+                                    args.access_key_id,
+                                    "access-key-id",
+                                    noninteractive,
+                                )?,
+                                cluster_ids: Some(args.cluster_ids).filter(|ids| !ids.is_empty()),
+                                group_name: args.group_name,
+                                region: args.region,
+                                secret_access_key: secret::required(
+                                    args.secret_access_key,
+                                    "secret-access-key",
+                                    noninteractive,
+                                )?,
+                                source: args.source,
+                            },
+                        )
+                    }
+                    CreateIntegrationData::AwsCloudWatchMetrics(args) => {
+                        esc_api::integrate::CreateIntegrationData::AwsCloudWatchMetrics(
+                            esc_api::integrate::CreateAwsCloudWatchMetricsIntegrationData {
+                                access_key_id: secret::required(
+                                    args.access_key_id,
+                                    "access-key-id",
+                                    noninteractive,
+                                )?,
+                                cluster_ids: Some(args.cluster_ids).filter(|ids| !ids.is_empty()),
+                                namespace: args.namespace,
+                                region: args.region,
+                                secret_access_key: secret::required(
+                                    args.secret_access_key,
+                                    "secret-access-key",
+                                    noninteractive,
+                                )?,
+                                source: args.source,
+                            },
+                        )
+                    }
+                    CreateIntegrationData::GcpLogging(args) => {
+                        esc_api::integrate::CreateIntegrationData::GcpLogging(
+                            esc_api::integrate::CreateGcpLoggingIntegrationData {
+                                gcp_project_id: args.gcp_project_id,
+                                gcp_service_account_private_key: secret::required(
+                                    args.gcp_service_account_private_key,
+                                    "gcp-service-account-private-key",
+                                    noninteractive,
+                                )?,
+                                log_id: args.log_id,
+                                source: args.source,
                             },
                         )
                     }
                 };
+                let client = client_builder.create().await?;
+                let (organization_id, project_id) =
+                    resolve::scope(&client, params.organization_id, params.project_id).await?;
                 let resp = esc_api::integrate::create_integration(
                     &client,
-                    params.organization_id,
-                    params.project_id,
+                    organization_id,
+                    project_id,
                     esc_api::integrate::CreateIntegrationRequest {
                         data,
                         description: params.description,
@@ -3192,59 +5121,131 @@ async fn call_api<'a, 'b>(
             }
             IntegrationsCommand::Delete(params) => {
                 let client = client_builder.create().await?;
+                let (organization_id, project_id) =
+                    resolve::scope(&client, params.organization_id, params.project_id).await?;
+                let integration_id = resolve::integration(
+                    &client,
+                    &organization_id,
+                    &project_id,
+                    params.integration_id,
+                )
+                .await?;
                 esc_api::integrate::delete_integration(
                     &client,
-                    params.organization_id,
-                    params.project_id,
-                    esc_api::integrate::IntegrationId(params.integration_id),
+                    organization_id,
+                    project_id,
+                    integration_id,
                 )
                 .await?;
             }
             IntegrationsCommand::Get(params) => {
                 let client = client_builder.create().await?;
+                let (organization_id, project_id) =
+                    resolve::scope(&client, params.organization_id, params.project_id).await?;
+                let integration_id = resolve::integration(
+                    &client,
+                    &organization_id,
+                    &project_id,
+                    params.integration_id,
+                )
+                .await?;
                 let resp = esc_api::integrate::get_integration(
                     &client,
-                    params.organization_id,
-                    params.project_id,
-                    esc_api::integrate::IntegrationId(params.integration_id),
+                    organization_id,
+                    project_id,
+                    integration_id,
                 )
                 .await?;
                 printer.print(resp)?;
             }
-            IntegrationsCommand::Update(params) => {
+            IntegrationsCommand::Update(mut params) => {
                 use esc_api::integrate::*;
-                // TODO: rework this. It's probably saner to force the user to say the type of sink they're updating
-                let data: Option<UpdateIntegrationData> = match params.data {
-                    Some(data) => match data.api_key {
-                        Some(api_key) => {
-                            Some(UpdateIntegrationData::UpdateOpsGenieIntegrationData(
-                                UpdateOpsGenieIntegrationData {
-                                    api_key: Some(api_key),
-                                },
-                            ))
-                        }
-                        None => {
-                            if data.channel_id.is_some() || data.token.is_some() {
-                                Some(UpdateIntegrationData::UpdateSlackIntegrationData(
-                                    UpdateSlackIntegrationData {
-                                        channel_id: data.channel_id,
-                                        token: data.token,
-                                    },
-                                ))
-                            } else {
-                                None
-                            }
-                        }
-                    },
-                    None => None,
+                let deprecated = params.deprecated_data();
+                let data = match (params.data, deprecated) {
+                    (Some(_), Some(_)) => {
+                        return Err("--api-key, --channel-id and --token can't be combined with a sink subcommand".into());
+                    }
+                    (None, Some(data)) => {
+                        eprintln!("Warning: --api-key, --channel-id and --token are deprecated, pass the sink as a subcommand instead, such as `esc integrations update --integration-id <id> slack --token <token>`");
+                        Some(data)
+                    }
+                    (data, None) => data,
                 };
+                let data = data.map(|data| match data {
+                    self::UpdateIntegrationData::OpsGenie(args) => {
+                        UpdateIntegrationData::UpdateOpsGenieIntegrationData(
+                            UpdateOpsGenieIntegrationData {
+                                api_key: args.api_key.map(Secret::into_inner),
+                            },
+                        )
+                    }
+                    self::UpdateIntegrationData::Slack(args) => {
+                        UpdateIntegrationData::UpdateSlackIntegrationData(
+                            UpdateSlackIntegrationData {
+                                channel_id: args.channel_id,
+                                token: args.token.map(Secret::into_inner),
+                            },
+                        )
+                    }
+                    self::UpdateIntegrationData::PagerDuty(args) => {
+                        UpdateIntegrationData::UpdatePagerDutyIntegrationData(
+                            UpdatePagerDutyIntegrationData {
+                                auth_token: args.auth_token.map(Secret::into_inner),
+                                service: args.service,
+                                user: args.user,
+                            },
+                        )
+                    }
+                    self::UpdateIntegrationData::AwsCloudWatchLogs(args) => {
+                        UpdateIntegrationData::UpdateAwsCloudWatchLogsIntegrationData(
+                            UpdateAwsCloudWatchLogsIntegrationData {
+                                access_key_id: args.access_key_id.map(Secret::into_inner),
+                                cluster_ids: args.cluster_ids,
+                                group_name: args.group_name,
+                                region: args.region,
+                                secret_access_key: args.secret_access_key.map(Secret::into_inner),
+                            },
+                        )
+                    }
+                    self::UpdateIntegrationData::AwsCloudWatchMetrics(args) => {
+                        UpdateIntegrationData::UpdateAwsCloudWatchMetricsIntegrationData(
+                            UpdateAwsCloudWatchMetricsIntegrationData {
+                                access_key_id: args.access_key_id.map(Secret::into_inner),
+                                cluster_ids: args.cluster_ids,
+                                namespace: args.namespace,
+                                region: args.region,
+                                secret_access_key: args.secret_access_key.map(Secret::into_inner),
+                            },
+                        )
+                    }
+                    self::UpdateIntegrationData::GcpLogging(args) => {
+                        UpdateIntegrationData::UpdateGcpLoggingIntegrationData(
+                            UpdateGcpLoggingIntegrationData {
+                                gcp_project_id: args.gcp_project_id,
+                                gcp_service_account_private_key: args
+                                    .gcp_service_account_private_key
+                                    .map(Secret::into_inner),
+                                log_id: args.log_id,
+                            },
+                        )
+                    }
+                });
 
                 let client = client_builder.create().await?;
+                let (organization_id, project_id) =
+                    resolve::scope(&client, params.organization_id, params.project_id).await?;
+                let integration_id = resolve::integration(
+                    &client,
+                    &organization_id,
+                    &project_id,
+                    params.integration_id,
+                )
+                .await?;
                 esc_api::integrate::update_integration(
                     &client,
-                    params.organization_id,
-                    params.project_id,
-                    esc_api::integrate::IntegrationId(params.integration_id),
+                    organization_id,
+                    project_id,
+                    integration_id,
                     UpdateIntegrationRequest {
                         description: params.description,
                         data,
@@ -3254,28 +5255,260 @@ async fn call_api<'a, 'b>(
             }
             IntegrationsCommand::TestIntegration(params) => {
                 let client = client_builder.create().await?;
+                let (organization_id, project_id) =
+                    resolve::scope(&client, params.organization_id, params.project_id).await?;
+                let integration_id = resolve::integration(
+                    &client,
+                    &organization_id,
+                    &project_id,
+                    params.integration_id,
+                )
+                .await?;
+                ensure_supports_test_api(&client, &organization_id, &project_id, &integration_id)
+                    .await?;
                 esc_api::integrate::test_integration(
                     &client,
-                    params.organization_id,
-                    params.project_id,
-                    esc_api::integrate::IntegrationId(params.integration_id),
+                    organization_id,
+                    project_id,
+                    integration_id,
                 )
                 .await?;
             }
+            IntegrationsCommand::Options(params) => {
+                let client = client_builder.create().await?;
+                let organization_id = resolve::org(&client, params.organization_id).await?;
+                let resp =
+                    esc_api::integrate::get_integrations_options(&client, organization_id).await?;
+                printer.print(resp)?;
+            }
         },
 
         Command::GenerateBashCompletion => {
             // clap_complete::generate_to(clap_complete::shells::Bashg, clap_app, "esc", out_dir)
-            clap_app.gen_completions_to("esc", clap::Shell::Bash, &mut std::io::stdout());
+            let mut script = Vec::new();
+            clap_app.gen_completions_to("esc", clap::Shell::Bash, &mut script);
+            print!("{}", complete::bash_script(&String::from_utf8(script)?));
         }
 
         Command::GenerateZshCompletion => {
-            clap_app.gen_completions_to("esc", clap::Shell::Zsh, &mut std::io::stdout());
+            let mut script = Vec::new();
+            clap_app.gen_completions_to("esc", clap::Shell::Zsh, &mut script);
+            print!("{}", complete::zsh_script(&String::from_utf8(script)?));
         }
 
         Command::GeneratePowershellCompletion => {
             clap_app.gen_completions_to("esc", clap::Shell::PowerShell, &mut std::io::stdout());
         }
+
+        Command::Wait(params) => {
+            let target = match params.resource {
+                resolve::Kind::Cluster if params.status.is_none() => {
+                    esc_api::wait::Target::healthy_cluster()
+                }
+                resolve::Kind::Cluster => wait::target::<esc_api::mesdb::Cluster>(params.status)?,
+                resolve::Kind::Network => wait::target::<esc_api::infra::Network>(params.status)?,
+                resolve::Kind::Peering => wait::target::<esc_api::infra::Peering>(params.status)?,
+                resolve::Kind::Acl => wait::target::<esc_api::infra::Acl>(params.status)?,
+                resolve::Kind::Backup => wait::target::<esc_api::mesdb::Backup>(params.status)?,
+                kind => unreachable!("{} is not accepted by esc wait", kind),
+            };
+
+            let client = client_builder.create().await?;
+            let (org_id, project_id) =
+                resolve::scope(&client, params.org_id, params.project_id).await?;
+            let args = wait::WaitArgs::always(params.timeout);
+
+            match params.resource {
+                resolve::Kind::Cluster => {
+                    let id = esc_api::ClusterId(params.id);
+                    let id = resolve::cluster(&client, &org_id, &project_id, id).await?;
+                    wait::cluster(&client, &org_id, &project_id, &id, target, &args).await?;
+                }
+                resolve::Kind::Network => {
+                    let id = esc_api::NetworkId(params.id);
+                    let id = resolve::network(&client, &org_id, &project_id, id).await?;
+                    wait::network(&client, &org_id, &project_id, &id, target, &args).await?;
+                }
+                resolve::Kind::Peering => {
+                    let id = esc_api::PeeringId(params.id);
+                    let id = resolve::peering(&client, &org_id, &project_id, id).await?;
+                    wait::peering(&client, &org_id, &project_id, &id, target, &args).await?;
+                }
+                resolve::Kind::Acl => {
+                    let id = esc_api::infra::AclId(params.id);
+                    let id = resolve::acl(&client, &org_id, &project_id, id).await?;
+                    wait::acl(&client, &org_id, &project_id, &id, target, &args).await?;
+                }
+                _ => {
+                    let id = esc_api::BackupId(params.id);
+                    let id = resolve::backup(&client, &org_id, &project_id, id).await?;
+                    wait::backup(&client, &org_id, &project_id, &id, target, &args).await?;
+                }
+            }
+        }
+
+        Command::Plan(params) => {
+            let desired = manifest::load(&params.file)?;
+            let client = client_builder.create().await?;
+            let (org_id, project_id) =
+                resolve::scope(&client, params.org_id, params.project_id).await?;
+            let live =
+                esc_api::manifest::fetch(&utils::without_observer(&client), &org_id, &project_id)
+                    .await?;
+            printer.print(esc_api::manifest::plan(&desired, &live))?;
+        }
+
+        Command::Apply(params) => {
+            let mut desired = manifest::load(&params.file)?;
+            manifest::resolve_secrets(&mut desired)?;
+            let client = client_builder.create().await?;
+            let (org_id, project_id) =
+                resolve::scope(&client, params.org_id, params.project_id).await?;
+            let live =
+                esc_api::manifest::fetch(&utils::behind_the_scenes(&client), &org_id, &project_id)
+                    .await?;
+            let plan = esc_api::manifest::plan(&desired, &live);
+            printer.print(plan.clone())?;
+
+            if plan.is_empty() {
+                return Ok(());
+            }
+
+            if !params.allow_delete && !plan.destructive().is_empty() {
+                eprintln!(
+                    "The plan deletes or replaces resources. Pass --allow-delete to apply it"
+                );
+                std::process::exit(1);
+            }
+
+            let options = esc_api::manifest::ApplyOptions {
+                allow_delete: params.allow_delete,
+                wait: esc_api::wait::WaitOptions::with_timeout(params.timeout),
+            };
+            let mut current = (String::new(), String::new());
+            let mut reporter = wait::ProgressReporter::new();
+            let result = esc_api::manifest::apply(
+                &client,
+                &org_id,
+                &project_id,
+                &desired,
+                live,
+                &options,
+                |event| match event {
+                    esc_api::manifest::ApplyEvent::Started(change) => {
+                        let verb = match change.action {
+                            esc_api::manifest::Action::Create => "Creating",
+                            esc_api::manifest::Action::Update => "Updating",
+                            esc_api::manifest::Action::Replace => "Replacing",
+                            esc_api::manifest::Action::Delete => "Deleting",
+                        };
+                        eprintln!("{} {} \"{}\"", verb, change.kind, change.name);
+                        current = (change.kind.to_string(), change.name.clone());
+                    }
+                    esc_api::manifest::ApplyEvent::Waiting(change, status) => {
+                        reporter.report(&change.kind.to_string(), &change.name, status)
+                    }
+                    esc_api::manifest::ApplyEvent::Finished(change) => {
+                        eprintln!("Done with {} \"{}\"", change.kind, change.name)
+                    }
+                },
+            )
+            .await;
+
+            match result {
+                Err(esc_api::manifest::ManifestError::Wait(err)) => {
+                    wait::finish::<()>(&current.0, &current.1, Err(err))?;
+                }
+                other => other?,
+            }
+        }
+
+        Command::Export(params) => {
+            let client = client_builder.create().await?;
+            let (org_id, project_id) =
+                resolve::scope(&client, params.org_id, params.project_id).await?;
+            let live =
+                esc_api::manifest::fetch(&utils::without_observer(&client), &org_id, &project_id)
+                    .await?;
+            printer.print(live.manifest)?;
+        }
+
+        Command::Drift(params) => {
+            let baseline = manifest::load(&params.baseline)?;
+            let client = client_builder.create().await?;
+            let (org_id, project_id) =
+                resolve::scope(&client, params.org_id, params.project_id).await?;
+            let live =
+                esc_api::manifest::fetch(&utils::without_observer(&client), &org_id, &project_id)
+                    .await?;
+            let drift = esc_api::manifest::drift(&baseline, &live.manifest);
+            let drifted = !drift.is_empty();
+            printer.print(drift)?;
+            if drifted {
+                std::process::exit(constants::EXIT_CODE_DRIFT_DETECTED);
+            }
+        }
+
+        Command::Api(params) => {
+            let client = client_builder.create().await?;
+            let lookup = utils::behind_the_scenes(&client);
+            let mut path = params.path;
+            let needs_project = api::has_placeholder(&path, api::PROJECT_PLACEHOLDERS);
+            if needs_project || api::has_placeholder(&path, api::ORG_PLACEHOLDERS) {
+                let org_id = match params.org_id {
+                    Some(org_id) => org_id,
+                    None => parse_org_id("")?,
+                };
+                let org_id = resolve::org(&lookup, org_id).await?;
+                if needs_project {
+                    let project_id = match params.project_id {
+                        Some(project_id) => project_id,
+                        None => parse_project_id("")?,
+                    };
+                    let project_id = resolve::project(&lookup, &org_id, project_id).await?;
+                    path = api::fill(&path, api::PROJECT_PLACEHOLDERS, &project_id.0);
+                }
+                path = api::fill(&path, api::ORG_PLACEHOLDERS, &org_id.0);
+            }
+
+            let mut fields = params.raw_fields;
+            fields.extend(params.fields);
+            let body = api::body(fields, params.input.as_deref())?;
+            let resp: serde_json::Value = client
+                .send_request(params.method, path, body.as_ref(), None)
+                .await?;
+            if !resp.is_null() {
+                printer.print_json_only(resp)?;
+            }
+        }
+
+        Command::Complete(params) => {
+            if params.refresh {
+                let client_builder = ClientBuilder {
+                    observer: None,
+                    noninteractive: true,
+                    ..client_builder
+                };
+                // Runs detached from the shell, so there's nobody to report errors to.
+                if let Ok(client) = client_builder.create().await {
+                    if let Err(err) = complete::refresh(&client, &params.flag, &params.words).await
+                    {
+                        log::debug!("Could not refresh the cache: {}", err);
+                    }
+                }
+            } else {
+                let ids = complete::complete(
+                    &client_builder.base_url,
+                    &params.flag,
+                    &params.prefix,
+                    &params.words,
+                )
+                .await;
+                for id in ids {
+                    println!("{}", id);
+                }
+            }
+        }
     };
 
     Ok(())
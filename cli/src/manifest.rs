@@ -0,0 +1,50 @@
+// Reads the manifests used by `esc plan` and `esc apply`. Manifests are YAML,
+// or TOML when the file name ends with `.toml`. Integration secrets may use
+// the same @FILE and env:VAR forms as secret flags.
+use esc_api::manifest::Manifest;
+use std::path::Path;
+
+type Result<A> = std::result::Result<A, Box<dyn std::error::Error>>;
+
+pub fn parse(path: &Path, contents: &str) -> Result<Manifest> {
+    let is_toml = path
+        .extension()
+        .map(|ext| ext.eq_ignore_ascii_case("toml"))
+        .unwrap_or(false);
+
+    let manifest: Manifest = if is_toml {
+        toml::from_str(contents).map_err(|e| format!("{}: {}", path.display(), e))?
+    } else {
+        serde_yaml::from_str(contents).map_err(|e| format!("{}: {}", path.display(), e))?
+    };
+
+    manifest
+        .validate()
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(manifest)
+}
+
+pub fn load(path: &Path) -> Result<Manifest> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    parse(path, &contents)
+}
+
+/// Replaces `@FILE` and `env:VAR` secrets with their values.
+pub fn resolve_secrets(manifest: &mut Manifest) -> Result<()> {
+    for integration in manifest.integrations.iter_mut() {
+        for secret in integration.sink.secrets_mut() {
+            if let Some(value) = secret.as_ref() {
+                if value == "-" {
+                    return Err(format!(
+                        "Integration \"{}\" can't read a secret from stdin",
+                        integration.name
+                    )
+                    .into());
+                }
+                *secret = Some(crate::secret::parse(value)?.into_inner());
+            }
+        }
+    }
+    Ok(())
+}
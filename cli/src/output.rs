@@ -3,12 +3,24 @@ use structopt::StructOpt;
 #[derive(StructOpt, Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub enum OutputFormat {
-    #[structopt(about = "Shows responses using the ESC cli's custom output. Deprecated.")]
+    #[structopt(about = "Shows responses as tables. This is the default")]
+    Table,
+    #[structopt(
+        about = "Shows responses using the ESC cli's custom output. Deprecated, use table instead."
+    )]
     Cli,
     #[structopt(
         about = "Shows responses using a JSON form of the ESC cli's custom output. Deprecated."
     )]
     CliJson,
+    #[structopt(about = "Shows response bodies as YAML")]
+    Yaml,
+    #[structopt(
+        about = "Shows responses as CSV, with nested fields flattened into dotted columns"
+    )]
+    Csv,
+    #[structopt(about = "Shows every item of list responses as a JSON object on its own line")]
+    Ndjson,
     #[structopt(about = "Shows response bodies exactly as they appear in the API")]
     Api,
     #[structopt(
@@ -19,25 +31,45 @@ pub enum OutputFormat {
 
 static OUTPUT_FORMAT_HELP: &str = r#"
 Output format options:
+    table - Shows responses as tables. This is the default.
+    yaml - Shows response bodies as YAML.
+    csv - Shows responses as CSV, with nested fields flattened into dotted columns.
+    ndjson - Shows every item of list responses as a JSON object on its own line.
     api - Shows response bodies exactly as they appear in the API.
-    cli - Shows responses using the ESC cli's custom output format. Deprecated. 
+    cli - Shows responses using the ESC cli's custom output format. Deprecated, use table instead.
     cli-json - Shows responses using the ESC cli's custom output format, but serialized back into JSON. Deprecated.
 "#;
 
 impl OutputFormat {
     pub fn is_v1(&self) -> bool {
         match self {
+            Self::Table => true,
             Self::Cli => true,
             Self::CliJson => true,
+            Self::Yaml | Self::Csv | Self::Ndjson => false,
             Self::Api => false,
             Self::ApiVerbose => false,
         }
     }
 
+    /// Formats rendering response bodies straight from the API types.
+    pub fn is_structured(&self) -> bool {
+        matches!(self, Self::Yaml | Self::Csv | Self::Ndjson)
+    }
+
+    /// Formats showing the HTTP traffic instead of rendering responses.
+    pub fn shows_traffic(&self) -> bool {
+        matches!(self, Self::Api | Self::ApiVerbose)
+    }
+
     pub fn as_str(&self) -> &str {
         match self {
+            Self::Table => "table",
             Self::Cli => "cli",
             Self::CliJson => "cli-json",
+            Self::Yaml => "yaml",
+            Self::Csv => "csv",
+            Self::Ndjson => "ndjson",
             Self::Api => "api",
             Self::ApiVerbose => "api-verbose",
         }
@@ -48,8 +80,12 @@ impl std::str::FromStr for OutputFormat {
     type Err = String;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
+            "table" => Ok(Self::Table),
             "cli" => Ok(Self::Cli),
             "cli-json" => Ok(Self::CliJson),
+            "yaml" => Ok(Self::Yaml),
+            "csv" => Ok(Self::Csv),
+            "ndjson" => Ok(Self::Ndjson),
             "api" => Ok(Self::Api),
             "api-verbose" => Ok(Self::ApiVerbose),
             _ => {
@@ -0,0 +1,1124 @@
+// An implementation of JMESPath (https://jmespath.org/specification.html) for
+// `--query`. It follows the specification's grammar and built-in functions,
+// and is checked against cases of the official compliance suite kept in
+// cli/tests/jmespath.
+use serde_json::{Map, Number, Value};
+use std::cmp::Ordering;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Identifier(String),
+    QuotedIdentifier(String),
+    Literal(Value),
+    Number(i64),
+    Dot,
+    Star,
+    Flatten,
+    Filter,
+    LBracket,
+    RBracket,
+    LBrace,
+    RBrace,
+    LParen,
+    RParen,
+    Comma,
+    Colon,
+    Pipe,
+    Or,
+    And,
+    Not,
+    Current,
+    ExpRef,
+    Cmp(Comparator),
+    Eof,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Comparator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Token {
+    fn binding_power(&self) -> u8 {
+        match self {
+            Token::Pipe => 1,
+            Token::Or => 2,
+            Token::And => 3,
+            Token::Cmp(_) => 5,
+            Token::Flatten => 9,
+            Token::Star => 20,
+            Token::Filter => 21,
+            Token::Dot => 40,
+            Token::Not => 45,
+            Token::LBrace => 50,
+            Token::LBracket => 55,
+            Token::LParen => 60,
+            _ => 0,
+        }
+    }
+}
+
+fn read_delimited(
+    chars: &[char],
+    start: usize,
+    delimiter: char,
+) -> Result<(String, usize), String> {
+    let mut text = String::new();
+    let mut idx = start;
+    while idx < chars.len() {
+        let c = chars[idx];
+        if c == '\\' && idx + 1 < chars.len() {
+            text.push(c);
+            text.push(chars[idx + 1]);
+            idx += 2;
+        } else if c == delimiter {
+            return Ok((text, idx + 1));
+        } else {
+            text.push(c);
+            idx += 1;
+        }
+    }
+    Err(format!(
+        "Unterminated {} at position {}",
+        delimiter,
+        start - 1
+    ))
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = query.chars().collect();
+    let mut tokens = Vec::new();
+    let mut idx = 0;
+
+    while idx < chars.len() {
+        let c = chars[idx];
+        let next = chars.get(idx + 1).copied();
+        let (token, len) = match c {
+            ' ' | '\t' | '\n' | '\r' => {
+                idx += 1;
+                continue;
+            }
+            '.' => (Token::Dot, 1),
+            '*' => (Token::Star, 1),
+            ',' => (Token::Comma, 1),
+            ':' => (Token::Colon, 1),
+            '{' => (Token::LBrace, 1),
+            '}' => (Token::RBrace, 1),
+            ']' => (Token::RBracket, 1),
+            '(' => (Token::LParen, 1),
+            ')' => (Token::RParen, 1),
+            '@' => (Token::Current, 1),
+            '[' => match next {
+                Some('?') => (Token::Filter, 2),
+                Some(']') => (Token::Flatten, 2),
+                _ => (Token::LBracket, 1),
+            },
+            '|' if next == Some('|') => (Token::Or, 2),
+            '|' => (Token::Pipe, 1),
+            '&' if next == Some('&') => (Token::And, 2),
+            '&' => (Token::ExpRef, 1),
+            '!' if next == Some('=') => (Token::Cmp(Comparator::Ne), 2),
+            '!' => (Token::Not, 1),
+            '=' if next == Some('=') => (Token::Cmp(Comparator::Eq), 2),
+            '<' if next == Some('=') => (Token::Cmp(Comparator::Le), 2),
+            '<' => (Token::Cmp(Comparator::Lt), 1),
+            '>' if next == Some('=') => (Token::Cmp(Comparator::Ge), 2),
+            '>' => (Token::Cmp(Comparator::Gt), 1),
+            '\'' => {
+                let (raw, end) = read_delimited(&chars, idx + 1, '\'')?;
+                let text = raw.replace("\\'", "'").replace("\\\\", "\\");
+                tokens.push(Token::Literal(Value::String(text)));
+                idx = end;
+                continue;
+            }
+            '`' => {
+                let (raw, end) = read_delimited(&chars, idx + 1, '`')?;
+                let value = serde_json::from_str(&raw.replace("\\`", "`"))
+                    .map_err(|e| format!("Invalid literal `{}`: {}", raw, e))?;
+                tokens.push(Token::Literal(value));
+                idx = end;
+                continue;
+            }
+            '"' => {
+                let (raw, end) = read_delimited(&chars, idx + 1, '"')?;
+                let name = serde_json::from_str(&format!("\"{}\"", raw))
+                    .map_err(|e| format!("Invalid identifier \"{}\": {}", raw, e))?;
+                tokens.push(Token::QuotedIdentifier(name));
+                idx = end;
+                continue;
+            }
+            c if c.is_ascii_digit() || (c == '-' && next.is_some_and(|n| n.is_ascii_digit())) => {
+                let mut end = idx + 1;
+                while end < chars.len() && chars[end].is_ascii_digit() {
+                    end += 1;
+                }
+                let text: String = chars[idx..end].iter().collect();
+                let number = text
+                    .parse()
+                    .map_err(|_| format!("Invalid number {}", text))?;
+                tokens.push(Token::Number(number));
+                idx = end;
+                continue;
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut end = idx + 1;
+                while end < chars.len() && (chars[end].is_ascii_alphanumeric() || chars[end] == '_')
+                {
+                    end += 1;
+                }
+                tokens.push(Token::Identifier(chars[idx..end].iter().collect()));
+                idx = end;
+                continue;
+            }
+            c => return Err(format!("Unexpected character '{}' at position {}", c, idx)),
+        };
+        tokens.push(token);
+        idx += len;
+    }
+
+    tokens.push(Token::Eof);
+    Ok(tokens)
+}
+
+#[derive(Clone, Debug)]
+enum Node {
+    Identity,
+    Field(String),
+    Literal(Value),
+    Subexpression(Box<Node>, Box<Node>),
+    Index(i64),
+    Slice(Option<i64>, Option<i64>, Option<i64>),
+    IndexExpression(Box<Node>, Box<Node>),
+    Projection(Box<Node>, Box<Node>),
+    ValueProjection(Box<Node>, Box<Node>),
+    FilterProjection(Box<Node>, Box<Node>, Box<Node>),
+    Flatten(Box<Node>),
+    Comparison(Comparator, Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+    And(Box<Node>, Box<Node>),
+    Not(Box<Node>),
+    Pipe(Box<Node>, Box<Node>),
+    MultiSelectList(Vec<Node>),
+    MultiSelectHash(Vec<(String, Node)>),
+    Function(String, Vec<Node>),
+    ExpRef(Box<Node>),
+}
+
+// Projections stop at tokens binding less than this.
+const PROJECTION_STOP: u8 = 10;
+
+struct Parser {
+    tokens: Vec<Token>,
+    idx: usize,
+}
+
+impl Parser {
+    fn current(&self) -> &Token {
+        &self.tokens[self.idx]
+    }
+
+    fn peek(&self, offset: usize) -> &Token {
+        self.tokens.get(self.idx + offset).unwrap_or(&Token::Eof)
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.idx].clone();
+        if self.idx < self.tokens.len() - 1 {
+            self.idx += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        if *self.current() == expected {
+            self.advance();
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected {:?} but found {:?}",
+                expected,
+                self.current()
+            ))
+        }
+    }
+
+    fn expression(&mut self, binding_power: u8) -> Result<Node, String> {
+        let token = self.advance();
+        let mut left = self.nud(token)?;
+        while binding_power < self.current().binding_power() {
+            let token = self.advance();
+            left = self.led(token, left)?;
+        }
+        Ok(left)
+    }
+
+    fn nud(&mut self, token: Token) -> Result<Node, String> {
+        match token {
+            Token::Literal(value) => Ok(Node::Literal(value)),
+            Token::Identifier(name) => Ok(Node::Field(name)),
+            Token::QuotedIdentifier(name) => {
+                if *self.current() == Token::LParen {
+                    return Err("Quoted identifiers can't name functions".to_string());
+                }
+                Ok(Node::Field(name))
+            }
+            Token::Star => {
+                let right = if *self.current() == Token::RBracket {
+                    Node::Identity
+                } else {
+                    self.projection_rhs(Token::Star.binding_power())?
+                };
+                Ok(Node::ValueProjection(
+                    Box::new(Node::Identity),
+                    Box::new(right),
+                ))
+            }
+            Token::Filter => self.led(Token::Filter, Node::Identity),
+            Token::LBrace => self.multi_select_hash(),
+            Token::LParen => {
+                let node = self.expression(0)?;
+                self.expect(Token::RParen)?;
+                Ok(node)
+            }
+            Token::Flatten => {
+                let left = Node::Flatten(Box::new(Node::Identity));
+                let right = self.projection_rhs(Token::Flatten.binding_power())?;
+                Ok(Node::Projection(Box::new(left), Box::new(right)))
+            }
+            Token::Not => {
+                let node = self.expression(Token::Not.binding_power())?;
+                Ok(Node::Not(Box::new(node)))
+            }
+            Token::LBracket => match self.current() {
+                Token::Number(_) | Token::Colon => {
+                    let right = self.index_expression()?;
+                    self.project_if_slice(Node::Identity, right)
+                }
+                Token::Star if *self.peek(1) == Token::RBracket => {
+                    self.advance();
+                    self.advance();
+                    let right = self.projection_rhs(Token::Star.binding_power())?;
+                    Ok(Node::Projection(Box::new(Node::Identity), Box::new(right)))
+                }
+                _ => self.multi_select_list(),
+            },
+            Token::Current => Ok(Node::Identity),
+            Token::ExpRef => {
+                let node = self.expression(Token::ExpRef.binding_power())?;
+                Ok(Node::ExpRef(Box::new(node)))
+            }
+            Token::Eof => Err("Unexpected end of the query".to_string()),
+            token => Err(format!("Unexpected {:?}", token)),
+        }
+    }
+
+    fn led(&mut self, token: Token, left: Node) -> Result<Node, String> {
+        match token {
+            Token::Dot => {
+                if *self.current() == Token::Star {
+                    self.advance();
+                    let right = self.projection_rhs(Token::Dot.binding_power())?;
+                    Ok(Node::ValueProjection(Box::new(left), Box::new(right)))
+                } else {
+                    let right = self.dot_rhs(Token::Dot.binding_power())?;
+                    Ok(Node::Subexpression(Box::new(left), Box::new(right)))
+                }
+            }
+            Token::Pipe => {
+                let right = self.expression(Token::Pipe.binding_power())?;
+                Ok(Node::Pipe(Box::new(left), Box::new(right)))
+            }
+            Token::Or => {
+                let right = self.expression(Token::Or.binding_power())?;
+                Ok(Node::Or(Box::new(left), Box::new(right)))
+            }
+            Token::And => {
+                let right = self.expression(Token::And.binding_power())?;
+                Ok(Node::And(Box::new(left), Box::new(right)))
+            }
+            Token::LParen => {
+                let name = match left {
+                    Node::Field(name) => name,
+                    _ => return Err("Only functions can be called".to_string()),
+                };
+                let mut args = Vec::new();
+                while *self.current() != Token::RParen {
+                    args.push(self.expression(0)?);
+                    if *self.current() == Token::Comma {
+                        self.advance();
+                    }
+                }
+                self.expect(Token::RParen)?;
+                Ok(Node::Function(name, args))
+            }
+            Token::Filter => {
+                let condition = self.expression(0)?;
+                self.expect(Token::RBracket)?;
+                let right = if *self.current() == Token::Flatten {
+                    Node::Identity
+                } else {
+                    self.projection_rhs(Token::Filter.binding_power())?
+                };
+                Ok(Node::FilterProjection(
+                    Box::new(left),
+                    Box::new(right),
+                    Box::new(condition),
+                ))
+            }
+            Token::Cmp(comparator) => {
+                let right = self.expression(Token::Cmp(comparator).binding_power())?;
+                Ok(Node::Comparison(
+                    comparator,
+                    Box::new(left),
+                    Box::new(right),
+                ))
+            }
+            Token::Flatten => {
+                let left = Node::Flatten(Box::new(left));
+                let right = self.projection_rhs(Token::Flatten.binding_power())?;
+                Ok(Node::Projection(Box::new(left), Box::new(right)))
+            }
+            Token::LBracket => match self.current() {
+                Token::Number(_) | Token::Colon => {
+                    let right = self.index_expression()?;
+                    self.project_if_slice(left, right)
+                }
+                _ => {
+                    self.expect(Token::Star)?;
+                    self.expect(Token::RBracket)?;
+                    let right = self.projection_rhs(Token::Star.binding_power())?;
+                    Ok(Node::Projection(Box::new(left), Box::new(right)))
+                }
+            },
+            token => Err(format!("Unexpected {:?}", token)),
+        }
+    }
+
+    fn projection_rhs(&mut self, binding_power: u8) -> Result<Node, String> {
+        match self.current() {
+            token if token.binding_power() < PROJECTION_STOP => Ok(Node::Identity),
+            Token::LBracket | Token::Filter => self.expression(binding_power),
+            Token::Dot => {
+                self.advance();
+                self.dot_rhs(binding_power)
+            }
+            token => Err(format!("Unexpected {:?} after a projection", token)),
+        }
+    }
+
+    fn dot_rhs(&mut self, binding_power: u8) -> Result<Node, String> {
+        match self.current() {
+            Token::Identifier(_) | Token::QuotedIdentifier(_) | Token::Star => {
+                self.expression(binding_power)
+            }
+            Token::LBracket => {
+                self.advance();
+                self.multi_select_list()
+            }
+            Token::LBrace => {
+                self.advance();
+                self.multi_select_hash()
+            }
+            token => Err(format!("Unexpected {:?} after a dot", token)),
+        }
+    }
+
+    fn index_expression(&mut self) -> Result<Node, String> {
+        if *self.current() == Token::Colon || *self.peek(1) == Token::Colon {
+            let mut parts = [None, None, None];
+            let mut part = 0;
+            while *self.current() != Token::RBracket && part < 3 {
+                match self.advance() {
+                    Token::Colon => part += 1,
+                    Token::Number(n) => parts[part] = Some(n),
+                    token => return Err(format!("Unexpected {:?} in a slice", token)),
+                }
+            }
+            self.expect(Token::RBracket)?;
+            if parts[2] == Some(0) {
+                return Err("Slices can't have a step of 0".to_string());
+            }
+            Ok(Node::Slice(parts[0], parts[1], parts[2]))
+        } else {
+            let index = match self.advance() {
+                Token::Number(n) => n,
+                token => return Err(format!("Unexpected {:?} in an index", token)),
+            };
+            self.expect(Token::RBracket)?;
+            Ok(Node::Index(index))
+        }
+    }
+
+    fn project_if_slice(&mut self, left: Node, right: Node) -> Result<Node, String> {
+        let is_slice = matches!(right, Node::Slice(..));
+        let node = Node::IndexExpression(Box::new(left), Box::new(right));
+        if is_slice {
+            let right = self.projection_rhs(Token::Star.binding_power())?;
+            Ok(Node::Projection(Box::new(node), Box::new(right)))
+        } else {
+            Ok(node)
+        }
+    }
+
+    fn multi_select_list(&mut self) -> Result<Node, String> {
+        let mut nodes = Vec::new();
+        loop {
+            nodes.push(self.expression(0)?);
+            if *self.current() == Token::RBracket {
+                break;
+            }
+            self.expect(Token::Comma)?;
+        }
+        self.expect(Token::RBracket)?;
+        Ok(Node::MultiSelectList(nodes))
+    }
+
+    fn multi_select_hash(&mut self) -> Result<Node, String> {
+        let mut pairs = Vec::new();
+        loop {
+            let key = match self.advance() {
+                Token::Identifier(key) | Token::QuotedIdentifier(key) => key,
+                token => return Err(format!("Expected a key but found {:?}", token)),
+            };
+            self.expect(Token::Colon)?;
+            pairs.push((key, self.expression(0)?));
+            if *self.current() == Token::RBrace {
+                break;
+            }
+            self.expect(Token::Comma)?;
+        }
+        self.expect(Token::RBrace)?;
+        Ok(Node::MultiSelectHash(pairs))
+    }
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::String(s) => !s.is_empty(),
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(o) => !o.is_empty(),
+        Value::Number(_) => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+        Value::Number(_) => "number",
+    }
+}
+
+fn number(n: f64) -> Value {
+    if n.fract() == 0.0 && n.abs() < i64::MAX as f64 {
+        Value::Number(Number::from(n as i64))
+    } else {
+        Number::from_f64(n)
+            .map(Value::Number)
+            .unwrap_or(Value::Null)
+    }
+}
+
+// Only numbers can be compared with `<`, `<=`, `>` and `>=`.
+fn compare(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        _ => None,
+    }
+}
+
+// Sorting functions accept strings as well as numbers.
+fn order(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        _ => compare(a, b),
+    }
+}
+
+fn index(len: usize, idx: i64) -> Option<usize> {
+    let idx = if idx < 0 { len as i64 + idx } else { idx };
+    if idx >= 0 && (idx as usize) < len {
+        Some(idx as usize)
+    } else {
+        None
+    }
+}
+
+fn slice(items: &[Value], start: Option<i64>, stop: Option<i64>, step: Option<i64>) -> Vec<Value> {
+    let len = items.len() as i64;
+    let step = step.unwrap_or(1);
+    let clamp = |n: i64, low: i64, high: i64| n.max(low).min(high);
+    let bound = |n: Option<i64>, default: i64| match n {
+        None => default,
+        Some(n) if n < 0 => clamp(len + n, if step < 0 { -1 } else { 0 }, len),
+        Some(n) => clamp(n, 0, if step < 0 { len - 1 } else { len }),
+    };
+    let (start, stop) = if step > 0 {
+        (bound(start, 0), bound(stop, len))
+    } else {
+        (bound(start, len - 1), bound(stop, -1))
+    };
+
+    let mut out = Vec::new();
+    let mut idx = start;
+    while (step > 0 && idx < stop) || (step < 0 && idx > stop) {
+        if idx >= 0 && idx < len {
+            out.push(items[idx as usize].clone());
+        }
+        idx += step;
+    }
+    out
+}
+
+fn search(node: &Node, value: &Value) -> Result<Value, String> {
+    Ok(match node {
+        Node::Identity => value.clone(),
+        Node::Field(name) => value.get(name).cloned().unwrap_or(Value::Null),
+        Node::Literal(literal) => literal.clone(),
+        Node::Subexpression(left, right) | Node::Pipe(left, right) => {
+            search(right, &search(left, value)?)?
+        }
+        Node::Index(idx) => match value {
+            Value::Array(items) => index(items.len(), *idx)
+                .map(|i| items[i].clone())
+                .unwrap_or(Value::Null),
+            _ => Value::Null,
+        },
+        Node::Slice(start, stop, step) => match value {
+            Value::Array(items) => Value::Array(slice(items, *start, *stop, *step)),
+            _ => Value::Null,
+        },
+        Node::IndexExpression(left, right) => search(right, &search(left, value)?)?,
+        Node::Projection(left, right) => match search(left, value)? {
+            Value::Array(items) => project(right, items.iter())?,
+            _ => Value::Null,
+        },
+        Node::ValueProjection(left, right) => match search(left, value)? {
+            Value::Object(fields) => project(right, fields.values())?,
+            _ => Value::Null,
+        },
+        Node::FilterProjection(left, right, condition) => match search(left, value)? {
+            Value::Array(items) => {
+                let mut kept = Vec::new();
+                for item in items {
+                    if is_truthy(&search(condition, &item)?) {
+                        kept.push(item);
+                    }
+                }
+                project(right, kept.iter())?
+            }
+            _ => Value::Null,
+        },
+        Node::Flatten(node) => match search(node, value)? {
+            Value::Array(items) => {
+                let mut out = Vec::new();
+                for item in items {
+                    match item {
+                        Value::Array(inner) => out.extend(inner),
+                        item => out.push(item),
+                    }
+                }
+                Value::Array(out)
+            }
+            _ => Value::Null,
+        },
+        Node::Comparison(comparator, left, right) => {
+            let left = search(left, value)?;
+            let right = search(right, value)?;
+            match comparator {
+                Comparator::Eq => Value::Bool(left == right),
+                Comparator::Ne => Value::Bool(left != right),
+                _ => match compare(&left, &right) {
+                    None => Value::Null,
+                    Some(ordering) => Value::Bool(match comparator {
+                        Comparator::Lt => ordering == Ordering::Less,
+                        Comparator::Le => ordering != Ordering::Greater,
+                        Comparator::Gt => ordering == Ordering::Greater,
+                        _ => ordering != Ordering::Less,
+                    }),
+                },
+            }
+        }
+        Node::Or(left, right) => {
+            let left = search(left, value)?;
+            if is_truthy(&left) {
+                left
+            } else {
+                search(right, value)?
+            }
+        }
+        Node::And(left, right) => {
+            let left = search(left, value)?;
+            if is_truthy(&left) {
+                search(right, value)?
+            } else {
+                left
+            }
+        }
+        Node::Not(node) => Value::Bool(!is_truthy(&search(node, value)?)),
+        Node::MultiSelectList(nodes) => {
+            if value.is_null() {
+                return Ok(Value::Null);
+            }
+            let items = nodes
+                .iter()
+                .map(|node| search(node, value))
+                .collect::<Result<Vec<_>, _>>()?;
+            Value::Array(items)
+        }
+        Node::MultiSelectHash(pairs) => {
+            if value.is_null() {
+                return Ok(Value::Null);
+            }
+            let mut fields = Map::new();
+            for (key, node) in pairs {
+                fields.insert(key.clone(), search(node, value)?);
+            }
+            Value::Object(fields)
+        }
+        Node::Function(name, args) => call(name, args, value)?,
+        Node::ExpRef(_) => return Err("& can only be used in function arguments".to_string()),
+    })
+}
+
+fn project<'a, I: Iterator<Item = &'a Value>>(right: &Node, items: I) -> Result<Value, String> {
+    let mut out = Vec::new();
+    for item in items {
+        let result = search(right, item)?;
+        if !result.is_null() {
+            out.push(result);
+        }
+    }
+    Ok(Value::Array(out))
+}
+
+fn expect_array<'a>(function: &str, value: &'a Value) -> Result<&'a Vec<Value>, String> {
+    value
+        .as_array()
+        .ok_or_else(|| format!("{}() expects an array, got {}", function, type_name(value)))
+}
+
+fn expect_string<'a>(function: &str, value: &'a Value) -> Result<&'a str, String> {
+    value
+        .as_str()
+        .ok_or_else(|| format!("{}() expects a string, got {}", function, type_name(value)))
+}
+
+fn expect_number(function: &str, value: &Value) -> Result<f64, String> {
+    value
+        .as_f64()
+        .ok_or_else(|| format!("{}() expects a number, got {}", function, type_name(value)))
+}
+
+fn sort_values(function: &str, items: &mut [(Value, Value)]) -> Result<(), String> {
+    let mut error = None;
+    items.sort_by(|(a, _), (b, _)| {
+        order(a, b).unwrap_or_else(|| {
+            error = Some(format!(
+                "{}() can only sort numbers or strings, got {} and {}",
+                function,
+                type_name(a),
+                type_name(b)
+            ));
+            Ordering::Equal
+        })
+    });
+    match error {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+// Pairs every item with the key it's compared by.
+fn keyed(function: &str, args: &[Node], value: &Value) -> Result<Vec<(Value, Value)>, String> {
+    let items = search(&args[0], value)?;
+    let items = expect_array(function, &items)?;
+    let key = match &args[1] {
+        Node::ExpRef(node) => node,
+        _ => return Err(format!("{}() expects an &expression", function)),
+    };
+    items
+        .iter()
+        .map(|item| Ok((search(key, item)?, item.clone())))
+        .collect()
+}
+
+fn call(name: &str, args: &[Node], value: &Value) -> Result<Value, String> {
+    let arity = match name {
+        "abs" | "avg" | "ceil" | "floor" | "keys" | "length" | "reverse" | "sort" | "sum"
+        | "to_array" | "to_number" | "to_string" | "type" | "values" | "max" | "min" => Some(1),
+        "contains" | "ends_with" | "join" | "map" | "max_by" | "min_by" | "sort_by"
+        | "starts_with" => Some(2),
+        "merge" | "not_null" => None,
+        _ => return Err(format!("Unknown function {}()", name)),
+    };
+    match arity {
+        Some(arity) if args.len() != arity => {
+            return Err(format!(
+                "{}() takes {} argument(s), got {}",
+                name,
+                arity,
+                args.len()
+            ));
+        }
+        None if args.is_empty() => {
+            return Err(format!("{}() takes at least one argument", name));
+        }
+        _ => {}
+    }
+
+    if matches!(name, "map" | "max_by" | "min_by" | "sort_by") {
+        let mut items = if name == "map" {
+            let expression = match &args[0] {
+                Node::ExpRef(node) => node,
+                _ => return Err("map() expects an &expression".to_string()),
+            };
+            let items = search(&args[1], value)?;
+            let mapped = expect_array(name, &items)?
+                .iter()
+                .map(|item| search(expression, item))
+                .collect::<Result<Vec<_>, _>>()?;
+            return Ok(Value::Array(mapped));
+        } else {
+            keyed(name, args, value)?
+        };
+        sort_values(name, &mut items)?;
+        return Ok(match name {
+            "max_by" => items.pop().map(|(_, v)| v).unwrap_or(Value::Null),
+            "min_by" => items
+                .into_iter()
+                .next()
+                .map(|(_, v)| v)
+                .unwrap_or(Value::Null),
+            _ => Value::Array(items.into_iter().map(|(_, v)| v).collect()),
+        });
+    }
+
+    let args = args
+        .iter()
+        .map(|arg| search(arg, value))
+        .collect::<Result<Vec<_>, _>>()?;
+    let arg = args.first().cloned().unwrap_or(Value::Null);
+
+    Ok(match name {
+        "abs" => number(expect_number(name, &arg)?.abs()),
+        "ceil" => number(expect_number(name, &arg)?.ceil()),
+        "floor" => number(expect_number(name, &arg)?.floor()),
+        "avg" | "sum" => {
+            let items = expect_array(name, &arg)?;
+            let mut total = 0.0;
+            for item in items {
+                total += expect_number(name, item)?;
+            }
+            if name == "sum" {
+                number(total)
+            } else if items.is_empty() {
+                Value::Null
+            } else {
+                number(total / items.len() as f64)
+            }
+        }
+        "contains" => match &arg {
+            Value::Array(items) => Value::Bool(items.contains(&args[1])),
+            Value::String(s) => Value::Bool(s.contains(expect_string(name, &args[1])?)),
+            other => {
+                return Err(format!(
+                    "contains() expects an array or a string, got {}",
+                    type_name(other)
+                ))
+            }
+        },
+        "starts_with" => {
+            Value::Bool(expect_string(name, &arg)?.starts_with(expect_string(name, &args[1])?))
+        }
+        "ends_with" => {
+            Value::Bool(expect_string(name, &arg)?.ends_with(expect_string(name, &args[1])?))
+        }
+        "join" => {
+            let separator = expect_string(name, &arg)?;
+            let parts = expect_array(name, &args[1])?
+                .iter()
+                .map(|item| expect_string(name, item))
+                .collect::<Result<Vec<_>, _>>()?;
+            Value::String(parts.join(separator))
+        }
+        "keys" => match &arg {
+            Value::Object(fields) => {
+                Value::Array(fields.keys().cloned().map(Value::String).collect())
+            }
+            other => {
+                return Err(format!(
+                    "keys() expects an object, got {}",
+                    type_name(other)
+                ))
+            }
+        },
+        "values" => match &arg {
+            Value::Object(fields) => Value::Array(fields.values().cloned().collect()),
+            other => {
+                return Err(format!(
+                    "values() expects an object, got {}",
+                    type_name(other)
+                ))
+            }
+        },
+        "length" => match &arg {
+            Value::String(s) => number(s.chars().count() as f64),
+            Value::Array(items) => number(items.len() as f64),
+            Value::Object(fields) => number(fields.len() as f64),
+            other => {
+                return Err(format!(
+                    "length() expects a string, an array or an object, got {}",
+                    type_name(other)
+                ))
+            }
+        },
+        "max" | "min" => {
+            let mut items: Vec<(Value, Value)> = expect_array(name, &arg)?
+                .iter()
+                .map(|item| (item.clone(), item.clone()))
+                .collect();
+            sort_values(name, &mut items)?;
+            let item = if name == "max" {
+                items.pop()
+            } else {
+                items.into_iter().next()
+            };
+            item.map(|(_, v)| v).unwrap_or(Value::Null)
+        }
+        "merge" => {
+            let mut merged = Map::new();
+            for arg in args.iter() {
+                match arg {
+                    Value::Object(fields) => merged.extend(fields.clone()),
+                    other => {
+                        return Err(format!("merge() expects objects, got {}", type_name(other)))
+                    }
+                }
+            }
+            Value::Object(merged)
+        }
+        "not_null" => args
+            .into_iter()
+            .find(|arg| !arg.is_null())
+            .unwrap_or(Value::Null),
+        "reverse" => match &arg {
+            Value::String(s) => Value::String(s.chars().rev().collect()),
+            Value::Array(items) => Value::Array(items.iter().rev().cloned().collect()),
+            other => {
+                return Err(format!(
+                    "reverse() expects a string or an array, got {}",
+                    type_name(other)
+                ))
+            }
+        },
+        "sort" => {
+            let mut items: Vec<(Value, Value)> = expect_array(name, &arg)?
+                .iter()
+                .map(|item| (item.clone(), item.clone()))
+                .collect();
+            sort_values(name, &mut items)?;
+            Value::Array(items.into_iter().map(|(_, v)| v).collect())
+        }
+        "to_array" => match arg {
+            Value::Array(_) => arg,
+            other => Value::Array(vec![other]),
+        },
+        "to_number" => match &arg {
+            Value::Number(_) => arg,
+            Value::String(s) => s.parse::<f64>().map(number).unwrap_or(Value::Null),
+            _ => Value::Null,
+        },
+        "to_string" => match arg {
+            Value::String(_) => arg,
+            other => Value::String(other.to_string()),
+        },
+        "type" => Value::String(type_name(&arg).to_string()),
+        _ => unreachable!("every function is handled"),
+    })
+}
+
+/// A parsed JMESPath expression.
+#[derive(Clone, Debug)]
+pub struct Query {
+    expression: String,
+    root: Node,
+}
+
+impl Query {
+    pub fn parse(expression: &str) -> Result<Query, String> {
+        let mut parser = Parser {
+            tokens: tokenize(expression)?,
+            idx: 0,
+        };
+        let root = parser
+            .expression(0)
+            .and_then(|root| match parser.current() {
+                Token::Eof => Ok(root),
+                token => Err(format!("Unexpected {:?}", token)),
+            })
+            .map_err(|e| format!("Invalid query \"{}\": {}", expression, e))?;
+        Ok(Query {
+            expression: expression.to_string(),
+            root,
+        })
+    }
+
+    pub fn search(&self, value: &Value) -> Result<Value, String> {
+        search(&self.root, value)
+            .map_err(|e| format!("Could not evaluate \"{}\": {}", self.expression, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Query;
+    use serde_json::json;
+
+    fn search(query: &str, value: serde_json::Value) -> serde_json::Value {
+        Query::parse(query).unwrap().search(&value).unwrap()
+    }
+
+    #[test]
+    fn test_fields_and_indexes() {
+        let value = json!({"a": {"b": [1, 2, 3]}, "c d": true});
+        assert_eq!(search("a.b[0]", value.clone()), json!(1));
+        assert_eq!(search("a.b[-1]", value.clone()), json!(3));
+        assert_eq!(search("a.b[1:]", value.clone()), json!([2, 3]));
+        assert_eq!(search("a.b[::-1]", value.clone()), json!([3, 2, 1]));
+        assert_eq!(search("\"c d\"", value.clone()), json!(true));
+        assert_eq!(search("a.missing", value), json!(null));
+    }
+
+    #[test]
+    fn test_projections_and_filters() {
+        let value = json!({"clusters": [
+            {"id": "a", "status": "available", "diskSizeGb": 8, "tags": ["x"]},
+            {"id": "b", "status": "provisioning", "diskSizeGb": 16, "tags": ["y", "z"]},
+            {"id": "c", "status": "available", "diskSizeGb": 32}
+        ]});
+        assert_eq!(
+            search("clusters[*].id", value.clone()),
+            json!(["a", "b", "c"])
+        );
+        assert_eq!(
+            search("clusters[?status=='available'].id", value.clone()),
+            json!(["a", "c"])
+        );
+        assert_eq!(
+            search("clusters[?diskSizeGb > `10`].id | [0]", value.clone()),
+            json!("b")
+        );
+        assert_eq!(
+            search("clusters[].tags[]", value.clone()),
+            json!(["x", "y", "z"])
+        );
+        assert_eq!(
+            search("clusters[0].{id: id, size: diskSizeGb}", value.clone()),
+            json!({"id": "a", "size": 8})
+        );
+        assert_eq!(
+            search("clusters[*].[id, status][0]", value.clone()),
+            json!(["a", "available"])
+        );
+        assert_eq!(search("length(clusters)", value.clone()), json!(3));
+        assert_eq!(
+            search("max_by(clusters, &diskSizeGb).id", value.clone()),
+            json!("c")
+        );
+        assert_eq!(
+            search("sort_by(clusters, &status)[*].id", value.clone()),
+            json!(["a", "c", "b"])
+        );
+        assert_eq!(
+            search("clusters[?starts_with(id, 'b') || !tags].id", value),
+            json!(["b", "c"])
+        );
+    }
+
+    // Numbers are compared by value, since `1.0` and `1` are the same to
+    // JMESPath but not to serde_json.
+    fn same(a: &serde_json::Value, b: &serde_json::Value) -> bool {
+        use serde_json::Value;
+        match (a, b) {
+            (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+            (Value::Array(a), Value::Array(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same(a, b))
+            }
+            (Value::Object(a), Value::Object(b)) => {
+                a.len() == b.len() && a.iter().all(|(k, v)| b.get(k).is_some_and(|w| same(v, w)))
+            }
+            _ => a == b,
+        }
+    }
+
+    // Runs a file of the JMESPath compliance suite
+    // (https://github.com/jmespath/jmespath.test). Cases expecting an error
+    // only check that there is one, not its kind.
+    fn compliance(file: &str, suite: &str) {
+        let suite: serde_json::Value = serde_json::from_str(suite).unwrap();
+        let mut failures = Vec::new();
+        for group in suite.as_array().unwrap() {
+            for case in group["cases"].as_array().unwrap() {
+                let expression = case["expression"].as_str().unwrap();
+                let result = Query::parse(expression).and_then(|q| q.search(&group["given"]));
+                let passed = match (&result, case.get("error")) {
+                    (Err(_), Some(_)) => true,
+                    (Ok(value), None) => same(value, &case["result"]),
+                    _ => false,
+                };
+                if !passed {
+                    failures.push(format!("{}: {} gave {:?}", file, expression, result));
+                }
+            }
+        }
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+
+    macro_rules! compliance {
+        ($($name:ident),*) => {
+            $(
+                #[test]
+                fn $name() {
+                    compliance(
+                        stringify!($name),
+                        include_str!(concat!("../tests/jmespath/", stringify!($name), ".json")),
+                    );
+                }
+            )*
+        };
+    }
+
+    compliance!(
+        basic,
+        boolean,
+        current,
+        escape,
+        filters,
+        functions,
+        indices,
+        literal,
+        multiselect,
+        pipe,
+        slice,
+        syntax,
+        wildcard
+    );
+
+    #[test]
+    fn test_invalid_queries() {
+        assert!(Query::parse("clusters[").is_err());
+        assert!(Query::parse("a.").is_err());
+        assert!(Query::parse("nope(a)").unwrap().search(&json!({})).is_err());
+    }
+}
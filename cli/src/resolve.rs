@@ -0,0 +1,460 @@
+// Turns the values users pass to the various id flags into real resource ids.
+// A value may be an exact id, an exact name (or description), or a unique
+// prefix of an id, in which case the existing list operations are used to find
+// the resource it refers to. Recent listings are kept in the local cache.
+use crate::cache;
+use esc_api::resources::{OrganizationId, ProjectId};
+use std::fmt;
+
+type Result<A> = std::result::Result<A, Box<dyn std::error::Error>>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Organization,
+    Project,
+    Network,
+    Peering,
+    Acl,
+    Cluster,
+    Backup,
+    Job,
+    Integration,
+    Group,
+    Member,
+}
+
+impl Kind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Kind::Organization => "organization",
+            Kind::Project => "project",
+            Kind::Network => "network",
+            Kind::Peering => "peering",
+            Kind::Acl => "acl",
+            Kind::Cluster => "cluster",
+            Kind::Backup => "backup",
+            Kind::Job => "job",
+            Kind::Integration => "integration",
+            Kind::Group => "group",
+            Kind::Member => "member",
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The organization and project a resource lives in. Organizations have
+/// neither, projects, groups and members only have an organization.
+#[derive(Clone, Debug, Default)]
+pub struct Scope {
+    pub org_id: Option<OrganizationId>,
+    pub project_id: Option<ProjectId>,
+}
+
+impl Scope {
+    pub fn org(org_id: &OrganizationId) -> Self {
+        Scope {
+            org_id: Some(org_id.clone()),
+            project_id: None,
+        }
+    }
+
+    pub fn project(org_id: &OrganizationId, project_id: &ProjectId) -> Self {
+        Scope {
+            org_id: Some(org_id.clone()),
+            project_id: Some(project_id.clone()),
+        }
+    }
+
+    fn org_id(&self) -> OrganizationId {
+        self.org_id.clone().expect("scope has an organization")
+    }
+
+    fn project_id(&self) -> ProjectId {
+        self.project_id.clone().expect("scope has a project")
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Candidate {
+    pub id: String,
+    pub names: Vec<String>,
+}
+
+impl Candidate {
+    fn new(id: impl AsRef<str>, name: impl Into<String>) -> Self {
+        Candidate {
+            id: id.as_ref().to_string(),
+            names: vec![name.into()],
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AmbiguousError {
+    kind: Kind,
+    input: String,
+    matches: Vec<Candidate>,
+}
+
+impl fmt::Display for AmbiguousError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "\"{}\" matches more than one {}:", self.input, self.kind)?;
+        for candidate in self.matches.iter() {
+            writeln!(f, "  {}  {}", candidate.id, candidate.names.join(", "))?;
+        }
+        write!(f, "Use the full id or a longer id prefix instead.")
+    }
+}
+
+impl std::error::Error for AmbiguousError {}
+
+/// Picks the candidate `input` refers to. An exact id always wins, followed by
+/// exact names and then id prefixes. `None` means nothing matched, in which
+/// case the caller passes the value to the API untouched.
+pub fn select(
+    kind: Kind,
+    input: &str,
+    candidates: &[Candidate],
+) -> std::result::Result<Option<String>, AmbiguousError> {
+    if candidates.iter().any(|c| c.id == input) {
+        return Ok(Some(input.to_string()));
+    }
+
+    let mut matches: Vec<&Candidate> = candidates
+        .iter()
+        .filter(|c| c.names.iter().any(|name| name == input))
+        .collect();
+
+    if matches.is_empty() {
+        matches = candidates
+            .iter()
+            .filter(|c| c.id.starts_with(input))
+            .collect();
+    }
+
+    match matches.len() {
+        0 => Ok(None),
+        1 => Ok(Some(matches[0].id.clone())),
+        _ => Err(AmbiguousError {
+            kind,
+            input: input.to_string(),
+            matches: matches.into_iter().cloned().collect(),
+        }),
+    }
+}
+
+pub async fn list(
+    client: &esc_api::Client,
+    kind: Kind,
+    scope: &Scope,
+) -> esc_api::Result<Vec<Candidate>> {
+    let candidates = match kind {
+        Kind::Organization => esc_api::resources::list_organizations(client)
+            .await?
+            .organizations
+            .into_iter()
+            .map(|o| Candidate::new(o.id, o.name))
+            .collect(),
+        Kind::Project => esc_api::resources::list_projects(client, scope.org_id())
+            .await?
+            .projects
+            .into_iter()
+            .map(|p| Candidate::new(p.id, p.name))
+            .collect(),
+        Kind::Network => esc_api::infra::list_networks(client, scope.org_id(), scope.project_id())
+            .await?
+            .networks
+            .into_iter()
+            .map(|n| Candidate::new(n.id, n.description))
+            .collect(),
+        Kind::Peering => esc_api::infra::list_peerings(client, scope.org_id(), scope.project_id())
+            .await?
+            .peerings
+            .into_iter()
+            .map(|p| Candidate::new(p.id, p.description))
+            .collect(),
+        Kind::Acl => esc_api::infra::list_acls(client, scope.org_id(), scope.project_id())
+            .await?
+            .acls
+            .into_iter()
+            .map(|a| Candidate::new(a.id, a.description))
+            .collect(),
+        Kind::Cluster => esc_api::mesdb::list_clusters(client, scope.org_id(), scope.project_id())
+            .await?
+            .clusters
+            .into_iter()
+            .map(|c| Candidate::new(c.id, c.description))
+            .collect(),
+        Kind::Backup => esc_api::mesdb::list_backups(client, scope.org_id(), scope.project_id())
+            .await?
+            .backups
+            .into_iter()
+            .map(|b| Candidate::new(b.id, b.description))
+            .collect(),
+        Kind::Job => esc_api::orchestrate::list_jobs(client, scope.org_id(), scope.project_id())
+            .await?
+            .jobs
+            .into_iter()
+            .map(|j| Candidate::new(j.id, j.description))
+            .collect(),
+        Kind::Integration => {
+            esc_api::integrate::list_integrations(client, scope.org_id(), scope.project_id())
+                .await?
+                .integrations
+                .into_iter()
+                .map(|i| Candidate::new(i.id, i.description))
+                .collect()
+        }
+        Kind::Group => esc_api::access::list_groups(client, scope.org_id(), None)
+            .await?
+            .groups
+            .into_iter()
+            .map(|g| Candidate::new(g.id, g.name))
+            .collect(),
+        Kind::Member => esc_api::access::list_members(client, scope.org_id())
+            .await?
+            .members
+            .into_iter()
+            .map(|m| Candidate {
+                id: m.id.0,
+                names: vec![m.name, m.email],
+            })
+            .collect(),
+    };
+
+    Ok(candidates)
+}
+
+async fn resolve(
+    client: &esc_api::Client,
+    kind: Kind,
+    scope: &Scope,
+    input: String,
+) -> Result<String> {
+    if input.trim().is_empty() {
+        return Ok(input);
+    }
+
+    if let Some(entry) = cache::load(&client.base_url, kind, scope).await {
+        if !entry.is_stale() {
+            if let Ok(Some(id)) = select(kind, &input, &entry.candidates) {
+                return Ok(id);
+            }
+        }
+    }
+
+    let client = crate::utils::behind_the_scenes(client);
+
+    let candidates = match list(&client, kind, scope).await {
+        Ok(candidates) => candidates,
+        Err(err) => {
+            log::debug!("Could not list {}s to resolve {:?}: {}", kind, input, err);
+            return Ok(input);
+        }
+    };
+    cache::store(&client.base_url, kind, scope, &candidates).await;
+
+    Ok(select(kind, &input, &candidates)?.unwrap_or(input))
+}
+
+pub async fn org(client: &esc_api::Client, id: OrganizationId) -> Result<OrganizationId> {
+    let id = resolve(client, Kind::Organization, &Scope::default(), id.0).await?;
+    Ok(OrganizationId(id))
+}
+
+pub async fn project(
+    client: &esc_api::Client,
+    org_id: &OrganizationId,
+    id: ProjectId,
+) -> Result<ProjectId> {
+    let id = resolve(client, Kind::Project, &Scope::org(org_id), id.0).await?;
+    Ok(ProjectId(id))
+}
+
+/// Resolves the organization and the project within it.
+pub async fn scope(
+    client: &esc_api::Client,
+    org_id: OrganizationId,
+    project_id: ProjectId,
+) -> Result<(OrganizationId, ProjectId)> {
+    let org_id = org(client, org_id).await?;
+    let project_id = project(client, &org_id, project_id).await?;
+    Ok((org_id, project_id))
+}
+
+pub async fn group(
+    client: &esc_api::Client,
+    org_id: &OrganizationId,
+    id: esc_api::GroupId,
+) -> Result<esc_api::GroupId> {
+    let id = resolve(client, Kind::Group, &Scope::org(org_id), id.0).await?;
+    Ok(esc_api::GroupId(id))
+}
+
+pub async fn member(
+    client: &esc_api::Client,
+    org_id: &OrganizationId,
+    id: esc_api::MemberId,
+) -> Result<esc_api::MemberId> {
+    let id = resolve(client, Kind::Member, &Scope::org(org_id), id.0).await?;
+    Ok(esc_api::MemberId(id))
+}
+
+pub async fn network(
+    client: &esc_api::Client,
+    org_id: &OrganizationId,
+    project_id: &ProjectId,
+    id: esc_api::NetworkId,
+) -> Result<esc_api::NetworkId> {
+    let scope = Scope::project(org_id, project_id);
+    let id = resolve(client, Kind::Network, &scope, id.0).await?;
+    Ok(esc_api::NetworkId(id))
+}
+
+pub async fn peering(
+    client: &esc_api::Client,
+    org_id: &OrganizationId,
+    project_id: &ProjectId,
+    id: esc_api::PeeringId,
+) -> Result<esc_api::PeeringId> {
+    let scope = Scope::project(org_id, project_id);
+    let id = resolve(client, Kind::Peering, &scope, id.0).await?;
+    Ok(esc_api::PeeringId(id))
+}
+
+pub async fn acl(
+    client: &esc_api::Client,
+    org_id: &OrganizationId,
+    project_id: &ProjectId,
+    id: esc_api::infra::AclId,
+) -> Result<esc_api::infra::AclId> {
+    let scope = Scope::project(org_id, project_id);
+    let id = resolve(client, Kind::Acl, &scope, id.0).await?;
+    Ok(esc_api::infra::AclId(id))
+}
+
+pub async fn cluster(
+    client: &esc_api::Client,
+    org_id: &OrganizationId,
+    project_id: &ProjectId,
+    id: esc_api::ClusterId,
+) -> Result<esc_api::ClusterId> {
+    let scope = Scope::project(org_id, project_id);
+    let id = resolve(client, Kind::Cluster, &scope, id.0).await?;
+    Ok(esc_api::ClusterId(id))
+}
+
+pub async fn backup(
+    client: &esc_api::Client,
+    org_id: &OrganizationId,
+    project_id: &ProjectId,
+    id: esc_api::BackupId,
+) -> Result<esc_api::BackupId> {
+    let scope = Scope::project(org_id, project_id);
+    let id = resolve(client, Kind::Backup, &scope, id.0).await?;
+    Ok(esc_api::BackupId(id))
+}
+
+pub async fn job(
+    client: &esc_api::Client,
+    org_id: &OrganizationId,
+    project_id: &ProjectId,
+    id: esc_api::JobId,
+) -> Result<esc_api::JobId> {
+    let scope = Scope::project(org_id, project_id);
+    let id = resolve(client, Kind::Job, &scope, id.0).await?;
+    Ok(esc_api::JobId(id))
+}
+
+pub async fn integration(
+    client: &esc_api::Client,
+    org_id: &OrganizationId,
+    project_id: &ProjectId,
+    id: String,
+) -> Result<esc_api::IntegrationId> {
+    let scope = Scope::project(org_id, project_id);
+    let id = resolve(client, Kind::Integration, &scope, id).await?;
+    Ok(esc_api::IntegrationId(id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates() -> Vec<Candidate> {
+        vec![
+            Candidate::new("c1abc", "prod"),
+            Candidate::new("c1abd", "staging"),
+            Candidate::new("c2xyz", "c1"),
+            Candidate::new("d4aaa", "dev"),
+            Candidate::new("d5bbb", "dev"),
+        ]
+    }
+
+    #[test]
+    fn test_exact_id_beats_names_and_prefixes() {
+        let mut candidates = candidates();
+        candidates.push(Candidate::new("prod", "other"));
+        assert_eq!(
+            select(Kind::Cluster, "prod", &candidates).unwrap(),
+            Some("prod".to_string())
+        );
+        assert_eq!(
+            select(Kind::Cluster, "c1abc", &candidates).unwrap(),
+            Some("c1abc".to_string())
+        );
+    }
+
+    #[test]
+    fn test_exact_name_beats_prefixes() {
+        // "c1" is a prefix of two ids, but also the name of another cluster.
+        assert_eq!(
+            select(Kind::Cluster, "c1", &candidates()).unwrap(),
+            Some("c2xyz".to_string())
+        );
+        assert_eq!(
+            select(Kind::Cluster, "staging", &candidates()).unwrap(),
+            Some("c1abd".to_string())
+        );
+    }
+
+    #[test]
+    fn test_unique_prefix() {
+        assert_eq!(
+            select(Kind::Cluster, "c2", &candidates()).unwrap(),
+            Some("c2xyz".to_string())
+        );
+        assert_eq!(
+            select(Kind::Cluster, "d4", &candidates()).unwrap(),
+            Some("d4aaa".to_string())
+        );
+    }
+
+    #[test]
+    fn test_names_shared_by_several_resources_are_ambiguous() {
+        let err = select(Kind::Cluster, "dev", &candidates()).unwrap_err();
+        let ids: Vec<&str> = err.matches.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["d4aaa", "d5bbb"]);
+    }
+
+    #[test]
+    fn test_ambiguous_prefix_lists_its_candidates() {
+        let err = select(Kind::Cluster, "c1a", &candidates()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "\"c1a\" matches more than one cluster:\n  c1abc  prod\n  c1abd  staging\nUse the full id or a longer id prefix instead."
+        );
+    }
+
+    #[test]
+    fn test_no_match_is_passed_through() {
+        assert_eq!(select(Kind::Cluster, "e", &candidates()).unwrap(), None);
+        assert_eq!(select(Kind::Cluster, "Prod", &candidates()).unwrap(), None);
+        assert_eq!(select(Kind::Cluster, "anything", &[]).unwrap(), None);
+    }
+}
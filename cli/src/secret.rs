@@ -0,0 +1,76 @@
+// Parses the values of secret-bearing flags. Besides a literal value, which
+// ends up in shell history and `ps` output, a secret can be read from a file
+// (`@path`), an environment variable (`env:NAME`) or stdin (`-`). Required
+// secrets which were left out are prompted for without echoing them.
+use std::fmt;
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+type Result<A> = std::result::Result<A, Box<dyn std::error::Error>>;
+
+static STDIN_READ: AtomicBool = AtomicBool::new(false);
+
+/// A secret value. It's never shown by `Debug`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret(<redacted>)")
+    }
+}
+
+fn trim_newline(value: String) -> String {
+    value.trim_end_matches(['\n', '\r']).to_string()
+}
+
+pub fn parse(value: &str) -> std::result::Result<Secret, String> {
+    if value == "-" {
+        if STDIN_READ.swap(true, Ordering::SeqCst) {
+            return Err("Only one secret can be read from stdin".to_string());
+        }
+        let mut contents = String::new();
+        std::io::stdin()
+            .read_to_string(&mut contents)
+            .map_err(|e| format!("Could not read the secret from stdin: {}", e))?;
+        return Ok(Secret(trim_newline(contents)));
+    }
+
+    if let Some(path) = value.strip_prefix('@') {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Could not read the secret from {}: {}", path, e))?;
+        return Ok(Secret(trim_newline(contents)));
+    }
+
+    if let Some(name) = value.strip_prefix("env:") {
+        return std::env::var(name)
+            .map(Secret)
+            .map_err(|_| format!("Environment variable {} is not set", name));
+    }
+
+    Ok(Secret(value.to_string()))
+}
+
+/// Prompts for `label` without echoing what's typed.
+pub fn prompt(label: &str) -> Result<Secret> {
+    let value = rpassword::read_password_from_tty(Some(&format!("{}: ", label)))?;
+    Ok(Secret(value))
+}
+
+/// Returns the secret given to `flag`, or prompts for it. Fails instead of
+/// prompting when `--noninteractive` is set.
+pub fn required(value: Option<Secret>, flag: &str, noninteractive: bool) -> Result<String> {
+    match value {
+        Some(secret) => Ok(secret.into_inner()),
+        None if noninteractive => {
+            Err(format!("--{} is required when --noninteractive is set", flag).into())
+        }
+        None => Ok(prompt(flag)?.into_inner()),
+    }
+}
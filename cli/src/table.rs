@@ -0,0 +1,277 @@
+// Renders responses as aligned tables for `--fmt table`, or as CSV for
+// `--fmt csv`. Responses are turned into rows of flattened fields, nested objects giving dotted column names such
+// as `addresses.grpc`. Column names match regardless of case, dashes and
+// underscores, so `disk_size_gb` selects the `diskSizeGb` field.
+use serde_json::{Map, Value};
+
+const SEPARATOR: &str = "  ";
+// Columns are never truncated below this, even if the table overflows.
+const MIN_WIDTH: usize = 6;
+
+#[derive(Clone, Debug, Default)]
+pub struct TableOptions {
+    /// Columns to show instead of the default ones.
+    pub columns: Option<Vec<String>>,
+    pub sort_by: Option<String>,
+    pub no_headers: bool,
+    /// Width cells are truncated to fit in. Unlimited if absent.
+    pub width: Option<usize>,
+}
+
+pub type Row = Map<String, Value>;
+
+fn flatten_into(prefix: &str, value: Value, row: &mut Row) {
+    match value {
+        Value::Object(fields) if !fields.is_empty() => {
+            for (key, value) in fields {
+                let key = if prefix.is_empty() {
+                    key
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten_into(&key, value, row);
+            }
+        }
+        value => {
+            row.insert(prefix.to_string(), value);
+        }
+    }
+}
+
+/// Turns an object into a single level one with dotted keys.
+pub fn flatten(value: Value) -> Row {
+    let mut row = Row::new();
+    match value {
+        Value::Object(_) => flatten_into("", value, &mut row),
+        value => {
+            row.insert("value".to_string(), value);
+        }
+    }
+    row
+}
+
+/// The items held by a response. Lists give every item, and so do envelopes
+/// such as `{"clusters": [...]}`, while anything else is a single item.
+pub fn items(value: Value) -> Vec<Value> {
+    match value {
+        Value::Array(items) => items,
+        Value::Object(mut fields) => {
+            if fields.len() == 1 {
+                let (key, inner) = fields.iter().next().expect("one field");
+                if inner.is_array() || inner.is_object() {
+                    let key = key.clone();
+                    return items(fields.remove(&key).expect("the field exists"));
+                }
+            }
+            vec![Value::Object(fields)]
+        }
+        value => vec![value],
+    }
+}
+
+/// The items of a response as flattened rows. Returns `None` for scalars
+/// such as ids.
+pub fn rows(value: Value) -> Option<Vec<Row>> {
+    match value {
+        Value::Array(_) | Value::Object(_) => Some(items(value).into_iter().map(flatten).collect()),
+        _ => None,
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+// Every key found in the rows, the id first.
+fn all_keys(rows: &[Row]) -> Vec<String> {
+    let mut keys: Vec<String> = rows.iter().flat_map(|row| row.keys().cloned()).collect();
+    keys.sort();
+    keys.dedup();
+    keys.sort_by_key(|key| key != "id");
+    keys
+}
+
+/// Finds the key of the rows a column name refers to.
+pub fn resolve_column(rows: &[Row], name: &str) -> Result<String, String> {
+    let keys = all_keys(rows);
+    if keys.is_empty() {
+        return Ok(name.to_string());
+    }
+    let wanted = normalize(name);
+    keys.iter()
+        .find(|key| normalize(key) == wanted)
+        .cloned()
+        .ok_or_else(|| {
+            format!(
+                "Unknown column \"{}\". Available columns: {}",
+                name,
+                keys.join(", ")
+            )
+        })
+}
+
+/// Renders a field as text. Lists of scalars are joined with commas.
+pub fn cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(items)) if items.iter().all(|v| !v.is_array() && !v.is_object()) => items
+            .iter()
+            .map(|v| cell(Some(v)))
+            .collect::<Vec<_>>()
+            .join(","),
+        Some(value) => value.to_string(),
+    }
+}
+
+// `diskSizeGb` becomes `DISK_SIZE_GB`.
+fn header(key: &str) -> String {
+    let mut header = String::new();
+    let mut previous_lower = false;
+    for c in key.chars() {
+        if c.is_uppercase() && previous_lower {
+            header.push('_');
+        }
+        previous_lower = c.is_lowercase() || c.is_ascii_digit();
+        header.extend(c.to_uppercase());
+    }
+    header
+}
+
+fn compare(a: Option<&Value>, b: Option<&Value>) -> std::cmp::Ordering {
+    match (a.and_then(Value::as_f64), b.and_then(Value::as_f64)) {
+        (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal),
+        _ => cell(a).cmp(&cell(b)),
+    }
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(width.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+// Narrows the widest columns until the table fits.
+fn fit(widths: &mut [usize], max: usize) {
+    let separators = SEPARATOR.len() * widths.len().saturating_sub(1);
+    while widths.iter().sum::<usize>() + separators > max {
+        let widest = widths
+            .iter_mut()
+            .max_by_key(|w| **w)
+            .filter(|w| **w > MIN_WIDTH);
+        match widest {
+            Some(width) => *width -= 1,
+            None => break,
+        }
+    }
+}
+
+// Picks the columns and sorts the rows as the options say.
+fn select(
+    rows: &mut [Row],
+    default_columns: &[&str],
+    options: &TableOptions,
+) -> Result<Vec<String>, String> {
+    let columns = match options.columns.as_ref() {
+        Some(names) => names
+            .iter()
+            .map(|name| resolve_column(rows, name))
+            .collect::<Result<Vec<_>, _>>()?,
+        None if default_columns.is_empty() => all_keys(rows),
+        None => default_columns.iter().map(|c| c.to_string()).collect(),
+    };
+
+    if let Some(sort_by) = options.sort_by.as_ref() {
+        let key = resolve_column(rows, sort_by)?;
+        rows.sort_by(|a, b| compare(a.get(&key), b.get(&key)));
+    }
+
+    Ok(columns)
+}
+
+/// Renders rows as a table. `default_columns` are used unless the options
+/// select some, and every column is shown if there are none.
+pub fn render(
+    mut rows: Vec<Row>,
+    default_columns: &[&str],
+    options: &TableOptions,
+) -> Result<String, String> {
+    let columns = select(&mut rows, default_columns, options)?;
+
+    let mut lines: Vec<Vec<String>> = Vec::new();
+    if !options.no_headers {
+        lines.push(columns.iter().map(|c| header(c)).collect());
+    }
+    for row in rows.iter() {
+        lines.push(columns.iter().map(|c| cell(row.get(c))).collect());
+    }
+
+    let mut widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(idx, _)| {
+            lines
+                .iter()
+                .map(|line| line[idx].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    if let Some(max) = options.width {
+        fit(&mut widths, max);
+    }
+
+    let mut out = String::new();
+    for line in lines {
+        let last = line.len().saturating_sub(1);
+        let mut text = String::new();
+        for (idx, value) in line.iter().enumerate() {
+            let value = truncate(value, widths[idx]);
+            if idx == last {
+                text.push_str(&value);
+            } else {
+                let padding = widths[idx] - value.chars().count();
+                text.push_str(&value);
+                text.push_str(&" ".repeat(padding));
+                text.push_str(SEPARATOR);
+            }
+        }
+        out.push_str(text.trim_end());
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Renders rows as CSV, headed by the dotted names of the columns. Every
+/// column is included unless the options select some. Widths are ignored.
+pub fn csv(mut rows: Vec<Row>, options: &TableOptions) -> Result<String, String> {
+    let columns = select(&mut rows, &[], options)?;
+
+    let mut out = String::new();
+    let mut write_line = |fields: Vec<String>| {
+        let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        out.push_str(&line.join(","));
+        out.push('\n');
+    };
+    if !options.no_headers {
+        write_line(columns.clone());
+    }
+    for row in rows.iter() {
+        write_line(columns.iter().map(|c| cell(row.get(c))).collect());
+    }
+    Ok(out)
+}
@@ -0,0 +1,137 @@
+// Go template style line formats for `--template`, such as
+// `{{.id}}\t{{.status}}`. Only field references are supported: `{{.}}` is the
+// item itself and `{{.addresses.grpc}}` a nested field, while numbers index
+// into lists. `\t`, `\n` and `\\` are unescaped so shells don't need to.
+use serde_json::Value;
+
+#[derive(Clone, Debug)]
+enum Part {
+    Text(String),
+    Field(Vec<String>),
+}
+
+#[derive(Clone, Debug)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('\\') => out.push('\\'),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+impl Template {
+    pub fn parse(template: &str) -> Result<Template, String> {
+        let mut parts = Vec::new();
+        let mut rest = template;
+        while let Some(start) = rest.find("{{") {
+            if start > 0 {
+                parts.push(Part::Text(unescape(&rest[..start])));
+            }
+            let end = rest[start..]
+                .find("}}")
+                .ok_or_else(|| format!("Unclosed {{{{ in template \"{}\"", template))?;
+            let field = rest[start + 2..start + end].trim();
+            let path = field.strip_prefix('.').ok_or_else(|| {
+                format!(
+                    "Invalid field \"{}\" in template \"{}\", fields start with a dot such as {{{{.id}}}}",
+                    field, template
+                )
+            })?;
+            parts.push(Part::Field(
+                path.split('.')
+                    .filter(|s| !s.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            ));
+            rest = &rest[start + end + 2..];
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Text(unescape(rest)));
+        }
+        Ok(Template { parts })
+    }
+
+    /// Renders an item. Missing fields render as nothing.
+    pub fn render(&self, item: &Value) -> String {
+        let mut out = String::new();
+        for part in self.parts.iter() {
+            match part {
+                Part::Text(text) => out.push_str(text),
+                Part::Field(path) => {
+                    let value = path.iter().try_fold(item, |value, key| match value {
+                        Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+                        value => value.get(key),
+                    });
+                    out.push_str(&crate::table::cell(value));
+                }
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Template;
+    use serde_json::json;
+
+    fn render(template: &str, item: serde_json::Value) -> String {
+        Template::parse(template).unwrap().render(&item)
+    }
+
+    #[test]
+    fn test_fields() {
+        let item = json!({
+            "id": "c1",
+            "diskSizeGb": 8,
+            "addresses": {"grpc": "esdb://c1:2113"},
+            "tags": ["a", "b"],
+            "nodes": [{"name": "n0"}, {"name": "n1"}],
+            "deleted": null,
+        });
+        assert_eq!(render("{{.id}}", item.clone()), "c1");
+        assert_eq!(render("{{ .diskSizeGb }}GB", item.clone()), "8GB");
+        assert_eq!(
+            render("{{.addresses.grpc}}", item.clone()),
+            "esdb://c1:2113"
+        );
+        assert_eq!(render("{{.tags}}", item.clone()), "a,b");
+        assert_eq!(render("{{.nodes.1.name}}", item.clone()), "n1");
+        assert_eq!(render("[{{.missing}}][{{.deleted}}]", item.clone()), "[][]");
+        assert_eq!(render("{{.}}", json!("plain")), "plain");
+    }
+
+    #[test]
+    fn test_text_is_unescaped() {
+        let item = json!({"id": "c1", "status": "available"});
+        assert_eq!(
+            render("{{.id}}\\t{{.status}}\\n", item.clone()),
+            "c1\tavailable\n"
+        );
+        assert_eq!(render("a\\\\b \\x", item), "a\\b \\x");
+    }
+
+    #[test]
+    fn test_invalid_templates() {
+        assert!(Template::parse("{{.id").is_err());
+        assert!(Template::parse("{{id}}").is_err());
+    }
+}
@@ -1,18 +1,22 @@
-pub fn action_from_str(s: &str) -> esc_api::access::Action {
+pub fn parse_action(s: &str) -> Result<esc_api::access::Action, String> {
     use esc_api::access::Action;
     match s {
-        "create" => Action::Create,
-        "delete" => Action::Delete,
-        "modify" => Action::Modify,
-        "none" => Action::_None,
-        "read" => Action::Read,
-        _ => {
-            eprintln!("Unknown action: {}", s);
-            std::process::exit(1);
-        }
+        "create" => Ok(Action::Create),
+        "delete" => Ok(Action::Delete),
+        "modify" => Ok(Action::Modify),
+        "none" => Ok(Action::_None),
+        "read" => Ok(Action::Read),
+        _ => Err(format!("Unknown action: {}", s)),
     }
 }
 
+pub fn action_from_str(s: &str) -> esc_api::access::Action {
+    parse_action(s).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    })
+}
+
 pub fn actions_from_str_vec(strings: Vec<String>) -> Vec<esc_api::access::Action> {
     strings
         .iter()
@@ -31,3 +35,47 @@ pub fn effect_from_str(s: &str) -> esc_api::access::Effect {
         }
     }
 }
+
+// Leaves the requests of `client` out of the API output formats, for instance
+// when the traffic would show a secret. --as-curl and --dry-run still apply.
+pub fn without_observer(client: &esc_api::Client) -> esc_api::Client {
+    let mut client = client.clone();
+    client.sender.observer = None;
+    client
+}
+
+// Requests made behind the scenes, such as lookups and polling, shouldn't show
+// up in the API output formats, nor be printed by --as-curl, which sends them
+// instead. --dry-run is kept so nothing behind the scenes changes anything.
+pub fn behind_the_scenes(client: &esc_api::Client) -> esc_api::Client {
+    let mut client = without_observer(client);
+    if client.sender.mode == esc_api::SendMode::Curl {
+        client.sender.mode = esc_api::SendMode::Send;
+    }
+    client
+}
+
+/// Writes `contents` to `path`, making sure only the current user can read it.
+pub fn write_secret_file(path: &std::path::Path, contents: &str) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(path)?;
+
+    // The mode only applies to new files.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+
+    file.write_all(contents.as_bytes())
+}
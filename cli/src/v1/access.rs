@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 
-use super::common::{List, StringNoQuotes, ToV1};
+use super::common::{List, StringNoQuotes, Table, ToV1};
 use super::resources::OrgId;
 use esc_api::access::GroupId;
 
@@ -29,8 +29,11 @@ pub struct Group {
     pub members: Vec<String>,
 }
 
+const GROUP_COLUMNS: &[&str] = &["id", "name", "members", "created"];
+
 impl ToV1 for esc_api::access::Group {
     type V1Type = Group;
+    const TABLE: Table = Table::Columns(GROUP_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         Group {
             created: self.created,
@@ -44,6 +47,7 @@ impl ToV1 for esc_api::access::Group {
 
 impl ToV1 for esc_api::access::GetGroupResponse {
     type V1Type = Group;
+    const TABLE: Table = Table::Columns(GROUP_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         self.group.to_v1()
     }
@@ -51,6 +55,7 @@ impl ToV1 for esc_api::access::GetGroupResponse {
 
 impl ToV1 for esc_api::access::ListGroupsResponse {
     type V1Type = List<Group>;
+    const TABLE: Table = Table::Columns(GROUP_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         let l: Vec<Group> = self.groups.into_iter().map(|g| g.to_v1()).collect();
         List(l)
@@ -128,8 +133,11 @@ pub struct Invite {
     pub created: DateTime<Utc>,
 }
 
+const INVITE_COLUMNS: &[&str] = &["id", "email", "groups", "accepted", "created"];
+
 impl ToV1 for esc_api::access::Invite {
     type V1Type = Invite;
+    const TABLE: Table = Table::Columns(INVITE_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         Invite {
             accepted: self.accepted,
@@ -146,6 +154,7 @@ impl ToV1 for esc_api::access::Invite {
 
 impl ToV1 for esc_api::access::ListInvitesResponse {
     type V1Type = List<Invite>;
+    const TABLE: Table = Table::Columns(INVITE_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         let invites = self.invites.into_iter().map(|i| i.to_v1()).collect();
         List(invites)
@@ -159,8 +168,11 @@ impl ToV1 for esc_api::access::CreatePolicyResponse {
     }
 }
 
+const POLICY_COLUMNS: &[&str] = &["id", "name", "effect", "actions", "resources"];
+
 impl ToV1 for esc_api::access::GetPolicyResponse {
     type V1Type = Self;
+    const TABLE: Table = Table::Columns(POLICY_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         self
     }
@@ -168,6 +180,7 @@ impl ToV1 for esc_api::access::GetPolicyResponse {
 
 impl ToV1 for esc_api::access::ListPoliciesResponse {
     type V1Type = Self;
+    const TABLE: Table = Table::Columns(POLICY_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         self
     }
@@ -185,8 +198,11 @@ pub struct Member {
     pub name: String,
 }
 
+const MEMBER_COLUMNS: &[&str] = &["id", "name", "email", "active", "created"];
+
 impl ToV1 for esc_api::access::Member {
     type V1Type = Member;
+    const TABLE: Table = Table::Columns(MEMBER_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         Member {
             created: self.created,
@@ -201,6 +217,7 @@ impl ToV1 for esc_api::access::Member {
 
 impl ToV1 for esc_api::access::GetMemberResponse {
     type V1Type = Member;
+    const TABLE: Table = Table::Columns(MEMBER_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         self.member.to_v1()
     }
@@ -208,8 +225,40 @@ impl ToV1 for esc_api::access::GetMemberResponse {
 
 impl ToV1 for esc_api::access::ListMembersResponse {
     type V1Type = List<Member>;
+    const TABLE: Table = Table::Columns(MEMBER_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         let l: Vec<Member> = self.members.into_iter().map(|m| m.to_v1()).collect();
         List(l)
     }
 }
+
+impl ToV1 for esc_api::access::GetSettingsResponse {
+    type V1Type = esc_api::access::Settings;
+    fn to_v1(self) -> Self::V1Type {
+        self.settings
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PolicyCheck {
+    pub action: esc_api::access::Action,
+    pub resource: String,
+    pub allowed: bool,
+}
+
+impl std::fmt::Debug for PolicyCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let verdict = if self.allowed { "allowed" } else { "denied" };
+        write!(f, "{:<8}{:<8}{}", verdict, self.action, self.resource)
+    }
+}
+
+const POLICY_CHECK_COLUMNS: &[&str] = &["action", "resource", "allowed"];
+
+impl ToV1 for List<PolicyCheck> {
+    type V1Type = Self;
+    const TABLE: Table = Table::Columns(POLICY_CHECK_COLUMNS);
+    fn to_v1(self) -> Self::V1Type {
+        self
+    }
+}
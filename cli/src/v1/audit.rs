@@ -1,7 +1,7 @@
 use chrono::{DateTime, Utc};
 use esc_api::OrgId;
 
-use super::common::{List, ToV1};
+use super::common::{List, Table, ToV1};
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -14,8 +14,11 @@ pub struct V1Log {
     pub time: DateTime<Utc>,
 }
 
+const LOG_COLUMNS: &[&str] = &["time", "user", "service", "message"];
+
 impl ToV1 for esc_api::audit::Log {
     type V1Type = V1Log;
+    const TABLE: Table = Table::Columns(LOG_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         V1Log {
             time: self.time,
@@ -30,6 +33,7 @@ impl ToV1 for esc_api::audit::Log {
 
 impl ToV1 for esc_api::audit::GetAuditResponse {
     type V1Type = List<V1Log>;
+    const TABLE: Table = Table::Columns(LOG_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         List(self.logs.into_iter().map(|p| p.to_v1()).collect())
     }
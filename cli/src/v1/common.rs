@@ -1,7 +1,17 @@
 use serde::ser::SerializeSeq;
 
+/// How `--fmt table` shows a value.
+pub enum Table {
+    /// The columns shown unless `--columns` is used. Every column if empty.
+    Columns(&'static [&'static str]),
+    /// The value is printed the same way as with `--fmt cli`, for output which
+    /// isn't made of rows.
+    Text,
+}
+
 pub trait ToV1 {
     type V1Type: std::fmt::Debug + serde::Serialize;
+    const TABLE: Table = Table::Columns(&[]);
     fn to_v1(self) -> Self::V1Type;
 }
 
@@ -54,3 +64,15 @@ where
         seq.end()
     }
 }
+
+impl<'de, A> serde::de::Deserialize<'de> for List<A>
+where
+    A: serde::de::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Vec::deserialize(deserializer).map(List)
+    }
+}
@@ -1,4 +1,4 @@
-use super::common::{List, ToV1};
+use super::common::{List, Table, ToV1};
 
 impl ToV1 for esc_api::infra::CreateNetworkResponse {
     type V1Type = esc_api::infra::NetworkId;
@@ -7,6 +7,13 @@ impl ToV1 for esc_api::infra::CreateNetworkResponse {
     }
 }
 
+impl ToV1 for esc_api::infra::CreatePeeringResponse {
+    type V1Type = esc_api::infra::PeeringId;
+    fn to_v1(self) -> Self::V1Type {
+        self.id
+    }
+}
+
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Provider {
@@ -53,8 +60,18 @@ pub struct Network {
     pub status: String,
 }
 
+const NETWORK_COLUMNS: &[&str] = &[
+    "id",
+    "description",
+    "provider",
+    "region",
+    "cidrBlock",
+    "status",
+];
+
 impl ToV1 for esc_api::infra::Network {
     type V1Type = Network;
+    const TABLE: Table = Table::Columns(NETWORK_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         Network {
             cidr_block: self.cidr_block.unwrap(),
@@ -69,6 +86,7 @@ impl ToV1 for esc_api::infra::Network {
 }
 impl ToV1 for esc_api::infra::GetNetworkResponse {
     type V1Type = Network;
+    const TABLE: Table = Table::Columns(NETWORK_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         self.network.to_v1()
     }
@@ -76,6 +94,7 @@ impl ToV1 for esc_api::infra::GetNetworkResponse {
 
 impl ToV1 for esc_api::infra::ListNetworksResponse {
     type V1Type = List<Network>;
+    const TABLE: Table = Table::Columns(NETWORK_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         let l = self.networks.into_iter().map(|n| n.to_v1()).collect();
         List(l)
@@ -97,8 +116,19 @@ pub struct Peering {
     pub status: String,
 }
 
+const PEERING_COLUMNS: &[&str] = &[
+    "id",
+    "description",
+    "networkId",
+    "peerNetwork",
+    "peerNetworkRegion",
+    "routes",
+    "status",
+];
+
 impl ToV1 for esc_api::infra::Peering {
     type V1Type = Peering;
+    const TABLE: Table = Table::Columns(PEERING_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         Peering {
             description: self.description,
@@ -116,8 +146,11 @@ impl ToV1 for esc_api::infra::Peering {
 }
 
 // The fields are out of order, but otherwise this is identical
+const COMMAND_COLUMNS: &[&str] = &["title", "language", "value"];
+
 impl ToV1 for esc_api::infra::CreatePeeringCommandsResponse {
     type V1Type = Vec<esc_api::infra::Command>;
+    const TABLE: Table = Table::Columns(COMMAND_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         self.commands
     }
@@ -125,6 +158,7 @@ impl ToV1 for esc_api::infra::CreatePeeringCommandsResponse {
 
 impl ToV1 for esc_api::infra::GetPeeringResponse {
     type V1Type = Peering;
+    const TABLE: Table = Table::Columns(PEERING_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         self.peering.to_v1()
     }
@@ -132,6 +166,7 @@ impl ToV1 for esc_api::infra::GetPeeringResponse {
 
 impl ToV1 for esc_api::infra::ListPeeringsResponse {
     type V1Type = List<Peering>;
+    const TABLE: Table = Table::Columns(PEERING_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         let v = self.peerings.into_iter().map(|p| p.to_v1()).collect();
         List(v)
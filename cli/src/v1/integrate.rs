@@ -1,6 +1,6 @@
 #![allow(clippy::enum_variant_names)]
 
-use super::common::ToV1;
+use super::common::{List, Table, ToV1};
 use chrono::{DateTime, Utc};
 
 use super::resources::OrgId;
@@ -37,8 +37,81 @@ pub enum IntegrationData {
         #[serde(rename = "source")]
         source: String,
     },
-    #[serde(rename = "not supported: switch to CLI option `--fmt api` to see this data")]
-    Unsupported,
+    #[serde(rename = "pagerDuty")]
+    PagerDutyIntegrationData {
+        /// Auth token used with the PagerDuty API
+        #[serde(rename = "authTokenDisplay")]
+        auth_token_display: String,
+        /// PagerDuty service to raise incidents in
+        #[serde(rename = "service")]
+        service: String,
+        /// PagerDuty user incidents are raised as
+        #[serde(rename = "user")]
+        user: String,
+        /// Source of data for integration
+        #[serde(rename = "source")]
+        source: String,
+    },
+    #[serde(rename = "awsCloudWatchLogs")]
+    AwsCloudWatchLogsIntegrationData {
+        /// AWS access key id
+        #[serde(rename = "accessKeyIdDisplay", skip_serializing_if = "Option::is_none")]
+        access_key_id_display: Option<String>,
+        /// Clusters whose logs are sent
+        #[serde(rename = "clusterIds", skip_serializing_if = "Option::is_none")]
+        cluster_ids: Option<Vec<String>>,
+        /// CloudWatch log group
+        #[serde(rename = "groupName")]
+        group_name: String,
+        /// AWS region
+        #[serde(rename = "region")]
+        region: String,
+        /// AWS secret access key
+        #[serde(
+            rename = "secretAccessKeyDisplay",
+            skip_serializing_if = "Option::is_none"
+        )]
+        secret_access_key_display: Option<String>,
+        /// Source of data for integration
+        #[serde(rename = "source")]
+        source: String,
+    },
+    #[serde(rename = "awsCloudWatchMetrics")]
+    AwsCloudWatchMetricsIntegrationData {
+        /// AWS access key id
+        #[serde(rename = "accessKeyIdDisplay", skip_serializing_if = "Option::is_none")]
+        access_key_id_display: Option<String>,
+        /// Clusters whose metrics are sent
+        #[serde(rename = "clusterIds")]
+        cluster_ids: Vec<String>,
+        /// CloudWatch metrics namespace
+        #[serde(rename = "namespace")]
+        namespace: String,
+        /// AWS region
+        #[serde(rename = "region")]
+        region: String,
+        /// AWS secret access key
+        #[serde(
+            rename = "secretAccessKeyDisplay",
+            skip_serializing_if = "Option::is_none"
+        )]
+        secret_access_key_display: Option<String>,
+        /// Source of data for integration
+        #[serde(rename = "source")]
+        source: String,
+    },
+    #[serde(rename = "gcpLogging")]
+    GcpLoggingIntegrationData {
+        /// GCP project logs are written to
+        #[serde(rename = "gcpProjectId")]
+        gcp_project_id: String,
+        /// Name of the log
+        #[serde(rename = "logId")]
+        log_id: String,
+        /// Source of data for integration
+        #[serde(rename = "source", skip_serializing_if = "Option::is_none")]
+        source: Option<String>,
+    },
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -78,13 +151,50 @@ impl ToV1 for esc_api::integrate::IntegrationData {
                     token_display: data.token_display,
                 }
             }
-            _ => IntegrationData::Unsupported,
+            esc_api::integrate::IntegrationData::PagerDuty(data) => {
+                IntegrationData::PagerDutyIntegrationData {
+                    auth_token_display: data.auth_token_display,
+                    service: data.service,
+                    user: data.user,
+                    source: data.source,
+                }
+            }
+            esc_api::integrate::IntegrationData::AwsCloudWatchLogs(data) => {
+                IntegrationData::AwsCloudWatchLogsIntegrationData {
+                    access_key_id_display: data.access_key_id_display,
+                    cluster_ids: data.cluster_ids,
+                    group_name: data.group_name,
+                    region: data.region,
+                    secret_access_key_display: data.secret_access_key_display,
+                    source: data.source,
+                }
+            }
+            esc_api::integrate::IntegrationData::AwsCloudWatchMetrics(data) => {
+                IntegrationData::AwsCloudWatchMetricsIntegrationData {
+                    access_key_id_display: data.access_key_id_display,
+                    cluster_ids: data.cluster_ids,
+                    namespace: data.namespace,
+                    region: data.region,
+                    secret_access_key_display: data.secret_access_key_display,
+                    source: data.source,
+                }
+            }
+            esc_api::integrate::IntegrationData::GcpLogging(data) => {
+                IntegrationData::GcpLoggingIntegrationData {
+                    gcp_project_id: data.gcp_project_id,
+                    log_id: data.log_id,
+                    source: data.source,
+                }
+            }
         }
     }
 }
 
+const INTEGRATION_COLUMNS: &[&str] = &["id", "description", "status", "created"];
+
 impl ToV1 for esc_api::integrate::Integration {
     type V1Type = Integration;
+    const TABLE: Table = Table::Columns(INTEGRATION_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         Integration {
             created: self.created,
@@ -114,6 +224,7 @@ pub struct GetIntegrationResponse {
 
 impl ToV1 for esc_api::integrate::GetIntegrationResponse {
     type V1Type = GetIntegrationResponse;
+    const TABLE: Table = Table::Columns(INTEGRATION_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         GetIntegrationResponse {
             integration: self.integration.to_v1(),
@@ -129,9 +240,20 @@ pub struct ListIntegrationsResponse {
 
 impl ToV1 for esc_api::integrate::ListIntegrationsResponse {
     type V1Type = ListIntegrationsResponse;
+    const TABLE: Table = Table::Columns(INTEGRATION_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         ListIntegrationsResponse {
             integrations: self.integrations.into_iter().map(|i| i.to_v1()).collect(),
         }
     }
 }
+
+const SOURCE_COLUMNS: &[&str] = &["id", "name", "enabled"];
+
+impl ToV1 for esc_api::integrate::IntegrationsOptionsResponse {
+    type V1Type = List<esc_api::integrate::SourceSummary>;
+    const TABLE: Table = Table::Columns(SOURCE_COLUMNS);
+    fn to_v1(self) -> Self::V1Type {
+        List(self.sources)
+    }
+}
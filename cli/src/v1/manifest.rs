@@ -0,0 +1,134 @@
+use super::common::{Table, ToV1};
+use esc_api::manifest::{Action, Drift, DriftStatus, FieldChange, Manifest, Plan};
+
+fn render_value(value: &Option<serde_json::Value>) -> String {
+    match value {
+        None => "(none)".to_string(),
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(value) => value.to_string(),
+    }
+}
+
+pub fn write_fields(f: &mut std::fmt::Formatter, fields: &[FieldChange]) -> std::fmt::Result {
+    for field in fields {
+        writeln!(
+            f,
+            "      {}: {} -> {}",
+            field.field,
+            render_value(&field.old),
+            render_value(&field.new)
+        )?;
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct PlanOutput(pub Plan);
+
+impl std::fmt::Debug for PlanOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let plan = &self.0;
+        if plan.is_empty() {
+            return write!(f, "No changes, the project matches the manifest.");
+        }
+
+        for change in plan.changes.iter() {
+            let (symbol, suffix) = match change.action {
+                Action::Create => ("  +", ""),
+                Action::Update => ("  ~", ""),
+                Action::Replace => ("-/+", " (replace)"),
+                Action::Delete => ("  -", ""),
+            };
+            writeln!(
+                f,
+                "{} {} \"{}\"{}",
+                symbol, change.kind, change.name, suffix
+            )?;
+            if let Some(reason) = change.reason.as_ref() {
+                writeln!(f, "      because {}", reason)?;
+            }
+            write_fields(f, &change.fields)?;
+        }
+
+        write!(
+            f,
+            "\nPlan: {} to create, {} to update, {} to replace, {} to delete.",
+            plan.count(Action::Create),
+            plan.count(Action::Update),
+            plan.count(Action::Replace),
+            plan.count(Action::Delete)
+        )
+    }
+}
+
+impl ToV1 for Plan {
+    type V1Type = PlanOutput;
+    const TABLE: Table = Table::Text;
+    fn to_v1(self) -> Self::V1Type {
+        PlanOutput(self)
+    }
+}
+
+/// Renders as YAML, so the output of `esc export` can be used as a manifest.
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct ManifestOutput(pub Manifest);
+
+impl std::fmt::Debug for ManifestOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let yaml = serde_yaml::to_string(&self.0).map_err(|_| std::fmt::Error)?;
+        write!(f, "{}", yaml.trim_end())
+    }
+}
+
+impl ToV1 for Manifest {
+    type V1Type = ManifestOutput;
+    const TABLE: Table = Table::Text;
+    fn to_v1(self) -> Self::V1Type {
+        ManifestOutput(self)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct DriftOutput(pub Drift);
+
+impl std::fmt::Debug for DriftOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let drift = &self.0;
+        if drift.is_empty() {
+            return write!(f, "No drift, the project matches the baseline.");
+        }
+
+        for resource in drift.resources.iter() {
+            let (symbol, status) = match resource.status {
+                DriftStatus::Added => ("+", "added"),
+                DriftStatus::Changed => ("~", "changed"),
+                DriftStatus::Removed => ("-", "removed"),
+            };
+            writeln!(
+                f,
+                "  {} {} \"{}\" ({})",
+                symbol, resource.kind, resource.name, status
+            )?;
+            write_fields(f, &resource.fields)?;
+        }
+
+        write!(
+            f,
+            "\nDrift: {} added, {} changed, {} removed.",
+            drift.count(DriftStatus::Added),
+            drift.count(DriftStatus::Changed),
+            drift.count(DriftStatus::Removed)
+        )
+    }
+}
+
+impl ToV1 for Drift {
+    type V1Type = DriftOutput;
+    const TABLE: Table = Table::Text;
+    fn to_v1(self) -> Self::V1Type {
+        DriftOutput(self)
+    }
+}
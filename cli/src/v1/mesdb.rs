@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 
-use super::common::{List, ToV1};
+use super::common::{List, Table, ToV1};
 use super::infra::Provider;
 use super::resources::OrgId;
 
@@ -43,8 +43,21 @@ pub struct EnrichedCluster {
     addresses: ClusterAddresses,
 }
 
+const CLUSTER_COLUMNS: &[&str] = &[
+    "id",
+    "description",
+    "provider",
+    "region",
+    "topology",
+    "instanceType",
+    "diskSizeGb",
+    "serverVersion",
+    "status",
+];
+
 impl ToV1 for esc_api::mesdb::Cluster {
     type V1Type = EnrichedCluster;
+    const TABLE: Table = Table::Columns(CLUSTER_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         let cluster = Cluster {
             created: self.created,
@@ -101,6 +114,7 @@ impl ToV1 for esc_api::mesdb::CreateClusterResponse {
 
 impl ToV1 for esc_api::mesdb::GetClusterResponse {
     type V1Type = EnrichedCluster;
+    const TABLE: Table = Table::Columns(CLUSTER_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         self.cluster.to_v1()
     }
@@ -108,12 +122,34 @@ impl ToV1 for esc_api::mesdb::GetClusterResponse {
 
 impl ToV1 for esc_api::mesdb::ListClustersResponse {
     type V1Type = List<EnrichedCluster>;
+    const TABLE: Table = Table::Columns(CLUSTER_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         let l = self.clusters.into_iter().map(|c| c.to_v1()).collect();
         List(l)
     }
 }
 
+const VERSION_COLUMNS: &[&str] = &["version", "tag", "name", "lts", "recommended"];
+
+const UPGRADE_VERSION_COLUMNS: &[&str] =
+    &["version", "tag", "change", "lts", "recommended", "warnings"];
+
+impl ToV1 for esc_api::mesdb::ListClusterCreateVersionsResponse {
+    type V1Type = List<esc_api::mesdb::ClusterCreateVersion>;
+    const TABLE: Table = Table::Columns(VERSION_COLUMNS);
+    fn to_v1(self) -> Self::V1Type {
+        List(self.versions)
+    }
+}
+
+impl ToV1 for esc_api::mesdb::ListClusterUpgradeVersionsResponse {
+    type V1Type = List<esc_api::mesdb::ClusterUpgradeVersion>;
+    const TABLE: Table = Table::Columns(UPGRADE_VERSION_COLUMNS);
+    fn to_v1(self) -> Self::V1Type {
+        List(self.versions)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Backup {
@@ -130,8 +166,18 @@ pub struct Backup {
     pub linked_resource: Option<String>,
 }
 
+const BACKUP_COLUMNS: &[&str] = &[
+    "id",
+    "description",
+    "sourceClusterDescription",
+    "sizeGb",
+    "status",
+    "created",
+];
+
 impl ToV1 for esc_api::mesdb::Backup {
     type V1Type = Backup;
+    const TABLE: Table = Table::Columns(BACKUP_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         Backup {
             created: self.created.to_rfc3339(),
@@ -158,6 +204,7 @@ impl ToV1 for esc_api::mesdb::CreateBackupResponse {
 
 impl ToV1 for esc_api::mesdb::GetBackupResponse {
     type V1Type = Backup;
+    const TABLE: Table = Table::Columns(BACKUP_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         self.backup.to_v1()
     }
@@ -165,6 +212,7 @@ impl ToV1 for esc_api::mesdb::GetBackupResponse {
 
 impl ToV1 for esc_api::mesdb::ListBackupsResponse {
     type V1Type = List<Backup>;
+    const TABLE: Table = Table::Columns(BACKUP_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         let l = self.backups.into_iter().map(|b| b.to_v1()).collect();
         List(l)
@@ -194,8 +242,19 @@ pub struct SharedCluster {
     pub server_version_tag: String,
 }
 
+const SHARED_CLUSTER_COLUMNS: &[&str] = &[
+    "id",
+    "name",
+    "provider",
+    "region",
+    "deploymentTier",
+    "serverVersionTag",
+    "status",
+];
+
 impl ToV1 for esc_api::mesdb::SharedCluster {
     type V1Type = SharedCluster;
+    const TABLE: Table = Table::Columns(SHARED_CLUSTER_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         SharedCluster {
             created: self.created.to_rfc3339(),
@@ -229,6 +288,7 @@ impl ToV1 for esc_api::mesdb::CreateSharedClusterResponse {
 
 impl ToV1 for esc_api::mesdb::GetSharedClusterResponse {
     type V1Type = SharedCluster;
+    const TABLE: Table = Table::Columns(SHARED_CLUSTER_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         self.cluster.to_v1()
     }
@@ -236,6 +296,7 @@ impl ToV1 for esc_api::mesdb::GetSharedClusterResponse {
 
 impl ToV1 for esc_api::mesdb::ListSharedClustersResponse {
     type V1Type = List<SharedCluster>;
+    const TABLE: Table = Table::Columns(SHARED_CLUSTER_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         let l = self.clusters.into_iter().map(|c| c.to_v1()).collect();
         List(l)
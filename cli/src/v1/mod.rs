@@ -3,8 +3,11 @@ mod audit;
 mod common;
 mod infra;
 mod integrate;
+mod manifest;
 mod mesdb;
 mod orchestrate;
 mod resources;
+mod teardown;
 
-pub use common::ToV1;
+pub use access::PolicyCheck;
+pub use common::{List, Table, ToV1};
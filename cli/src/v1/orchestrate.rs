@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 
-use super::common::{List, ToV1};
+use super::common::{List, StringNoQuotes, Table, ToV1};
 use super::resources::OrgId;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -17,13 +17,6 @@ pub enum JobData {
     ScheduledBackup(JobDataScheduledBackup),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct ScheduleField {
-    pub expr_type: String,
-    pub number: i32,
-}
-
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Job {
@@ -38,8 +31,11 @@ pub struct Job {
     pub data: JobData,
 }
 
+const JOB_COLUMNS: &[&str] = &["id", "description", "schedule", "status"];
+
 impl ToV1 for esc_api::orchestrate::Job {
     type V1Type = Job;
+    const TABLE: Table = Table::Columns(JOB_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         let data = match self.data {
             esc_api::orchestrate::JobData::ScheduledBackup(backup) => {
@@ -71,6 +67,7 @@ impl ToV1 for esc_api::orchestrate::CreateJobResponse {
 
 impl ToV1 for esc_api::orchestrate::GetJobResponse {
     type V1Type = Job;
+    const TABLE: Table = Table::Columns(JOB_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         self.job.to_v1()
     }
@@ -78,6 +75,7 @@ impl ToV1 for esc_api::orchestrate::GetJobResponse {
 
 impl ToV1 for esc_api::orchestrate::ListJobsResponse {
     type V1Type = List<Job>;
+    const TABLE: Table = Table::Columns(JOB_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         List(self.jobs.into_iter().map(|j| j.to_v1()).collect())
     }
@@ -86,6 +84,7 @@ impl ToV1 for esc_api::orchestrate::ListJobsResponse {
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct HistoryItem {
+    pub id: String,
     #[serde(rename = "organizationId")]
     pub org_id: OrgId,
     pub project_id: esc_api::resources::ProjectId,
@@ -97,10 +96,21 @@ pub struct HistoryItem {
     pub end_time: Option<DateTime<Utc>>,
 }
 
+const HISTORY_COLUMNS: &[&str] = &[
+    "id",
+    "jobId",
+    "status",
+    "startTime",
+    "endTime",
+    "linkedResource",
+];
+
 impl ToV1 for esc_api::orchestrate::HistoryItem {
     type V1Type = HistoryItem;
+    const TABLE: Table = Table::Columns(HISTORY_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         HistoryItem {
+            id: self.id,
             details: self.details,
             end_time: self.end_time,
             job_id: self.job_id,
@@ -113,8 +123,16 @@ impl ToV1 for esc_api::orchestrate::HistoryItem {
     }
 }
 
+impl ToV1 for esc_api::orchestrate::RunJobResponse {
+    type V1Type = StringNoQuotes;
+    fn to_v1(self) -> Self::V1Type {
+        StringNoQuotes(self.history_id.unwrap_or_default())
+    }
+}
+
 impl ToV1 for esc_api::orchestrate::GetHistoryResponse {
     type V1Type = List<HistoryItem>;
+    const TABLE: Table = Table::Columns(HISTORY_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         List(self.items.into_iter().map(|i| i.to_v1()).collect())
     }
@@ -2,7 +2,7 @@ use chrono::{DateTime, Utc};
 use esc_api::resources::{MfaStatus, UpdateMfaResponse};
 use std::fmt::Formatter;
 
-use super::common::{List, ToV1};
+use super::common::{List, Table, ToV1};
 
 #[derive(Clone, Eq, PartialEq, Debug, Deserialize, Serialize, Default)]
 pub struct OrgId(pub String);
@@ -28,8 +28,11 @@ pub struct Organization {
     pub created: DateTime<Utc>,
 }
 
+const ORGANIZATION_COLUMNS: &[&str] = &["id", "name", "created"];
+
 impl ToV1 for esc_api::resources::Organization {
     type V1Type = Organization;
+    const TABLE: Table = Table::Columns(ORGANIZATION_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         Organization {
             created: self.created,
@@ -48,6 +51,7 @@ impl ToV1 for esc_api::resources::CreateOrganizationResponse {
 
 impl ToV1 for esc_api::resources::GetOrganizationResponse {
     type V1Type = Organization;
+    const TABLE: Table = Table::Columns(ORGANIZATION_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         self.organization.to_v1()
     }
@@ -55,6 +59,7 @@ impl ToV1 for esc_api::resources::GetOrganizationResponse {
 
 impl ToV1 for esc_api::resources::ListOrganizationsResponse {
     type V1Type = List<Organization>;
+    const TABLE: Table = Table::Columns(ORGANIZATION_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         List(self.organizations.into_iter().map(|o| o.to_v1()).collect())
     }
@@ -88,8 +93,11 @@ pub struct Project {
     pub created: DateTime<Utc>,
 }
 
+const PROJECT_COLUMNS: &[&str] = &["id", "name", "created"];
+
 impl ToV1 for esc_api::resources::Project {
     type V1Type = Project;
+    const TABLE: Table = Table::Columns(PROJECT_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         Project {
             created: self.created,
@@ -109,6 +117,7 @@ impl ToV1 for esc_api::resources::CreateProjectResponse {
 
 impl ToV1 for esc_api::resources::GetProjectResponse {
     type V1Type = Project;
+    const TABLE: Table = Table::Columns(PROJECT_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         self.project.to_v1()
     }
@@ -116,6 +125,7 @@ impl ToV1 for esc_api::resources::GetProjectResponse {
 
 impl ToV1 for esc_api::resources::ListProjectsResponse {
     type V1Type = List<Project>;
+    const TABLE: Table = Table::Columns(PROJECT_COLUMNS);
     fn to_v1(self) -> Self::V1Type {
         List(self.projects.into_iter().map(|p| p.to_v1()).collect())
     }
@@ -0,0 +1,41 @@
+use super::common::{Table, ToV1};
+use esc_api::teardown::{Action, Plan};
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct TeardownPlanOutput(pub Plan);
+
+impl std::fmt::Debug for TeardownPlanOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let plan = &self.0;
+        for step in plan.steps.iter() {
+            let (symbol, suffix) = match (step.action, &step.reason) {
+                (Action::Delete, _) => ("  -", "".to_string()),
+                (Action::Unprotect, _) => ("  -", " (unprotect first)".to_string()),
+                (Action::Wait, _) => ("  -", " (already deleting)".to_string()),
+                (Action::Keep, Some(reason)) => ("  =", format!(" (kept, {})", reason)),
+                (Action::Keep, None) => ("  =", " (kept)".to_string()),
+            };
+            writeln!(
+                f,
+                "{} {} \"{}\" {}{}",
+                symbol, step.kind, step.name, step.id, suffix
+            )?;
+        }
+
+        write!(
+            f,
+            "\nTeardown: {} to delete, {} to keep.",
+            plan.steps.len() - plan.count(Action::Keep),
+            plan.count(Action::Keep)
+        )
+    }
+}
+
+impl ToV1 for Plan {
+    type V1Type = TeardownPlanOutput;
+    const TABLE: Table = Table::Text;
+    fn to_v1(self) -> Self::V1Type {
+        TeardownPlanOutput(self)
+    }
+}
@@ -0,0 +1,106 @@
+// Checks the server versions passed to `clusters create` and `clusters upgrade`
+// against the versions the API offers, so a typo is reported along with the
+// valid choices instead of a failed operation.
+use esc_api::mesdb::{ClusterCreateVersion, ClusterUpgradeVersion};
+use esc_api::{ClusterId, OrgId, ProjectId};
+
+type Result<A> = std::result::Result<A, Box<dyn std::error::Error>>;
+
+fn create_matches(version: &ClusterCreateVersion, requested: &str) -> bool {
+    version.version == requested || version.tag == requested
+}
+
+fn describe_create(version: &ClusterCreateVersion) -> String {
+    let mut line = format!("  {}  (tag {})", version.version, version.tag);
+    if version.lts {
+        line.push_str(" lts");
+    }
+    if version.recommended {
+        line.push_str(" recommended");
+    }
+    line
+}
+
+/// Fails if `server_version` isn't one of the versions new clusters can be
+/// created with. If the versions can't be listed the request is let through
+/// and the API gets the final say.
+pub async fn check_create(
+    client: &esc_api::Client,
+    org_id: &OrgId,
+    server_version: &str,
+) -> Result<()> {
+    let client = crate::utils::behind_the_scenes(client);
+    let versions =
+        match esc_api::mesdb::list_versions_for_cluster_create(&client, org_id.clone()).await {
+            Ok(resp) => resp.versions,
+            Err(err) => {
+                log::debug!("Could not list server versions: {}", err);
+                return Ok(());
+            }
+        };
+
+    if versions.iter().any(|v| create_matches(v, server_version)) {
+        return Ok(());
+    }
+
+    let choices: Vec<String> = versions.iter().map(describe_create).collect();
+    Err(format!(
+        "Server version \"{}\" is not available. Available versions:\n{}",
+        server_version,
+        choices.join("\n")
+    )
+    .into())
+}
+
+/// Fails if the cluster can't be upgraded to `target_tag`. Warnings attached
+/// to the chosen version are written to stderr.
+pub async fn check_upgrade(
+    client: &esc_api::Client,
+    org_id: &OrgId,
+    project_id: &ProjectId,
+    cluster_id: &ClusterId,
+    target_tag: &str,
+) -> Result<()> {
+    let client = crate::utils::behind_the_scenes(client);
+    let versions = match esc_api::mesdb::list_versions_for_cluster_upgrade(
+        &client,
+        org_id.clone(),
+        project_id.clone(),
+        cluster_id.clone(),
+    )
+    .await
+    {
+        Ok(resp) => resp.versions,
+        Err(err) => {
+            log::debug!("Could not list upgrade versions: {}", err);
+            return Ok(());
+        }
+    };
+
+    if let Some(version) = versions.iter().find(|v| v.tag == target_tag) {
+        for warning in version.warnings.iter() {
+            eprintln!("Warning: {}", warning);
+        }
+        return Ok(());
+    }
+
+    if versions.is_empty() {
+        return Err(format!("Cluster {} has no upgrades available", cluster_id).into());
+    }
+
+    let choices: Vec<String> = versions.iter().map(describe_upgrade).collect();
+    Err(format!(
+        "Cluster {} can't be upgraded to \"{}\". Available target tags:\n{}",
+        cluster_id,
+        target_tag,
+        choices.join("\n")
+    )
+    .into())
+}
+
+fn describe_upgrade(version: &ClusterUpgradeVersion) -> String {
+    format!(
+        "  {}  ({} {})",
+        version.tag, version.change, version.version
+    )
+}
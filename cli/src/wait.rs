@@ -0,0 +1,285 @@
+// The `--wait` and `--timeout` flags shared by long-running commands, and the
+// glue which reports the progress of `esc_api::wait` on stderr.
+use crate::constants::{EXIT_CODE_WAIT_FAILED, EXIT_CODE_WAIT_TIMED_OUT};
+use esc_api::wait::{Target, WaitError, WaitOptions, Waitable};
+use std::time::Duration;
+use structopt::StructOpt;
+
+#[derive(StructOpt, Clone, Debug)]
+pub struct WaitArgs {
+    #[structopt(long, help = "Waits until the operation has completed")]
+    pub wait: bool,
+
+    #[structopt(
+        long,
+        parse(try_from_str = humantime::parse_duration),
+        default_value = "30m",
+        help = "How long --wait waits before giving up, for example 90s, 10m or 1h"
+    )]
+    pub timeout: Duration,
+}
+
+impl WaitArgs {
+    pub fn options(&self) -> WaitOptions {
+        WaitOptions::with_timeout(self.timeout)
+    }
+
+    /// Used by `esc wait`, which always waits.
+    pub fn always(timeout: Duration) -> Self {
+        WaitArgs {
+            wait: true,
+            timeout,
+        }
+    }
+}
+
+/// The target for `esc wait --for <status>`, or the ready state of the
+/// resource when no status was given.
+pub fn target<W: Waitable>(status: Option<String>) -> Result<Target, String> {
+    match status {
+        None => Ok(Target::ready::<W>()),
+        Some(status) => {
+            let status = status.to_lowercase();
+            if W::statuses().contains(&status.as_str()) {
+                Ok(Target::statuses(vec![status]))
+            } else {
+                Err(format!(
+                    "Unknown status \"{}\", expected one of: {}",
+                    status,
+                    W::statuses().join(", ")
+                ))
+            }
+        }
+    }
+}
+
+/// Prints the status whenever it changes.
+#[derive(Default)]
+pub struct ProgressReporter {
+    last: Option<String>,
+}
+
+impl ProgressReporter {
+    pub fn new() -> Self {
+        ProgressReporter { last: None }
+    }
+
+    pub fn report(&mut self, kind: &str, id: &str, status: String) {
+        if self.last.as_ref() != Some(&status) {
+            eprintln!("{} {}: {}", kind, id, status);
+            self.last = Some(status);
+        }
+    }
+}
+
+/// Exits with the wait exit codes if the resource failed or the timeout
+/// expired.
+pub fn finish<A>(
+    kind: &str,
+    id: &str,
+    result: Result<A, WaitError>,
+) -> Result<A, Box<dyn std::error::Error>> {
+    match result {
+        Ok(value) => Ok(value),
+        Err(WaitError::Failed { status }) => {
+            eprintln!("{} {} entered failure state \"{}\"", kind, id, status);
+            std::process::exit(EXIT_CODE_WAIT_FAILED);
+        }
+        Err(err @ WaitError::TimedOut { .. }) => {
+            eprintln!("Gave up waiting on {} {}: {}", kind.to_lowercase(), id, err);
+            std::process::exit(EXIT_CODE_WAIT_TIMED_OUT);
+        }
+        Err(WaitError::Api(err)) => Err(Box::new(err)),
+    }
+}
+
+// Dry runs don't change anything, so waiting would never end.
+fn skip(client: &esc_api::Client, args: &WaitArgs) -> bool {
+    !args.wait || client.sender.mode == esc_api::SendMode::DryRun
+}
+
+/// Waits for the cluster to match `target` if `--wait` was passed.
+pub async fn cluster(
+    client: &esc_api::Client,
+    org_id: &esc_api::OrgId,
+    project_id: &esc_api::ProjectId,
+    id: &esc_api::ClusterId,
+    target: Target,
+    args: &WaitArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if skip(client, args) {
+        return Ok(());
+    }
+
+    let client = crate::utils::behind_the_scenes(client);
+    let mut reporter = ProgressReporter::new();
+    let result = esc_api::wait::wait_for_cluster(
+        &client,
+        org_id.clone(),
+        project_id.clone(),
+        id.clone(),
+        &target,
+        &args.options(),
+        |cluster| reporter.report("Cluster", id.as_ref(), cluster.describe()),
+    )
+    .await;
+
+    finish("Cluster", id.as_ref(), result)?;
+    Ok(())
+}
+
+/// Same as `cluster` for networks.
+pub async fn network(
+    client: &esc_api::Client,
+    org_id: &esc_api::OrgId,
+    project_id: &esc_api::ProjectId,
+    id: &esc_api::NetworkId,
+    target: Target,
+    args: &WaitArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if skip(client, args) {
+        return Ok(());
+    }
+
+    let client = crate::utils::behind_the_scenes(client);
+    let mut reporter = ProgressReporter::new();
+    let result = esc_api::wait::wait_for_network(
+        &client,
+        org_id.clone(),
+        project_id.clone(),
+        id.clone(),
+        &target,
+        &args.options(),
+        |network| reporter.report("Network", id.as_ref(), network.describe()),
+    )
+    .await;
+
+    finish("Network", id.as_ref(), result)?;
+    Ok(())
+}
+
+/// Same as `cluster` for peerings.
+pub async fn peering(
+    client: &esc_api::Client,
+    org_id: &esc_api::OrgId,
+    project_id: &esc_api::ProjectId,
+    id: &esc_api::PeeringId,
+    target: Target,
+    args: &WaitArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if skip(client, args) {
+        return Ok(());
+    }
+
+    let client = crate::utils::behind_the_scenes(client);
+    let mut reporter = ProgressReporter::new();
+    let result = esc_api::wait::wait_for_peering(
+        &client,
+        org_id.clone(),
+        project_id.clone(),
+        id.clone(),
+        &target,
+        &args.options(),
+        |peering| reporter.report("Peering", id.as_ref(), peering.describe()),
+    )
+    .await;
+
+    finish("Peering", id.as_ref(), result)?;
+    Ok(())
+}
+
+/// Same as `cluster` for ACLs.
+pub async fn acl(
+    client: &esc_api::Client,
+    org_id: &esc_api::OrgId,
+    project_id: &esc_api::ProjectId,
+    id: &esc_api::infra::AclId,
+    target: Target,
+    args: &WaitArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if skip(client, args) {
+        return Ok(());
+    }
+
+    let client = crate::utils::behind_the_scenes(client);
+    let mut reporter = ProgressReporter::new();
+    let result = esc_api::wait::wait_for_acl(
+        &client,
+        org_id.clone(),
+        project_id.clone(),
+        id.clone(),
+        &target,
+        &args.options(),
+        |acl| reporter.report("Acl", id.as_ref(), acl.describe()),
+    )
+    .await;
+
+    finish("Acl", id.as_ref(), result)?;
+    Ok(())
+}
+
+/// Same as `cluster` for backups.
+pub async fn backup(
+    client: &esc_api::Client,
+    org_id: &esc_api::OrgId,
+    project_id: &esc_api::ProjectId,
+    id: &esc_api::BackupId,
+    target: Target,
+    args: &WaitArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if skip(client, args) {
+        return Ok(());
+    }
+
+    let client = crate::utils::behind_the_scenes(client);
+    let mut reporter = ProgressReporter::new();
+    let result = esc_api::wait::wait_for_backup(
+        &client,
+        org_id.clone(),
+        project_id.clone(),
+        id.clone(),
+        &target,
+        &args.options(),
+        |backup| reporter.report("Backup", id.as_ref(), backup.describe()),
+    )
+    .await;
+
+    finish("Backup", id.as_ref(), result)?;
+    Ok(())
+}
+
+/// Follows a job run until it has ended.
+pub async fn job_run(
+    client: &esc_api::Client,
+    org_id: &esc_api::OrgId,
+    project_id: &esc_api::ProjectId,
+    job_id: &esc_api::JobId,
+    history_id: String,
+    timeout: Duration,
+) -> Result<esc_api::orchestrate::HistoryItem, Box<dyn std::error::Error>> {
+    let client = crate::utils::behind_the_scenes(client);
+    let mut reporter = ProgressReporter::new();
+    let result = esc_api::wait::wait_for_job_run(
+        &client,
+        org_id.clone(),
+        project_id.clone(),
+        job_id.clone(),
+        history_id.clone(),
+        &WaitOptions::with_timeout(timeout),
+        |item| {
+            let status = match item {
+                Some(item) => item.status.clone(),
+                None => "not started".to_string(),
+            };
+            reporter.report("Job run", &history_id, status)
+        },
+    )
+    .await;
+
+    let item = finish("Job run", &history_id, result)?;
+    eprintln!("Job run {} ended: {}", history_id, item.details);
+    if !item.linked_resource.is_empty() {
+        eprintln!("Resulting resource: {}", item.linked_resource);
+    }
+    Ok(item)
+}
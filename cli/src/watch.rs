@@ -0,0 +1,225 @@
+// The `--watch` and `--interval` flags shared by list and get commands, which
+// re-poll a resource and redraw it, or with `--fmt ndjson` report what changed
+// between polls as events.
+use crate::output::OutputFormat;
+use crate::{table, v1, Printer};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::future::Future;
+use std::time::Duration;
+use structopt::StructOpt;
+
+#[derive(StructOpt, Clone, Debug)]
+pub struct WatchArgs {
+    #[structopt(
+        long,
+        help = "Polls again and redraws the output until interrupted. With --fmt ndjson only changes are shown"
+    )]
+    pub watch: bool,
+
+    #[structopt(
+        long,
+        parse(try_from_str = parse_interval),
+        default_value = "5s",
+        help = "How often --watch polls, for example 10s or 1m. At least 1s"
+    )]
+    pub interval: Duration,
+}
+
+fn parse_interval(src: &str) -> Result<Duration, String> {
+    let interval = humantime::parse_duration(src).map_err(|e| e.to_string())?;
+    if interval < Duration::from_secs(1) {
+        return Err("The interval must be at least 1s".to_string());
+    }
+    Ok(interval)
+}
+
+// The items of a response along with their ids, or their position for items
+// which don't have one.
+fn keyed(value: Value) -> Vec<(String, Value)> {
+    table::items(value)
+        .into_iter()
+        .enumerate()
+        .map(|(idx, item)| {
+            let key = match item.get("id") {
+                Some(Value::String(id)) => id.clone(),
+                Some(id) if !id.is_null() => id.to_string(),
+                _ => idx.to_string(),
+            };
+            (key, item)
+        })
+        .collect()
+}
+
+// The events turning the previous poll into the current one. Changed fields
+// are named like `--columns` names them.
+fn changes(previous: &[(String, Value)], current: &[(String, Value)]) -> Vec<Value> {
+    let mut events = Vec::new();
+    for (id, item) in current {
+        match previous.iter().find(|(key, _)| key == id) {
+            None => events.push(json!({ "event": "added", "id": id, "item": item })),
+            Some((_, old)) if old != item => {
+                let old = table::flatten(old.clone());
+                let new = table::flatten(item.clone());
+                let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+                keys.sort();
+                keys.dedup();
+                let fields: Vec<Value> = keys
+                    .into_iter()
+                    .filter(|key| old.get(*key) != new.get(*key))
+                    .map(|key| json!({ "field": key, "old": old.get(key), "new": new.get(key) }))
+                    .collect();
+                events
+                    .push(json!({ "event": "changed", "id": id, "fields": fields, "item": item }));
+            }
+            Some(_) => {}
+        }
+    }
+    for (id, item) in previous {
+        if !current.iter().any(|(key, _)| key == id) {
+            events.push(json!({ "event": "removed", "id": id, "item": item }));
+        }
+    }
+    events
+}
+
+/// Fetches a resource and prints it. With `--watch` this is repeated every
+/// interval until interrupted, the terminal being cleared in between. In
+/// NDJSON the first poll reports every item as added, and later polls only
+/// what was added, removed or changed since. A failed poll is reported and
+/// retried at the next interval rather than ending the watch.
+pub async fn run<A, E, F, Fut>(
+    printer: &Printer,
+    args: &WatchArgs,
+    mut fetch: F,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    A: std::fmt::Debug + Serialize + DeserializeOwned + v1::ToV1,
+    E: std::error::Error + 'static,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<A, E>>,
+{
+    if !args.watch {
+        return printer.print(fetch().await?);
+    }
+
+    if let OutputFormat::Ndjson = printer.format {
+        let mut previous = Vec::new();
+        loop {
+            match fetch().await {
+                Ok(resp) => {
+                    let current = keyed(printer.shaped(&resp)?);
+                    for event in changes(&previous, &current) {
+                        println!("{}", serde_json::to_string(&event)?);
+                    }
+                    previous = current;
+                }
+                Err(err) => poll_failed(args, &err),
+            }
+            tokio::time::sleep(args.interval).await;
+        }
+    }
+
+    // Output sent to a pipe or a file, or traffic shown by the api formats,
+    // is appended to rather than redrawn.
+    let redraw = terminal_size::terminal_size().is_some()
+        && (!printer.format.shows_traffic() || printer.replaces_traffic());
+    loop {
+        let resp = match fetch().await {
+            Ok(resp) => resp,
+            Err(err) => {
+                poll_failed(args, &err);
+                tokio::time::sleep(args.interval).await;
+                continue;
+            }
+        };
+        if redraw {
+            print!("\x1B[2J\x1B[H");
+            println!(
+                "Every {}, last polled at {}\n",
+                humantime::format_duration(args.interval),
+                chrono::Local::now().format("%H:%M:%S")
+            );
+        }
+        printer.print(resp)?;
+        tokio::time::sleep(args.interval).await;
+    }
+}
+
+fn poll_failed(args: &WatchArgs, err: &dyn std::error::Error) {
+    eprintln!(
+        "Polling failed at {}, trying again in {}: {}",
+        chrono::Local::now().format("%H:%M:%S"),
+        humantime::format_duration(args.interval),
+        err
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interval_is_at_least_a_second() {
+        assert_eq!(parse_interval("1s"), Ok(Duration::from_secs(1)));
+        assert_eq!(parse_interval("1m"), Ok(Duration::from_secs(60)));
+        assert!(parse_interval("500ms").is_err());
+        assert!(parse_interval("0s").is_err());
+        assert!(parse_interval("soon").is_err());
+    }
+
+    #[test]
+    fn test_items_are_keyed_by_id_or_position() {
+        let items = keyed(json!({"clusters": [{"id": "a"}, {"id": 7}, {"name": "c"}]}));
+        let keys: Vec<&str> = items.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["a", "7", "2"]);
+    }
+
+    #[test]
+    fn test_changes() {
+        let previous = keyed(json!([
+            {"id": "a", "status": "provisioning", "size": {"disk": 8}},
+            {"id": "b", "status": "available"},
+            {"id": "c", "status": "available"},
+        ]));
+        let current = keyed(json!([
+            {"id": "a", "status": "available", "size": {"disk": 16}},
+            {"id": "b", "status": "available"},
+            {"id": "d", "status": "provisioning"},
+        ]));
+        assert_eq!(
+            changes(&previous, &current),
+            vec![
+                json!({
+                    "event": "changed",
+                    "id": "a",
+                    "fields": [
+                        {"field": "size.disk", "old": 8, "new": 16},
+                        {"field": "status", "old": "provisioning", "new": "available"},
+                    ],
+                    "item": {"id": "a", "status": "available", "size": {"disk": 16}},
+                }),
+                json!({
+                    "event": "added",
+                    "id": "d",
+                    "item": {"id": "d", "status": "provisioning"},
+                }),
+                json!({
+                    "event": "removed",
+                    "id": "c",
+                    "item": {"id": "c", "status": "available"},
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_first_poll_adds_everything() {
+        let current = keyed(json!([{"id": "a"}, {"id": "b"}]));
+        let events = changes(&[], &current);
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|event| event["event"] == "added"));
+        assert!(changes(&current, &current).is_empty());
+    }
+}
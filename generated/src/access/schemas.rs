@@ -234,8 +234,8 @@ pub struct Policy {
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PolicyAllowedRequest {
-    pub action: String,
-    pub resource: Action,
+    pub action: Action,
+    pub resource: String,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]